@@ -0,0 +1,525 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use lsp_types::{DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString, Url};
+use til_parser::{
+    diagnostic::{Diagnostic, Severity},
+    eval::eval_import::{eval_import_stat, import_stat::ImportStatement},
+    lint::lint_files,
+    namespace::{Decl, Namespace as ParsedNamespace, Statement},
+    query::{files_to_project_with_diagnostics, parse_file, SourceFile},
+    Span, Spanned,
+};
+use til_query::ir::{
+    db::Database,
+    implementation::{Implementation, ImplementationKind},
+    interface_port::InterfacePort,
+    project::{namespace::Namespace, Project},
+    traits::GetSelf,
+    Ir,
+};
+use tydi_common::name::{Name, PathName, PathNameSelf};
+
+use crate::line_index::LineIndex;
+
+/// What an identifier refers to.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Target {
+    Namespace(PathName),
+    /// A declaration, by its namespace and name.
+    Declaration(PathName, Name),
+}
+
+/// A file of an analysis.
+#[derive(Debug, Clone)]
+pub struct AnalysisFile {
+    uri: Url,
+    source: SourceFile,
+    line_index: LineIndex,
+    /// The namespaces parsed from the file, which may be incomplete if the
+    /// file has parsing errors.
+    namespaces: Vec<ParsedNamespace>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl AnalysisFile {
+    pub fn uri(&self) -> &Url {
+        &self.uri
+    }
+
+    pub fn src(&self) -> &str {
+        self.source.src()
+    }
+
+    pub fn line_index(&self) -> &LineIndex {
+        &self.line_index
+    }
+
+    pub fn namespaces(&self) -> &[ParsedNamespace] {
+        self.namespaces.as_ref()
+    }
+
+    /// The diagnostics of the file, for publishing to the client.
+    pub fn lsp_diagnostics(&self) -> Vec<lsp_types::Diagnostic> {
+        self.diagnostics
+            .iter()
+            .map(|diagnostic| self.lsp_diagnostic(diagnostic))
+            .collect()
+    }
+
+    fn lsp_diagnostic(&self, diagnostic: &Diagnostic) -> lsp_types::Diagnostic {
+        let severity = match diagnostic.severity() {
+            Severity::Error => DiagnosticSeverity::ERROR,
+            Severity::Warning => DiagnosticSeverity::WARNING,
+            Severity::Note => DiagnosticSeverity::INFORMATION,
+        };
+        let mut message = diagnostic.message().to_string();
+        for note in diagnostic.notes() {
+            message.push('\n');
+            message.push_str(note);
+        }
+        // The primary label usually repeats the message, secondary labels
+        // point at related parts of the file.
+        let related = diagnostic
+            .labels()
+            .iter()
+            .filter(|label| {
+                label.span() != diagnostic.span() || label.message() != diagnostic.message()
+            })
+            .map(|label| DiagnosticRelatedInformation {
+                location: Location::new(self.uri.clone(), self.line_index.range(label.span())),
+                message: label.message().to_string(),
+            })
+            .collect::<Vec<_>>();
+        lsp_types::Diagnostic {
+            range: self.line_index.range(diagnostic.span()),
+            severity: Some(severity),
+            code: lint_code(diagnostic).map(NumberOrString::String),
+            source: Some("til".to_string()),
+            message,
+            related_information: if related.is_empty() {
+                None
+            } else {
+                Some(related)
+            },
+            ..Default::default()
+        }
+    }
+}
+
+/// The lint which produced a diagnostic (if any), as noted by
+/// [`lint_files`].
+fn lint_code(diagnostic: &Diagnostic) -> Option<String> {
+    diagnostic.notes().iter().find_map(|note| {
+        note.strip_prefix("Lint level: ")
+            .and_then(|level| level.split(' ').next())
+            .map(str::to_string)
+    })
+}
+
+/// The result of evaluating a set of files which make up a project, or a
+/// single file.
+///
+/// The query database is kept between updates, so declarations imported from
+/// the dependencies of the project are only loaded once.
+pub struct Analysis {
+    /// The project without any namespaces of the files, every update starts
+    /// from this project.
+    base: Project,
+    db: Database,
+    files: Vec<AnalysisFile>,
+    /// The ports of the streamlet instances of structural implementations, by
+    /// the namespace and name of the implementation (or streamlet) and the
+    /// name of the instance.
+    ///
+    /// These are kept from the last time every namespace was evaluated, so
+    /// ports can still be completed while a file can not be evaluated (e.g.,
+    /// while typing `instance.`).
+    instance_ports: HashMap<(PathName, Name), BTreeMap<Name, Vec<InterfacePort>>>,
+}
+
+impl Analysis {
+    /// Create an analysis of a project, which has no files until the first
+    /// [`Analysis::update`]. The declarations of the project (e.g. those of
+    /// its dependencies) must be in `db`.
+    pub fn new(base: Project, mut db: Database) -> Self {
+        db.set_project(base.clone());
+        Analysis {
+            base,
+            db,
+            files: vec![],
+            instance_ports: HashMap::new(),
+        }
+    }
+
+    /// Evaluate the files of the analysis again.
+    pub fn update(&mut self, files: Vec<(Url, SourceFile)>) {
+        self.db.set_project(self.base.clone());
+        let (uris, sources): (Vec<_>, Vec<_>) = files.into_iter().unzip();
+        let results = files_to_project_with_diagnostics(&sources, &mut self.db);
+        let lints = lint_files(&sources, &self.db)
+            .unwrap_or_else(|_| sources.iter().map(|_| vec![]).collect());
+        self.files = uris
+            .into_iter()
+            .zip(sources)
+            .zip(results.into_iter().zip(lints))
+            .map(|((uri, source), ((result, mut diagnostics), lints))| {
+                // Errors which aren't attributed to a part of the file (e.g.,
+                // when an imported namespace could not be evaluated)
+                if let (Err(err), true) = (&result, diagnostics.is_empty()) {
+                    diagnostics.push(Diagnostic::error(err.to_string(), 0..0));
+                }
+                diagnostics.extend(lints);
+                let (namespaces, _, _) = parse_file(source.src());
+                AnalysisFile {
+                    uri,
+                    line_index: LineIndex::new(source.src()),
+                    source,
+                    namespaces: namespaces.unwrap_or_default(),
+                    diagnostics,
+                }
+            })
+            .collect();
+        self.update_instance_ports();
+    }
+
+    pub fn db(&self) -> &Database {
+        &self.db
+    }
+
+    pub fn files(&self) -> &[AnalysisFile] {
+        self.files.as_ref()
+    }
+
+    pub fn file(&self, uri: &Url) -> Option<&AnalysisFile> {
+        self.files.iter().find(|file| file.uri() == uri)
+    }
+
+    /// The evaluated namespace with the given path, which may also be a
+    /// namespace of a dependency.
+    pub fn namespace(&self, path: &PathName) -> Option<Namespace> {
+        let project = self.db.project();
+        project
+            .namespaces()
+            .get(path)
+            .copied()
+            .or_else(|| project.imported_namespace(path))
+            .map(|id| id.get(&self.db))
+    }
+
+    /// The parsed namespace with the given path, and the index of the file it
+    /// was declared in.
+    fn parsed_namespace(&self, path: &PathName) -> Option<(usize, &ParsedNamespace)> {
+        self.files.iter().enumerate().find_map(|(idx, file)| {
+            file.namespaces()
+                .iter()
+                .find(|namespace| PathName::try_new(namespace.name()).as_ref() == Ok(path))
+                .map(|namespace| (idx, namespace))
+        })
+    }
+
+    fn namespace_exists(&self, path: &PathName) -> bool {
+        self.parsed_namespace(path).is_some() || self.namespace(path).is_some()
+    }
+
+    /// The names of the declarations in a namespace, whether they were parsed
+    /// or evaluated.
+    fn declarations(&self, path: &PathName) -> BTreeSet<Name> {
+        let mut result = BTreeSet::new();
+        if let Some((_, parsed)) = self.parsed_namespace(path) {
+            result.extend(parsed_declarations(parsed).map(|(name, _)| name));
+        }
+        if let Some(namespace) = self.namespace(path) {
+            result.extend(namespace.type_decls().keys().cloned());
+            result.extend(namespace.interface_ids().keys().cloned());
+            result.extend(namespace.implementation_ids().keys().cloned());
+            result.extend(namespace.streamlet_ids().keys().cloned());
+            result.extend(namespace.constants().keys().cloned());
+        }
+        result
+    }
+
+    /// The parsed declaration of a target, if it was declared in one of the
+    /// files.
+    pub fn parsed_declaration(&self, namespace: &PathName, name: &Name) -> Option<&Decl> {
+        let (_, parsed) = self.parsed_namespace(namespace)?;
+        parsed_declarations(parsed)
+            .find(|(decl_name, _)| decl_name == name)
+            .map(|(_, decl)| decl)
+    }
+
+    /// What the identifier (path) `path` refers to within the namespace
+    /// `namespace`, following the namespace's imports.
+    ///
+    /// Returns more than one target if the path is ambiguous.
+    pub fn resolve(&self, namespace: &PathName, path: &[String]) -> Vec<Target> {
+        let path = match PathName::try_new(path) {
+            Ok(path) if !path.is_empty() => path,
+            _ => return vec![],
+        };
+        let mut result = BTreeSet::new();
+        if let Some((_, parsed)) = self.parsed_namespace(namespace) {
+            if path.len() == 1 {
+                let name = path.last().unwrap();
+                if parsed_declarations(parsed).any(|(decl, _)| &decl == name) {
+                    return vec![Target::Declaration(namespace.clone(), name.clone())];
+                }
+            }
+            for stat in parsed.stats() {
+                if let (Statement::Import(import), span) = stat {
+                    if let Ok((import, _)) = eval_import_stat((import, span)) {
+                        let import = self.import_statement(import);
+                        for name in self.declarations(import.path_name()) {
+                            if let Ok(Some(reference)) = import.reference(&name) {
+                                if reference == path {
+                                    result.insert(Target::Declaration(
+                                        import.path_name().clone(),
+                                        name,
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        if result.is_empty() {
+            if self.namespace_exists(&path) {
+                result.insert(Target::Namespace(path));
+            } else if path.len() > 1
+                && self
+                    .declarations(&path.root())
+                    .contains(path.last().unwrap())
+            {
+                // A declaration referred to by its full name, e.g. in an
+                // import statement.
+                result.insert(Target::Declaration(
+                    path.root(),
+                    path.last().unwrap().clone(),
+                ));
+            }
+        }
+        result.into_iter().collect()
+    }
+
+    /// A path which does not refer to a namespace may refer to a declaration
+    /// in a namespace, as in
+    /// [`build_dependency_graph`](til_parser::eval::eval_import::build_dependency_graph).
+    fn import_statement(&self, import: ImportStatement) -> ImportStatement {
+        match import {
+            ImportStatement::Full(path)
+                if path.len() > 1
+                    && !self.namespace_exists(&path)
+                    && self.namespace_exists(&path.root()) =>
+            {
+                let decl = path.last().unwrap().clone();
+                ImportStatement::Single(path.root(), decl)
+            }
+            import => import,
+        }
+    }
+
+    /// Where a target was declared, if it was declared in one of the files.
+    pub fn location(&self, target: &Target) -> Option<Location> {
+        let (namespace, name) = match target {
+            Target::Namespace(namespace) => (namespace, None),
+            Target::Declaration(namespace, name) => (namespace, Some(name)),
+        };
+        let (idx, parsed) = self.parsed_namespace(namespace)?;
+        let span = match name {
+            Some(name) => name_span(
+                parsed_declarations(parsed)
+                    .find(|(decl_name, _)| decl_name == name)?
+                    .1
+                    .name(),
+            ),
+            None => namespace_name_span(parsed),
+        };
+        let file = &self.files[idx];
+        Some(Location::new(
+            file.uri().clone(),
+            file.line_index().range(&span),
+        ))
+    }
+
+    /// The ports of the instances of the structural implementation of
+    /// `declaration` (an implementation or streamlet) in `namespace`.
+    pub fn instance_ports(
+        &self,
+        namespace: &PathName,
+        declaration: &Name,
+    ) -> Option<&BTreeMap<Name, Vec<InterfacePort>>> {
+        self.instance_ports
+            .get(&(namespace.clone(), declaration.clone()))
+    }
+
+    fn update_instance_ports(&mut self) {
+        let project = self.db.project();
+        let db = &self.db;
+        for path in self
+            .files
+            .iter()
+            .flat_map(|file| file.namespaces())
+            .filter_map(|namespace| PathName::try_new(namespace.name()).ok())
+        {
+            let namespace = match project.namespaces().get(&path) {
+                Some(id) => id.get(db),
+                None => continue,
+            };
+            self.instance_ports
+                .retain(|(namespace, _), _| namespace != &path);
+            let implementations = namespace
+                .implementations(db)
+                .into_iter()
+                .map(|(name, implementation)| (name, Some(implementation)))
+                .chain(
+                    namespace
+                        .streamlets(db)
+                        .into_iter()
+                        .map(|(name, streamlet)| (name, streamlet.implementation(db))),
+                );
+            for (name, implementation) in implementations {
+                if let Some(ports) = implementation.as_ref().and_then(instance_ports) {
+                    self.instance_ports.insert((path.clone(), name), ports);
+                }
+            }
+        }
+    }
+}
+
+fn instance_ports(implementation: &Implementation) -> Option<BTreeMap<Name, Vec<InterfacePort>>> {
+    match implementation.kind() {
+        ImplementationKind::Structural(structure) => Some(
+            structure
+                .streamlet_instances()
+                .iter()
+                .map(|(name, instance)| {
+                    (name.clone(), instance.ports().values().cloned().collect())
+                })
+                .collect(),
+        ),
+        ImplementationKind::Link(_) => None,
+    }
+}
+
+/// The span of a parsed name, which (unlike the spans of tokens) may include
+/// trailing whitespace.
+pub fn name_span((name, span): &Spanned<String>) -> Span {
+    span.start..span.start + name.chars().count()
+}
+
+pub fn namespace_name_span(namespace: &ParsedNamespace) -> Span {
+    let span = namespace.name_span();
+    span.start..(span.start + namespace.name().join("::").chars().count()).min(span.end)
+}
+
+/// The declarations of a parsed namespace with a valid name.
+pub fn parsed_declarations(namespace: &ParsedNamespace) -> impl Iterator<Item = (Name, &Decl)> {
+    namespace.stats().iter().filter_map(|(stat, _)| match stat {
+        Statement::Decl(decl) => Name::try_new(&decl.name().0).ok().map(|name| (name, decl)),
+        Statement::Import(_) => None,
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// An analysis of a single file, `a.til`.
+    pub(crate) fn analysis(src: &str) -> (Analysis, Url) {
+        analysis_of(&[("a.til", src)])
+    }
+
+    pub(crate) fn analysis_of(files: &[(&str, &str)]) -> (Analysis, Url) {
+        let mut analysis = Analysis::new(
+            Project::new("proj", ".", None::<&str>).unwrap(),
+            Database::default(),
+        );
+        let files = files
+            .iter()
+            .map(|(path, src)| {
+                (
+                    Url::parse(&format!("file:///proj/{}", path)).unwrap(),
+                    SourceFile::new(*src, ".").unwrap().with_path(path),
+                )
+            })
+            .collect::<Vec<_>>();
+        let uri = files[0].0.clone();
+        analysis.update(files);
+        (analysis, uri)
+    }
+
+    #[test]
+    fn diagnostics() {
+        let (analysis, uri) = analysis_of(&[
+            (
+                "a.til",
+                "namespace a {\n    type t = Bits(0);\n}\nnamespace z { type unused = Bits(1); }",
+            ),
+            ("b.til", "namespace b { import a::t; type u = Bits(1); }"),
+        ]);
+        let diagnostics = analysis.file(&uri).unwrap().lsp_diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(
+            diagnostics[0].range,
+            lsp_types::Range::new(
+                lsp_types::Position::new(1, 18),
+                lsp_types::Position::new(1, 19)
+            )
+        );
+        // Lints are reported as warnings
+        assert_eq!(diagnostics[1].severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(
+            diagnostics[1].code,
+            Some(NumberOrString::String("unused-declaration".to_string()))
+        );
+
+        // The importing file can not be evaluated
+        let diagnostics = analysis.files()[1].lsp_diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("could not be evaluated"));
+    }
+
+    #[test]
+    fn resolve() {
+        let (analysis, _) = analysis_of(&[
+            (
+                "a.til",
+                "namespace a::b { type byte = Bits(8); const width: natural = 8; }",
+            ),
+            (
+                "c.til",
+                "namespace c { import a::b; import a::b::byte; import a::b as ab; import a::b prefixed b_; type word = byte; }",
+            ),
+        ]);
+        let c = PathName::try_new(["c"]).unwrap();
+        let ab = PathName::try_new(["a", "b"]).unwrap();
+        let byte = Target::Declaration(ab.clone(), Name::try_new("byte").unwrap());
+        let resolve = |path: &[&str]| {
+            analysis.resolve(&c, &path.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+        };
+        assert_eq!(resolve(&["byte"]), vec![byte.clone()]);
+        assert_eq!(resolve(&["a", "b", "byte"]), vec![byte.clone()]);
+        assert_eq!(resolve(&["ab", "byte"]), vec![byte.clone()]);
+        assert_eq!(resolve(&["b_byte"]), vec![byte.clone()]);
+        assert_eq!(
+            resolve(&["word"]),
+            vec![Target::Declaration(
+                c.clone(),
+                Name::try_new("word").unwrap()
+            )]
+        );
+        assert_eq!(resolve(&["a", "b"]), vec![Target::Namespace(ab.clone())]);
+        assert_eq!(resolve(&["nothing"]), vec![]);
+
+        let location = analysis.location(&byte).unwrap();
+        assert_eq!(location.uri.path(), "/proj/a.til");
+        assert_eq!(
+            location.range,
+            lsp_types::Range::new(
+                lsp_types::Position::new(0, 22),
+                lsp_types::Position::new(0, 26)
+            )
+        );
+    }
+}
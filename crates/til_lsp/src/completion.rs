@@ -0,0 +1,104 @@
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionResponse, Documentation, MarkupContent,
+    MarkupKind, Position, Url,
+};
+use tydi_common::{
+    name::{Name, NameSelf},
+    traits::Document,
+};
+
+use crate::{
+    analysis::Analysis,
+    context::{selecting_instance, Tokens},
+    describe::port,
+};
+
+/// Complete the names of the ports of an instance (`instance.`), within a
+/// structural implementation.
+pub fn completion(
+    analysis: &Analysis,
+    uri: &Url,
+    position: Position,
+) -> Option<CompletionResponse> {
+    let file = analysis.file(uri)?;
+    let offset = file.line_index().offset(position);
+    let instance = Name::try_new(selecting_instance(file.src(), offset)?).ok()?;
+    let scope = Tokens::new(file.src()).scope(offset);
+    let ports = analysis
+        .instance_ports(&scope.namespace?, &scope.declaration?)?
+        .get(&instance)?;
+    let db = analysis.db();
+    let items = ports
+        .iter()
+        .map(|instance_port| CompletionItem {
+            label: instance_port.name().to_string(),
+            kind: Some(CompletionItemKind::FIELD),
+            detail: Some(
+                port(db, instance_port)
+                    .trim_start_matches(&format!("{}: ", instance_port.name()))
+                    .to_string(),
+            ),
+            documentation: instance_port.doc().map(|doc| {
+                Documentation::MarkupContent(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: doc.trim().to_string(),
+                })
+            }),
+            ..Default::default()
+        })
+        .collect();
+    Some(CompletionResponse::Array(items))
+}
+
+#[cfg(test)]
+mod tests {
+    use til_parser::query::SourceFile;
+
+    use crate::analysis::tests::analysis;
+
+    use super::*;
+
+    #[test]
+    fn instance_ports() {
+        let src = "namespace a {\n    type bs = Stream(data: Bits(8), dimensionality: 0, synchronicity: Sync, complexity: 4);\n    streamlet s = (\n        /// The input\n        x: in bs,\n        y: out bs,\n    );\n    impl i = s {\n        inst = s;\n        x -- inst.x;\n        inst.y -- y;\n    };\n}";
+        let (mut analysis, uri) = analysis(src);
+        let labels =
+            |analysis: &Analysis, position: Position| match completion(analysis, &uri, position) {
+                Some(CompletionResponse::Array(items)) => {
+                    items.into_iter().map(|item| item.label).collect::<Vec<_>>()
+                }
+                _ => vec![],
+            };
+        assert_eq!(labels(&analysis, Position::new(9, 18)), vec!["x", "y"]);
+        assert_eq!(
+            labels(&analysis, Position::new(9, 13)),
+            Vec::<String>::new()
+        );
+
+        let items = match completion(&analysis, &uri, Position::new(9, 18)) {
+            Some(CompletionResponse::Array(items)) => items,
+            _ => unreachable!(),
+        };
+        assert_eq!(items[0].kind, Some(CompletionItemKind::FIELD));
+        assert!(items[0]
+            .detail
+            .as_ref()
+            .unwrap()
+            .starts_with("in Stream(data: Bits(8),"));
+        assert_eq!(
+            items[0].documentation,
+            Some(Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: "The input".to_string(),
+            }))
+        );
+
+        // Ports are still completed while the file can not be evaluated
+        let src = src.replace("inst.y -- y;", "inst.");
+        analysis.update(vec![(
+            uri.clone(),
+            SourceFile::new(src, ".").unwrap().with_path("a.til"),
+        )]);
+        assert_eq!(labels(&analysis, Position::new(10, 13)), vec!["x", "y"]);
+    }
+}
@@ -0,0 +1,206 @@
+use chumsky::Parser;
+use til_parser::{
+    lex::{lexer, DeclKeyword, Operator, Token},
+    Span, Spanned,
+};
+use tydi_common::name::{Name, PathName};
+
+/// The tokens of a file, used to determine what a position in the file
+/// refers to.
+///
+/// Unlike the parsed namespaces, tokens are available for (nearly) all files,
+/// including those which are being edited and can not be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tokens {
+    tokens: Vec<Spanned<Token>>,
+}
+
+/// The namespace and declaration surrounding a position.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Scope {
+    pub namespace: Option<PathName>,
+    pub declaration: Option<Name>,
+}
+
+impl Tokens {
+    pub fn new(src: &str) -> Self {
+        let (tokens, _) = lexer().parse_recovery(src);
+        // The spans of tokens include the whitespace and comments following
+        // them, identifiers are limited to the identifier itself.
+        let tokens = tokens
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(token, span)| match &token {
+                Token::Identifier(name) => {
+                    let end = span.start + name.chars().count();
+                    (token, span.start..end)
+                }
+                _ => (token, span),
+            })
+            .collect();
+        Tokens { tokens }
+    }
+
+    /// The index of the identifier at (or directly before) `offset`.
+    pub fn identifier_at(&self, offset: usize) -> Option<usize> {
+        self.tokens.iter().position(|(token, span)| {
+            matches!(token, Token::Identifier(_)) && span.start <= offset && offset <= span.end
+        })
+    }
+
+    pub fn span(&self, idx: usize) -> &Span {
+        &self.tokens[idx].1
+    }
+
+    pub fn identifier(&self, idx: usize) -> Option<&String> {
+        match self.tokens.get(idx) {
+            Some((Token::Identifier(name), _)) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn is_op(&self, idx: usize, op: Operator) -> bool {
+        matches!(self.tokens.get(idx), Some((Token::Op(o), _)) if *o == op)
+    }
+
+    /// The path of the identifier at index `idx`, up to and including the
+    /// identifier itself (e.g., `a::b` for `b` in `a::b::c`).
+    pub fn path(&self, idx: usize) -> Vec<String> {
+        let mut start = idx;
+        while start >= 2 && self.is_op(start - 1, Operator::Path) {
+            if self.identifier(start - 2).is_none() {
+                break;
+            }
+            start -= 2;
+        }
+        (start..=idx)
+            .step_by(2)
+            .filter_map(|idx| self.identifier(idx).cloned())
+            .collect()
+    }
+
+    /// The instance a port is selected from, if the identifier at index `idx`
+    /// is a port of an instance (`instance.port`).
+    pub fn selected_instance(&self, idx: usize) -> Option<&String> {
+        if idx >= 2 && self.is_op(idx - 1, Operator::Select) {
+            self.identifier(idx - 2)
+        } else {
+            None
+        }
+    }
+
+    /// The namespace and declaration `offset` is in.
+    pub fn scope(&self, offset: usize) -> Scope {
+        // The namespaces surrounding the offset, with the depth of their bodies
+        let mut namespaces: Vec<(PathName, usize)> = vec![];
+        let mut declaration = None;
+        let mut depth = 0;
+        let mut idx = 0;
+        while idx < self.tokens.len() && self.tokens[idx].1.start < offset {
+            match &self.tokens[idx].0 {
+                Token::Decl(DeclKeyword::Namespace) => {
+                    let mut path = vec![];
+                    while let Some(name) = self.identifier(idx + 1) {
+                        path.push(name.clone());
+                        idx += if self.is_op(idx + 2, Operator::Path) {
+                            2
+                        } else {
+                            1
+                        };
+                    }
+                    if let Ok(path) = PathName::try_new(path) {
+                        namespaces.push((path, depth + 1));
+                        declaration = None;
+                    }
+                }
+                Token::Decl(_) if namespaces.last().map(|(_, d)| *d) == Some(depth) => {
+                    declaration = self.identifier(idx + 1).and_then(|n| Name::try_new(n).ok());
+                }
+                Token::Ctrl('{') => depth += 1,
+                Token::Ctrl('}') => {
+                    depth = depth.saturating_sub(1);
+                    if namespaces.last().map(|(_, d)| *d > depth) == Some(true) {
+                        namespaces.pop();
+                        declaration = None;
+                    }
+                }
+                _ => (),
+            }
+            idx += 1;
+        }
+        Scope {
+            namespace: namespaces.pop().map(|(path, _)| path),
+            declaration,
+        }
+    }
+}
+
+/// The name of the instance a port is being selected from at `offset`, for
+/// completion (e.g. `a` for `a.` and `a.p`).
+pub fn selecting_instance(src: &str, offset: usize) -> Option<String> {
+    let chars = src.chars().take(offset).collect::<Vec<_>>();
+    let is_ident = |c: &char| c.is_alphanumeric() || *c == '_';
+    let mut end = chars.len();
+    while end > 0 && is_ident(&chars[end - 1]) {
+        end -= 1;
+    }
+    if end == 0 || chars[end - 1] != '.' {
+        return None;
+    }
+    let end = end - 1;
+    let mut start = end;
+    while start > 0 && is_ident(&chars[start - 1]) {
+        start -= 1;
+    }
+    if start == end {
+        None
+    } else {
+        Some(chars[start..end].iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scopes() {
+        let src = "namespace a::b {\n    type t = Bits(8);\n    streamlet s = (x: in Stream(data: t)) { impl: { c = other; } };\n}\nnamespace c { impl i = s { }; }";
+        let tokens = Tokens::new(src);
+        let offset = |pat: &str| src.find(pat).unwrap();
+
+        let scope = tokens.scope(offset("Bits"));
+        assert_eq!(
+            scope.namespace,
+            Some(PathName::try_new(["a", "b"]).unwrap())
+        );
+        assert_eq!(scope.declaration, Some(Name::try_new("t").unwrap()));
+        // The impl keyword of the streamlet's implementation is not a declaration
+        let scope = tokens.scope(offset("other"));
+        assert_eq!(scope.declaration, Some(Name::try_new("s").unwrap()));
+        let scope = tokens.scope(offset("s { }") + 3);
+        assert_eq!(scope.namespace, Some(PathName::try_new(["c"]).unwrap()));
+        assert_eq!(scope.declaration, Some(Name::try_new("i").unwrap()));
+        assert_eq!(tokens.scope(0), Scope::default());
+
+        let idx = tokens.identifier_at(offset("b {")).unwrap();
+        assert_eq!(tokens.path(idx), vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(tokens.span(idx), &(offset("b {")..offset("b {") + 1));
+        let idx = tokens.identifier_at(offset("data: t") + 7).unwrap();
+        assert_eq!(tokens.path(idx), vec!["t".to_string()]);
+        assert_eq!(
+            tokens.span(idx),
+            &(offset("data: t") + 6..offset("data: t") + 7)
+        );
+
+        assert_eq!(
+            selecting_instance("a -- inst.", 10),
+            Some("inst".to_string())
+        );
+        assert_eq!(
+            selecting_instance("a -- inst.po", 12),
+            Some("inst".to_string())
+        );
+        assert_eq!(selecting_instance("a -- inst", 9), None);
+    }
+}
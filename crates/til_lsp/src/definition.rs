@@ -0,0 +1,74 @@
+use lsp_types::{GotoDefinitionResponse, Position, Url};
+
+use crate::{analysis::Analysis, context::Tokens};
+
+/// The locations of the declarations (or namespaces) the identifier at
+/// `position` refers to. Declarations of dependencies are not part of the
+/// analysis, and have no location.
+pub fn definition(
+    analysis: &Analysis,
+    uri: &Url,
+    position: Position,
+) -> Option<GotoDefinitionResponse> {
+    let file = analysis.file(uri)?;
+    let offset = file.line_index().offset(position);
+    let tokens = Tokens::new(file.src());
+    let idx = tokens.identifier_at(offset)?;
+    let namespace = tokens.scope(offset).namespace?;
+    let mut locations = analysis
+        .resolve(&namespace, &tokens.path(idx))
+        .iter()
+        .filter_map(|target| analysis.location(target))
+        .collect::<Vec<_>>();
+    match locations.len() {
+        0 => None,
+        1 => Some(GotoDefinitionResponse::Scalar(locations.remove(0))),
+        _ => Some(GotoDefinitionResponse::Array(locations)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{Location, Range};
+
+    use crate::analysis::tests::analysis_of;
+
+    use super::*;
+
+    #[test]
+    fn definitions() {
+        let (analysis, uri) = analysis_of(&[
+            (
+                "b.til",
+                "namespace c {\n    import a::b::byte;\n    import a::b as x;\n    type word = Group(a: byte, b: x::byte);\n}",
+            ),
+            ("a.til", "namespace a::b { type byte = Bits(8); }"),
+        ]);
+        let a = Url::parse("file:///proj/a.til").unwrap();
+        let byte = GotoDefinitionResponse::Scalar(Location::new(
+            a.clone(),
+            Range::new(Position::new(0, 22), Position::new(0, 26)),
+        ));
+        let definition_at =
+            |line: u32, character: u32| definition(&analysis, &uri, Position::new(line, character));
+        assert_eq!(definition_at(3, 26), Some(byte.clone()));
+        assert_eq!(definition_at(3, 38), Some(byte.clone()));
+        // The path of an import
+        assert_eq!(definition_at(1, 18), Some(byte));
+        assert_eq!(
+            definition_at(2, 14),
+            Some(GotoDefinitionResponse::Scalar(Location::new(
+                a,
+                Range::new(Position::new(0, 10), Position::new(0, 14)),
+            )))
+        );
+        assert_eq!(
+            definition_at(3, 10),
+            Some(GotoDefinitionResponse::Scalar(Location::new(
+                uri.clone(),
+                Range::new(Position::new(3, 9), Position::new(3, 13)),
+            )))
+        );
+        assert_eq!(definition_at(3, 22), None);
+    }
+}
@@ -0,0 +1,395 @@
+use core::fmt;
+
+use til_query::{
+    common::{
+        logical::{
+            logicaltype::{
+                genericproperty::GenericProperty,
+                stream::{Stream, StreamProperty},
+                LogicalType,
+            },
+            split_streams::SplitStreams,
+        },
+        physical::{signal_list::SignalList, stream::PhysicalBitCount},
+    },
+    ir::{
+        generics::{
+            behavioral::BehavioralGenericKind, param_value::GenericParamValue, GenericKind,
+            GenericParameter,
+        },
+        interface_port::InterfacePort,
+        project::{interface::Interface, namespace::Namespace},
+        traits::GetSelf,
+        Ir,
+    },
+};
+use tydi_common::{
+    error::Result,
+    name::{Name, NameSelf, PathName},
+    traits::Document,
+};
+use tydi_intern::Id;
+
+/// A logical type in the syntax of TIL, with all declared types resolved.
+pub fn logical_type(db: &dyn Ir, typ: &LogicalType) -> String {
+    let fields = |ids: &tydi_common::map::InsertionOrderedMap<PathName, Id<LogicalType>>| {
+        ids.iter()
+            .map(|(name, id)| format!("{}: {}", name, logical_type(db, &id.get(db))))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match typ {
+        LogicalType::Null => "Null".to_string(),
+        LogicalType::Bits(b) => format!("Bits({})", property(b)),
+        LogicalType::Group(group) => format!("Group({})", fields(group.field_ids())),
+        LogicalType::Union(union) if union.is_enumeration(db) => format!(
+            "Enum({})",
+            union
+                .field_ids()
+                .keys()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        LogicalType::Union(union) => format!("Union({})", fields(union.field_ids())),
+        LogicalType::Stream(id) => stream(db, &id.get(db)),
+        LogicalType::Array(array) => format!(
+            "Array({}, {})",
+            logical_type(db, &array.element_id().get(db)),
+            property(array.length())
+        ),
+    }
+}
+
+fn stream(db: &dyn Ir, stream: &Stream) -> String {
+    let mut props = vec![
+        format!("data: {}", logical_type(db, &stream.data(db))),
+        format!("throughput: {}", stream_property(&stream.throughput())),
+        format!("dimensionality: {}", property(stream.dimensionality())),
+        format!("synchronicity: {}", stream.synchronicity()),
+        format!("complexity: {}", stream_property(&stream.complexity())),
+        format!("direction: {}", stream.direction()),
+    ];
+    if stream.user(db) != LogicalType::Null {
+        props.push(format!("user: {}", logical_type(db, &stream.user(db))));
+    }
+    if stream.keep() {
+        props.push("keep: true".to_string());
+    }
+    format!("Stream({})", props.join(", "))
+}
+
+fn property<T: fmt::Display>(prop: &GenericProperty<T>) -> String {
+    let operand = |prop: &GenericProperty<T>| match prop {
+        GenericProperty::Combination(_, _, _) => format!("({})", property(prop)),
+        _ => property(prop),
+    };
+    match prop {
+        GenericProperty::Combination(l, op, r) => format!("{} {} {}", operand(l), op, operand(r)),
+        GenericProperty::Fixed(val) => val.to_string(),
+        GenericProperty::Parameterized(name) => name.to_string(),
+    }
+}
+
+fn stream_property<T: fmt::Display>(prop: &StreamProperty<T>) -> String {
+    match prop {
+        StreamProperty::Fixed(val) => val.to_string(),
+        StreamProperty::Parameterized(prop) => property(prop),
+    }
+}
+
+/// A bit count, evaluated where it does not depend on parameters.
+pub fn bit_count(bit_count: &PhysicalBitCount) -> String {
+    if let Some(val) = bit_count.try_eval() {
+        return val.to_string();
+    }
+    let operand = |b: &PhysicalBitCount| match b {
+        PhysicalBitCount::Combination(_, _, _) => format!("({})", self::bit_count(b)),
+        _ => self::bit_count(b),
+    };
+    match bit_count {
+        PhysicalBitCount::Combination(l, op, r) => {
+            format!("{} {} {}", operand(l), op, operand(r))
+        }
+        PhysicalBitCount::Fixed(val) => val.to_string(),
+        PhysicalBitCount::Parameterized(name) => name.to_string(),
+    }
+}
+
+/// The signals of a physical stream and their widths, e.g.
+/// `valid: 1, ready: 1, data: 8`.
+fn signals(signals: &SignalList<PhysicalBitCount>) -> String {
+    [
+        ("valid", signals.valid()),
+        ("ready", signals.ready()),
+        ("data", signals.data()),
+        ("last", signals.last()),
+        ("stai", signals.stai()),
+        ("endi", signals.endi()),
+        ("strb", signals.strb()),
+        ("user", signals.user()),
+    ]
+    .into_iter()
+    .filter_map(|(name, width)| {
+        width
+            .as_ref()
+            .map(|width| format!("{}: {}", name, bit_count(width)))
+    })
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// The physical streams a type (or port) is split into, as a Markdown list
+/// of their signals. Streams are named after `name` and their path, as in
+/// the signals of the generated VHDL.
+fn physical_streams(db: &dyn Ir, name: &str, split: Result<SplitStreams>) -> Result<Vec<String>> {
+    let mut result = vec![];
+    for (path, stream_id) in split?.streams() {
+        let physical = stream_id.get(db).physical(db)?;
+        let stream_name = if path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}__{}", name, path)
+        };
+        result.push(format!(
+            "- `{}`: {}",
+            stream_name,
+            signals(&SignalList::from(&physical))
+        ));
+    }
+    Ok(result)
+}
+
+/// The width of the (non-Stream) signals of a type, if it has any.
+fn width(db: &dyn Ir, typ: Id<LogicalType>) -> Result<Option<String>> {
+    let signals = db.logical_type_split_streams(typ)?.signals().get(db);
+    let fields = signals.fields(db)?;
+    Ok(PhysicalBitCount::sum(fields.values()).map(|b| bit_count(&b)))
+}
+
+pub fn port(db: &dyn Ir, port: &InterfacePort) -> String {
+    format!(
+        "{}: {} {}",
+        port.name(),
+        port.direction(),
+        stream(db, &port.stream(db))
+    )
+}
+
+fn parameter(param: &GenericParameter) -> String {
+    let kind = match param.kind() {
+        GenericKind::Behavioral(BehavioralGenericKind::Integer(integer)) => {
+            integer.kind().to_string().to_lowercase()
+        }
+        GenericKind::Interface(interface) => interface.to_string().to_lowercase(),
+    };
+    match param.default_value() {
+        GenericParamValue::Integer(value) => format!("{}: {} = {}", param.name(), kind, value),
+        _ => format!("{}: {}", param.name(), kind),
+    }
+}
+
+fn parameters<'a>(params: impl IntoIterator<Item = &'a GenericParameter>) -> String {
+    let params = params.into_iter().map(parameter).collect::<Vec<_>>();
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    }
+}
+
+/// Markdown consisting of a TIL code block, documentation and further
+/// details.
+fn markdown(code: String, doc: Option<&String>, details: Vec<String>) -> String {
+    let mut result = format!("```til\n{}\n```", code);
+    if let Some(doc) = doc {
+        result.push_str("\n\n---\n\n");
+        result.push_str(doc.trim());
+    }
+    if !details.is_empty() {
+        result.push_str("\n\n---\n\n");
+        result.push_str(&details.join("\n"));
+    }
+    result
+}
+
+/// A description of a port, as Markdown.
+pub fn describe_port(db: &dyn Ir, interface_port: &InterfacePort) -> String {
+    let mut details = vec![];
+    if let Ok(streams) = physical_streams(
+        db,
+        interface_port.name().as_ref(),
+        db.stream_split_streams(interface_port.stream_id()),
+    ) {
+        if !streams.is_empty() {
+            details.push("Physical streams:".to_string());
+            details.extend(streams);
+        }
+    }
+    markdown(port(db, interface_port), interface_port.doc(), details)
+}
+
+fn describe_interface(
+    db: &dyn Ir,
+    keyword: &str,
+    name: &PathName,
+    interface: &Interface,
+    doc: Option<&String>,
+) -> String {
+    let mut code = format!(
+        "{} {}{} = (",
+        keyword,
+        name.join("::"),
+        parameters(interface.parameters().values())
+    );
+    let mut details = vec![];
+    for (port_name, interface_port) in interface.ports().iter() {
+        code.push_str(&format!("\n    {},", port(db, interface_port)));
+        if let Ok(streams) = physical_streams(
+            db,
+            port_name.as_ref(),
+            db.stream_split_streams(interface_port.stream_id()),
+        ) {
+            details.extend(streams);
+        }
+    }
+    if interface.ports().len() > 0 {
+        code.push('\n');
+    }
+    code.push(')');
+    if !details.is_empty() {
+        details.insert(0, "Physical streams:".to_string());
+    }
+    markdown(code, doc, details)
+}
+
+/// A description of the declaration `name` in `namespace`, as Markdown.
+///
+/// Types are described by the type they resolve to (with the default values
+/// of their parameters), and the widths of their signals. Streamlets,
+/// implementations and interfaces are described by their ports and the
+/// signals of their ports.
+pub fn describe_declaration(
+    db: &dyn Ir,
+    namespace: &Namespace,
+    path: &PathName,
+    name: &Name,
+) -> Option<String> {
+    let full_name = path.with_child(name.clone());
+    if let Some(decl) = namespace.type_decls().get(name) {
+        let typ = decl.type_id(db).ok()?;
+        let code = format!(
+            "type {}{} = {}",
+            full_name.join("::"),
+            parameters(decl.parameters().values()),
+            logical_type(db, &typ.get(db))
+        );
+        let mut details = vec![];
+        if let Ok(Some(width)) = width(db, typ) {
+            details.push(format!("Width: {} bits", width));
+        }
+        if let Ok(streams) = physical_streams(db, name.as_ref(), db.logical_type_split_streams(typ))
+        {
+            if !streams.is_empty() {
+                details.push("Physical streams:".to_string());
+                details.extend(streams);
+            }
+        }
+        Some(markdown(code, decl.doc(), details))
+    } else if let Ok(streamlet) = namespace.get_streamlet(db, name.clone()) {
+        Some(describe_interface(
+            db,
+            "streamlet",
+            &full_name,
+            &streamlet.interface(db),
+            streamlet.doc(),
+        ))
+    } else if let Ok(implementation) = namespace.get_implementation(db, name.clone()) {
+        let interface = namespace.get_interface(db, name.clone()).ok()?;
+        Some(describe_interface(
+            db,
+            "impl",
+            &full_name,
+            &interface,
+            implementation.doc(),
+        ))
+    } else if let Ok(interface) = namespace.get_interface(db, name.clone()) {
+        Some(describe_interface(
+            db,
+            "interface",
+            &full_name,
+            &interface,
+            interface.doc(),
+        ))
+    } else {
+        namespace.constants().get(name).map(|constant| {
+            markdown(
+                format!(
+                    "const {}: {} = {}",
+                    full_name.join("::"),
+                    constant.kind().to_string().to_lowercase(),
+                    constant.value()
+                ),
+                constant.doc(),
+                vec![],
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::tests::analysis;
+
+    use super::*;
+
+    #[test]
+    fn describe() {
+        let (analysis, _) = analysis(
+            "namespace a {
+                /// A byte
+                type byte = Bits(8);
+                type pair = Group(a: byte, b: Enum(x, y, z));
+                type bytes<d: dimensionality = 1> = Stream(data: pair, throughput: 2.0, dimensionality: d, synchronicity: Sync, complexity: 8);
+                const width: natural = 8;
+                streamlet s = (x: in bytes, y: out bytes<d = 2>);
+            }",
+        );
+        let path = PathName::try_new(["a"]).unwrap();
+        let namespace = analysis.namespace(&path).unwrap();
+        let describe = |name: &str| {
+            describe_declaration(
+                analysis.db(),
+                &namespace,
+                &path,
+                &Name::try_new(name).unwrap(),
+            )
+            .unwrap()
+        };
+        assert_eq!(
+            describe("byte"),
+            "```til\ntype a::byte = Bits(8)\n```\n\n---\n\nA byte\n\n---\n\nWidth: 8 bits"
+        );
+        assert_eq!(
+            describe("pair"),
+            "```til\ntype a::pair = Group(a: Bits(8), b: Enum(x, y, z))\n```\n\n---\n\nWidth: 10 bits"
+        );
+        assert_eq!(
+            describe("bytes"),
+            concat!(
+                "```til\ntype a::bytes<d: dimensionality = 1> = Stream(data: Group(a: Bits(8), b: Enum(x, y, z)), ",
+                "throughput: 2, dimensionality: 1, synchronicity: Sync, complexity: 8, direction: Forward)\n```",
+                "\n\n---\n\nPhysical streams:\n",
+                "- `bytes`: valid: 1, ready: 1, data: 20, last: 2, stai: 1, endi: 1, strb: 2"
+            )
+        );
+        assert_eq!(
+            describe("width"),
+            "```til\nconst a::width: natural = 8\n```"
+        );
+        let streamlet = describe("s");
+        assert!(streamlet.starts_with("```til\nstreamlet a::s = (\n    x: in Stream("));
+        assert!(streamlet.contains("\n    y: out Stream(data: Group(a: Bits(8), b: Enum(x, y, z)), throughput: 2, dimensionality: 2,"));
+        assert!(streamlet.ends_with("- `x`: valid: 1, ready: 1, data: 20, last: 2, stai: 1, endi: 1, strb: 2\n- `y`: valid: 1, ready: 1, data: 20, last: 4, stai: 1, endi: 1, strb: 2"));
+    }
+}
@@ -0,0 +1,112 @@
+use lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind, Position, Url};
+use tydi_common::name::{Name, NameSelf};
+
+use crate::{
+    analysis::{Analysis, Target},
+    context::Tokens,
+    describe::{describe_declaration, describe_port},
+};
+
+/// Describe the identifier at `position`, which may refer to a declaration,
+/// a namespace, a port of the surrounding declaration or a port of an
+/// instance (`instance.port`).
+pub fn hover(analysis: &Analysis, uri: &Url, position: Position) -> Option<Hover> {
+    let file = analysis.file(uri)?;
+    let offset = file.line_index().offset(position);
+    let tokens = Tokens::new(file.src());
+    let idx = tokens.identifier_at(offset)?;
+    let name = Name::try_new(tokens.identifier(idx)?).ok()?;
+    let scope = tokens.scope(offset);
+    let namespace = scope.namespace?;
+    let db = analysis.db();
+
+    let description = if let Some(instance) = tokens.selected_instance(idx) {
+        let ports = analysis.instance_ports(&namespace, scope.declaration.as_ref()?)?;
+        let port = ports
+            .get(&Name::try_new(instance).ok()?)?
+            .iter()
+            .find(|port| port.name() == &name)?;
+        describe_port(db, port)
+    } else {
+        let descriptions = analysis
+            .resolve(&namespace, &tokens.path(idx))
+            .into_iter()
+            .filter_map(|target| match target {
+                Target::Namespace(path) => {
+                    Some(format!("```til\nnamespace {}\n```", path.join("::")))
+                }
+                Target::Declaration(path, name) => {
+                    describe_declaration(db, &analysis.namespace(&path)?, &path, &name)
+                }
+            })
+            .collect::<Vec<_>>();
+        if descriptions.is_empty() {
+            // A port of the surrounding streamlet, interface or implementation
+            let interface = analysis
+                .namespace(&namespace)?
+                .get_interface(db, scope.declaration?)
+                .ok()?;
+            let port = interface.ports().get(&name)?.clone();
+            describe_port(db, &port)
+        } else {
+            descriptions.join("\n\n---\n\n")
+        }
+    };
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: description,
+        }),
+        range: Some(file.line_index().range(tokens.span(idx))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::tests::analysis;
+
+    use super::*;
+
+    #[test]
+    fn hover_positions() {
+        let src = "namespace a {\n    type byte = Bits(8);\n    type bs = Stream(data: byte, dimensionality: 0, synchronicity: Sync, complexity: 4);\n    streamlet s = (x: in bs, y: out bs);\n    impl i = s {\n        inst = s;\n        x -- inst.x;\n        inst.y -- y;\n    };\n}";
+        let (analysis, uri) = analysis(src);
+        let hover_at = |line: u32, character: u32| {
+            hover(&analysis, &uri, Position::new(line, character)).map(|hover| {
+                match hover.contents {
+                    HoverContents::Markup(markup) => markup.value,
+                    _ => unreachable!(),
+                }
+            })
+        };
+        // A type
+        assert_eq!(
+            hover_at(2, 28).unwrap(),
+            "```til\ntype a::byte = Bits(8)\n```\n\n---\n\nWidth: 8 bits"
+        );
+        assert!(hover_at(3, 26)
+            .unwrap()
+            .starts_with("```til\ntype a::bs = Stream(data: Bits(8), throughput: 1,"));
+        // A streamlet
+        assert!(hover_at(4, 13)
+            .unwrap()
+            .starts_with("```til\nstreamlet a::s = (\n"));
+        // A port of the implementation
+        assert!(hover_at(6, 8)
+            .unwrap()
+            .starts_with("```til\nx: in Stream(data: Bits(8),"));
+        // A port of an instance
+        assert!(hover_at(7, 13)
+            .unwrap()
+            .starts_with("```til\ny: out Stream(data: Bits(8),"));
+        assert_eq!(
+            hover(&analysis, &uri, Position::new(6, 19)).unwrap().range,
+            Some(lsp_types::Range::new(
+                Position::new(6, 18),
+                Position::new(6, 19)
+            ))
+        );
+        assert_eq!(hover_at(0, 3), None);
+    }
+}
@@ -0,0 +1,10 @@
+pub mod analysis;
+pub mod completion;
+pub mod context;
+pub mod definition;
+pub mod describe;
+pub mod hover;
+pub mod line_index;
+pub mod server;
+pub mod symbols;
+pub mod workspace;
@@ -0,0 +1,83 @@
+use lsp_types::{Position, Range};
+use til_parser::Span;
+
+/// Converts between the character offsets used for spans by the parser, and
+/// the positions used by the Language Server Protocol, which are lines and
+/// UTF-16 code units within those lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    chars: Vec<char>,
+    /// The character offset at which every line starts.
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(src: &str) -> Self {
+        let chars = src.chars().collect::<Vec<_>>();
+        let mut line_starts = vec![0];
+        for (offset, c) in chars.iter().enumerate() {
+            if *c == '\n' {
+                line_starts.push(offset + 1);
+            }
+        }
+        LineIndex { chars, line_starts }
+    }
+
+    /// The position of a character offset, offsets beyond the end of the
+    /// source are placed at its end.
+    pub fn position(&self, offset: usize) -> Position {
+        let offset = offset.min(self.chars.len());
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let character = self.chars[self.line_starts[line]..offset]
+            .iter()
+            .map(|c| c.len_utf16())
+            .sum::<usize>();
+        Position::new(line as u32, character as u32)
+    }
+
+    /// The character offset of a position, positions beyond the end of a line
+    /// are placed at the end of that line.
+    pub fn offset(&self, position: Position) -> usize {
+        let line = position.line as usize;
+        if line >= self.line_starts.len() {
+            return self.chars.len();
+        }
+        let mut offset = self.line_starts[line];
+        let mut character = 0;
+        while offset < self.chars.len()
+            && self.chars[offset] != '\n'
+            && character < position.character as usize
+        {
+            character += self.chars[offset].len_utf16();
+            offset += 1;
+        }
+        offset
+    }
+
+    pub fn range(&self, span: &Span) -> Range {
+        Range::new(self.position(span.start), self.position(span.end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions() {
+        let index = LineIndex::new("namespace a {\n    // λ𝄞\n    type t = Bits(8);\n}");
+        assert_eq!(index.position(0), Position::new(0, 0));
+        assert_eq!(index.position(14), Position::new(1, 0));
+        // 𝄞 takes up two UTF-16 code units
+        assert_eq!(index.position(23), Position::new(1, 10));
+        assert_eq!(index.position(24), Position::new(2, 0));
+        assert_eq!(index.offset(Position::new(1, 10)), 23);
+        assert_eq!(index.offset(Position::new(2, 9)), 33);
+        assert_eq!(index.offset(Position::new(1, 99)), 23);
+        assert_eq!(index.offset(Position::new(9, 0)), 47);
+        assert_eq!(
+            index.range(&(33..34)),
+            Range::new(Position::new(2, 9), Position::new(2, 10))
+        );
+    }
+}
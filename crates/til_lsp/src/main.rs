@@ -0,0 +1,19 @@
+use log::{error, LevelFilter};
+use lsp_server::Connection;
+
+fn main() {
+    // Stdout is used to communicate with the client, so logging goes to stderr
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+        .filter_module("salsa", LevelFilter::Warn)
+        .target(env_logger::Target::Stderr)
+        .init();
+
+    let (connection, io_threads) = Connection::stdio();
+    if let Err(err) = til_lsp::server::run(&connection) {
+        error!("{}", err);
+    }
+    drop(connection);
+    if let Err(err) = io_threads.join() {
+        error!("{}", err);
+    }
+}
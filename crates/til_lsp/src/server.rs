@@ -0,0 +1,293 @@
+use std::error::Error;
+
+use log::{debug, info, warn};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response, ResponseError};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+        Notification as _, PublishDiagnostics,
+    },
+    request::{Completion, DocumentSymbolRequest, GotoDefinition, HoverRequest, Request as _},
+    CompletionOptions, HoverProviderCapability, InitializeParams, OneOf, PublishDiagnosticsParams,
+    ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind,
+};
+
+use crate::{
+    completion::completion, definition::definition, hover::hover, symbols::document_symbols,
+    workspace::Workspace,
+};
+
+pub type ServerResult<T> = std::result::Result<T, Box<dyn Error + Sync + Send>>;
+
+pub fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![".".to_string()]),
+            ..Default::default()
+        }),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    }
+}
+
+/// Initialize the connection, and handle messages until the client shuts the
+/// server down.
+pub fn run(connection: &Connection) -> ServerResult<()> {
+    let capabilities = serde_json::to_value(capabilities())?;
+    let params: InitializeParams = serde_json::from_value(connection.initialize(capabilities)?)?;
+    if let Some(client) = params.client_info {
+        info!("Initialized by {}", client.name);
+    }
+
+    let mut server = Server {
+        connection,
+        workspace: Workspace::new(),
+    };
+    loop {
+        // Documents are analysed when there are no messages left to handle,
+        // so a burst of changes is only analysed once.
+        let msg = match connection.receiver.try_recv() {
+            Ok(msg) => msg,
+            Err(_) => {
+                server.publish_diagnostics()?;
+                match connection.receiver.recv() {
+                    Ok(msg) => msg,
+                    Err(_) => return Ok(()),
+                }
+            }
+        };
+        match msg {
+            Message::Request(req) => {
+                if connection.handle_shutdown(&req)? {
+                    return Ok(());
+                }
+                server.handle_request(req)?;
+            }
+            Message::Notification(not) => server.handle_notification(not)?,
+            Message::Response(_) => (),
+        }
+    }
+}
+
+struct Server<'a> {
+    connection: &'a Connection,
+    workspace: Workspace,
+}
+
+impl Server<'_> {
+    fn publish_diagnostics(&mut self) -> ServerResult<()> {
+        for (uri, diagnostics) in self.workspace.update() {
+            let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+            self.connection
+                .sender
+                .send(Notification::new(PublishDiagnostics::METHOD.to_string(), params).into())?;
+        }
+        Ok(())
+    }
+
+    fn handle_request(&mut self, req: Request) -> ServerResult<()> {
+        // Requests are answered based on the current contents of the documents
+        self.publish_diagnostics()?;
+        let workspace = &self.workspace;
+        let id = req.id.clone();
+        let result = match req.method.as_str() {
+            GotoDefinition::METHOD => handle::<GotoDefinition>(req, |params| {
+                let params = params.text_document_position_params;
+                let uri = &params.text_document.uri;
+                workspace
+                    .analysis(uri)
+                    .and_then(|analysis| definition(analysis, uri, params.position))
+            }),
+            HoverRequest::METHOD => handle::<HoverRequest>(req, |params| {
+                let params = params.text_document_position_params;
+                let uri = &params.text_document.uri;
+                workspace
+                    .analysis(uri)
+                    .and_then(|analysis| hover(analysis, uri, params.position))
+            }),
+            Completion::METHOD => handle::<Completion>(req, |params| {
+                let params = params.text_document_position;
+                let uri = &params.text_document.uri;
+                workspace
+                    .analysis(uri)
+                    .and_then(|analysis| completion(analysis, uri, params.position))
+            }),
+            DocumentSymbolRequest::METHOD => handle::<DocumentSymbolRequest>(req, |params| {
+                let uri = &params.text_document.uri;
+                workspace
+                    .analysis(uri)
+                    .and_then(|analysis| document_symbols(analysis, uri))
+            }),
+            method => Err(ResponseError {
+                code: ErrorCode::MethodNotFound as i32,
+                message: format!("Unsupported request: {}", method),
+                data: None,
+            }),
+        };
+        let response = match result {
+            Ok(result) => Response {
+                id,
+                result: Some(result),
+                error: None,
+            },
+            Err(error) => Response {
+                id,
+                result: None,
+                error: Some(error),
+            },
+        };
+        self.connection.sender.send(response.into())?;
+        Ok(())
+    }
+
+    fn handle_notification(&mut self, not: Notification) -> ServerResult<()> {
+        let result = match not.method.as_str() {
+            DidOpenTextDocument::METHOD => params::<DidOpenTextDocument>(not).map(|params| {
+                let document = params.text_document;
+                self.workspace.open(document.uri, document.text);
+            }),
+            DidChangeTextDocument::METHOD => {
+                params::<DidChangeTextDocument>(not).map(|mut params| {
+                    // Documents are always synchronised in full
+                    if let Some(change) = params.content_changes.pop() {
+                        self.workspace.change(params.text_document.uri, change.text);
+                    }
+                })
+            }
+            DidSaveTextDocument::METHOD => params::<DidSaveTextDocument>(not)
+                .map(|params| self.workspace.save(&params.text_document.uri)),
+            DidCloseTextDocument::METHOD => params::<DidCloseTextDocument>(not)
+                .map(|params| self.workspace.close(&params.text_document.uri)),
+            method => {
+                debug!("Unsupported notification: {}", method);
+                Ok(())
+            }
+        };
+        if let Err(err) = result {
+            warn!("Invalid notification: {}", err);
+        }
+        Ok(())
+    }
+}
+
+/// Handle a request of type `R`, responding with the result of `handler`.
+fn handle<R: lsp_types::request::Request>(
+    req: Request,
+    handler: impl FnOnce(R::Params) -> R::Result,
+) -> Result<serde_json::Value, ResponseError> {
+    let params = serde_json::from_value(req.params).map_err(|err| ResponseError {
+        code: ErrorCode::InvalidParams as i32,
+        message: err.to_string(),
+        data: None,
+    })?;
+    serde_json::to_value(handler(params)).map_err(|err| ResponseError {
+        code: ErrorCode::InternalError as i32,
+        message: err.to_string(),
+        data: None,
+    })
+}
+
+fn params<N: lsp_types::notification::Notification>(
+    not: Notification,
+) -> serde_json::Result<N::Params> {
+    serde_json::from_value(not.params)
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{
+        notification::{Exit, Initialized},
+        request::{Initialize, Shutdown},
+        DidOpenTextDocumentParams, Hover, HoverParams, InitializedParams, Position,
+        TextDocumentIdentifier, TextDocumentItem, TextDocumentPositionParams, Url,
+    };
+
+    use super::*;
+
+    #[test]
+    fn session() {
+        let (server, client) = Connection::memory();
+        let server = std::thread::spawn(move || run(&server).unwrap());
+        let request = |id: i32, method: &str, params: serde_json::Value| -> Response {
+            client
+                .sender
+                .send(Request::new(id.into(), method.to_string(), params).into())
+                .unwrap();
+            match client.receiver.recv().unwrap() {
+                Message::Response(response) => response,
+                msg => panic!("Unexpected message: {:?}", msg),
+            }
+        };
+        let notify = |method: &str, params: serde_json::Value| {
+            client
+                .sender
+                .send(Notification::new(method.to_string(), params).into())
+                .unwrap()
+        };
+
+        #[allow(deprecated)]
+        let response = request(
+            1,
+            Initialize::METHOD,
+            serde_json::to_value(InitializeParams::default()).unwrap(),
+        );
+        assert!(response.error.is_none());
+        notify(
+            Initialized::METHOD,
+            serde_json::to_value(InitializedParams {}).unwrap(),
+        );
+
+        let uri = Url::parse("untitled:a.til").unwrap();
+        notify(
+            DidOpenTextDocument::METHOD,
+            serde_json::to_value(DidOpenTextDocumentParams {
+                text_document: TextDocumentItem::new(
+                    uri.clone(),
+                    "til".to_string(),
+                    1,
+                    "namespace a {\n    type t = Bits(8);\n    type u = Group(a: t);\n}"
+                        .to_string(),
+                ),
+            })
+            .unwrap(),
+        );
+        let diagnostics = match client.receiver.recv().unwrap() {
+            Message::Notification(not) => {
+                assert_eq!(not.method, PublishDiagnostics::METHOD);
+                serde_json::from_value::<PublishDiagnosticsParams>(not.params).unwrap()
+            }
+            msg => panic!("Unexpected message: {:?}", msg),
+        };
+        assert_eq!(diagnostics.uri, uri);
+        assert!(!diagnostics.diagnostics.is_empty());
+
+        let response = request(
+            2,
+            HoverRequest::METHOD,
+            serde_json::to_value(HoverParams {
+                text_document_position_params: TextDocumentPositionParams::new(
+                    TextDocumentIdentifier::new(uri),
+                    Position::new(2, 22),
+                ),
+                work_done_progress_params: Default::default(),
+            })
+            .unwrap(),
+        );
+        let hover: Option<Hover> = serde_json::from_value(response.result.unwrap()).unwrap();
+        assert!(hover.is_some());
+
+        let response = request(3, "til/unknown", serde_json::Value::Null);
+        assert_eq!(
+            response.error.map(|err| err.code),
+            Some(ErrorCode::MethodNotFound as i32)
+        );
+
+        let response = request(4, Shutdown::METHOD, serde_json::Value::Null);
+        assert!(response.error.is_none());
+        notify(Exit::METHOD, serde_json::Value::Null);
+        server.join().unwrap();
+    }
+}
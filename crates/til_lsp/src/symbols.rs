@@ -0,0 +1,134 @@
+use lsp_types::{DocumentSymbol, DocumentSymbolResponse, SymbolKind, Url};
+use til_parser::namespace::{Decl, Statement};
+
+use crate::analysis::{name_span, namespace_name_span, Analysis};
+
+/// The namespaces of a file, with their declarations.
+pub fn document_symbols(analysis: &Analysis, uri: &Url) -> Option<DocumentSymbolResponse> {
+    let file = analysis.file(uri)?;
+    let line_index = file.line_index();
+    let symbols = file
+        .namespaces()
+        .iter()
+        .map(|namespace| {
+            let span = namespace_name_span(namespace);
+            let end = namespace
+                .stats()
+                .iter()
+                .map(|(_, span)| span.end)
+                .max()
+                .unwrap_or(span.end);
+            let children = namespace
+                .stats()
+                .iter()
+                .filter_map(|(stat, span)| match stat {
+                    Statement::Decl(decl) => Some((decl, span)),
+                    Statement::Import(_) => None,
+                })
+                .map(|(decl, span)| {
+                    let (kind, detail) = match decl {
+                        Decl::TypeDecl(_, _, _, _) => (SymbolKind::STRUCT, "type"),
+                        Decl::ImplDecl(_, _, _) => (SymbolKind::OBJECT, "impl"),
+                        Decl::InterfaceDecl(_, _, _) => (SymbolKind::INTERFACE, "interface"),
+                        Decl::StreamletDecl(_, _, _) => (SymbolKind::CLASS, "streamlet"),
+                        Decl::ConstDecl(_, _, _, _) => (SymbolKind::CONSTANT, "const"),
+                    };
+                    symbol(
+                        decl.name().0.clone(),
+                        detail,
+                        kind,
+                        line_index.range(span),
+                        line_index.range(&name_span(decl.name())),
+                        None,
+                    )
+                })
+                .collect();
+            symbol(
+                namespace.name().join("::"),
+                "namespace",
+                SymbolKind::NAMESPACE,
+                line_index.range(&(span.start..end)),
+                line_index.range(&span),
+                Some(children),
+            )
+        })
+        .collect();
+    Some(DocumentSymbolResponse::Nested(symbols))
+}
+
+fn symbol(
+    name: String,
+    detail: &str,
+    kind: SymbolKind,
+    range: lsp_types::Range,
+    selection_range: lsp_types::Range,
+    children: Option<Vec<DocumentSymbol>>,
+) -> DocumentSymbol {
+    // `deprecated` is deprecated in favour of `tags`, but must still be set
+    #[allow(deprecated)]
+    DocumentSymbol {
+        name,
+        detail: Some(detail.to_string()),
+        kind,
+        tags: None,
+        deprecated: None,
+        range,
+        selection_range,
+        children,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{Position, Range};
+
+    use crate::analysis::tests::analysis;
+
+    use super::*;
+
+    #[test]
+    fn symbols() {
+        let (analysis, uri) = analysis(
+            "namespace a::b {\n    import c::d;\n    type t = Bits(8);\n    const w: natural = 4;\n}\nnamespace e {\n    streamlet s = ();\n}",
+        );
+        let symbols = match document_symbols(&analysis, &uri) {
+            Some(DocumentSymbolResponse::Nested(symbols)) => symbols,
+            _ => unreachable!(),
+        };
+        let summary = |symbol: &DocumentSymbol| (symbol.name.clone(), symbol.kind);
+        assert_eq!(
+            symbols.iter().map(summary).collect::<Vec<_>>(),
+            vec![
+                ("a::b".to_string(), SymbolKind::NAMESPACE),
+                ("e".to_string(), SymbolKind::NAMESPACE)
+            ]
+        );
+        assert_eq!(
+            symbols[0].selection_range,
+            Range::new(Position::new(0, 10), Position::new(0, 14))
+        );
+        let children = symbols[0].children.as_ref().unwrap();
+        assert_eq!(
+            children.iter().map(summary).collect::<Vec<_>>(),
+            vec![
+                ("t".to_string(), SymbolKind::STRUCT),
+                ("w".to_string(), SymbolKind::CONSTANT)
+            ]
+        );
+        assert_eq!(
+            children[0].selection_range,
+            Range::new(Position::new(2, 9), Position::new(2, 10))
+        );
+        assert_eq!(children[1].detail, Some("const".to_string()));
+        assert_eq!(
+            symbols[1]
+                .children
+                .as_ref()
+                .unwrap()
+                .iter()
+                .map(summary)
+                .collect::<Vec<_>>(),
+            vec![("s".to_string(), SymbolKind::CLASS)]
+        );
+    }
+}
@@ -0,0 +1,399 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use log::{debug, warn};
+use lsp_types::{Diagnostic, DiagnosticSeverity, Range, Url};
+use til_parser::{
+    project::{import_dependencies_quiet, read_project_file, PROJECT_FILE_NAME},
+    query::SourceFile,
+};
+use til_query::ir::{db::Database, project::Project};
+use tydi_common::error::Result;
+
+use crate::analysis::Analysis;
+
+/// The files a document is analysed together with.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ScopeKey {
+    /// The files of a project, by the location of its project file.
+    Project(PathBuf),
+    /// A file which is not part of a project, and is analysed on its own.
+    File(Url),
+}
+
+struct ScopeFile {
+    uri: Url,
+    /// The path of the file, relative to its project.
+    path: PathBuf,
+    /// The directory the links of the file are relative to.
+    link_root: PathBuf,
+}
+
+struct Scope {
+    analysis: Analysis,
+    files: Vec<ScopeFile>,
+    /// Why a file could not be analysed as part of its project.
+    project_error: Option<String>,
+    /// Whether the files changed since the last analysis.
+    dirty: bool,
+}
+
+impl Scope {
+    fn project(project_file: &Path) -> Result<Self> {
+        let project_info = read_project_file(project_file)?;
+        let location = project_file.parent().unwrap_or(Path::new("")).to_path_buf();
+        let db = Database::default();
+        let mut project = project_info.to_project(location.clone())?;
+        import_dependencies_quiet(
+            &project_info,
+            &location,
+            &db,
+            &mut project,
+            &BTreeMap::new(),
+        )?;
+        let files = project_info
+            .file_paths(&location)?
+            .into_iter()
+            .filter_map(|(file_location, link_root)| {
+                let uri = Url::from_file_path(&file_location).ok()?;
+                let path = file_location
+                    .strip_prefix(&location)
+                    .unwrap_or(&file_location)
+                    .to_path_buf();
+                Some(ScopeFile {
+                    uri,
+                    path,
+                    link_root,
+                })
+            })
+            .collect();
+        Ok(Scope {
+            analysis: Analysis::new(project, db),
+            files,
+            project_error: None,
+            dirty: true,
+        })
+    }
+
+    /// A scope of a single file, which is evaluated like `til check` would.
+    fn file(uri: &Url, project_error: Option<String>) -> Self {
+        let (path, link_root) = match uri.to_file_path() {
+            Ok(path) => (
+                PathBuf::from(path.file_name().unwrap_or(path.as_os_str())),
+                path.parent().unwrap_or(Path::new(".")).to_path_buf(),
+            ),
+            Err(_) => (PathBuf::from(uri.as_str()), PathBuf::from(".")),
+        };
+        Scope {
+            analysis: Analysis::new(
+                Project::new("proj", ".", None::<&str>).unwrap(),
+                Database::default(),
+            ),
+            files: vec![ScopeFile {
+                uri: uri.clone(),
+                path,
+                link_root,
+            }],
+            project_error,
+            dirty: true,
+        }
+    }
+
+    fn contains(&self, uri: &Url) -> bool {
+        self.files.iter().any(|file| &file.uri == uri)
+    }
+
+    /// Analyse the files again, using the contents of open documents over
+    /// those of the files on disk.
+    fn update(&mut self, documents: &HashMap<Url, String>) {
+        let mut sources = vec![];
+        for file in &self.files {
+            let src = match documents.get(&file.uri) {
+                Some(src) => src.clone(),
+                None => match std::fs::read_to_string(file.path_on_disk()) {
+                    Ok(src) => src,
+                    Err(err) => {
+                        warn!("Unable to read file {}: {}", file.uri, err);
+                        continue;
+                    }
+                },
+            };
+            match SourceFile::new(src, file.link_root.clone()) {
+                Ok(source) => sources.push((file.uri.clone(), source.with_path(&file.path))),
+                Err(err) => warn!("Unable to analyse file {}: {}", file.uri, err),
+            }
+        }
+        self.analysis.update(sources);
+        self.dirty = false;
+    }
+
+    fn diagnostics(&self) -> Vec<(Url, Vec<Diagnostic>)> {
+        self.analysis
+            .files()
+            .iter()
+            .map(|file| {
+                let mut diagnostics = file.lsp_diagnostics();
+                if let Some(err) = &self.project_error {
+                    diagnostics.push(Diagnostic {
+                        range: Range::default(),
+                        severity: Some(DiagnosticSeverity::ERROR),
+                        source: Some("til".to_string()),
+                        message: err.clone(),
+                        ..Default::default()
+                    });
+                }
+                (file.uri().clone(), diagnostics)
+            })
+            .collect()
+    }
+}
+
+impl ScopeFile {
+    fn path_on_disk(&self) -> PathBuf {
+        self.uri
+            .to_file_path()
+            .unwrap_or_else(|_| self.path.clone())
+    }
+}
+
+/// The documents opened by the client, and the analyses of the projects (or
+/// single files) they are part of.
+///
+/// Analyses are only updated on [`Workspace::update`], so changes to multiple
+/// documents (or many changes to one document) are analysed together.
+#[derive(Default)]
+pub struct Workspace {
+    documents: HashMap<Url, String>,
+    document_scopes: HashMap<Url, ScopeKey>,
+    scopes: HashMap<ScopeKey, Scope>,
+    /// The files diagnostics were published for.
+    published: HashSet<Url>,
+}
+
+impl Workspace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn open(&mut self, uri: Url, src: String) {
+        self.documents.insert(uri.clone(), src);
+        self.assign_scope(uri);
+    }
+
+    pub fn change(&mut self, uri: Url, src: String) {
+        self.documents.insert(uri.clone(), src);
+        match self.document_scopes.get(&uri) {
+            Some(key) => self.set_dirty(&key.clone()),
+            None => self.assign_scope(uri),
+        }
+    }
+
+    /// A document was saved. Saving a project file reloads the project.
+    pub fn save(&mut self, uri: &Url) {
+        let is_project_file = uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| path.file_name().map(|name| name == PROJECT_FILE_NAME))
+            .unwrap_or(false);
+        if is_project_file {
+            // The files of the project may have changed, as well as whether
+            // other open documents are part of it.
+            self.scopes.clear();
+            self.document_scopes.clear();
+            let documents = self.documents.keys().cloned().collect::<Vec<_>>();
+            for document in documents {
+                self.assign_scope(document);
+            }
+        } else if let Some(key) = self.document_scopes.get(uri) {
+            self.set_dirty(&key.clone());
+        }
+    }
+
+    /// A document was closed, files of projects are analysed as they are on
+    /// disk again.
+    pub fn close(&mut self, uri: &Url) {
+        self.documents.remove(uri);
+        match self.document_scopes.remove(uri) {
+            Some(key @ ScopeKey::File(_)) => {
+                self.scopes.remove(&key);
+            }
+            Some(key) => {
+                if self.document_scopes.values().any(|other| other == &key) {
+                    self.set_dirty(&key);
+                } else {
+                    self.scopes.remove(&key);
+                }
+            }
+            None => (),
+        }
+    }
+
+    /// Analyse the scopes which changed since the last update, and return
+    /// the diagnostics to publish for their files.
+    ///
+    /// Files which are no longer part of any analysis get empty diagnostics,
+    /// to clear those published before.
+    pub fn update(&mut self) -> Vec<(Url, Vec<Diagnostic>)> {
+        let mut result = vec![];
+        for scope in self.scopes.values_mut().filter(|scope| scope.dirty) {
+            scope.update(&self.documents);
+            result.extend(scope.diagnostics());
+        }
+        let analysed = self
+            .scopes
+            .values()
+            .flat_map(|scope| scope.analysis.files().iter().map(|file| file.uri().clone()))
+            .collect::<HashSet<_>>();
+        for uri in self.published.difference(&analysed) {
+            result.push((uri.clone(), vec![]));
+        }
+        self.published = analysed;
+        result
+    }
+
+    /// The analysis a file is part of, as of the last update.
+    pub fn analysis(&self, uri: &Url) -> Option<&Analysis> {
+        match self.document_scopes.get(uri) {
+            Some(key) => self.scopes.get(key),
+            None => self.scopes.values().find(|scope| scope.contains(uri)),
+        }
+        .map(|scope| &scope.analysis)
+    }
+
+    fn set_dirty(&mut self, key: &ScopeKey) {
+        if let Some(scope) = self.scopes.get_mut(key) {
+            scope.dirty = true;
+        }
+    }
+
+    /// Find (or load) the scope of a document.
+    fn assign_scope(&mut self, uri: Url) {
+        let (key, project_error) = match project_file_of(&uri) {
+            Some(project_file) => {
+                let key = ScopeKey::Project(project_file.clone());
+                if self.scopes.contains_key(&key) {
+                    (Some(key), None)
+                } else {
+                    match Scope::project(&project_file) {
+                        Ok(scope) if scope.contains(&uri) => {
+                            debug!("Loaded project {}", project_file.display());
+                            self.scopes.insert(key.clone(), scope);
+                            (Some(key), None)
+                        }
+                        // The document is not one of the project's files
+                        Ok(_) => (None, None),
+                        Err(err) => (
+                            None,
+                            Some(format!(
+                                "Unable to load project {}: {}",
+                                project_file.display(),
+                                err
+                            )),
+                        ),
+                    }
+                }
+            }
+            None => (None, None),
+        };
+        let key = match key {
+            Some(key) if self.scopes[&key].contains(&uri) => key,
+            _ => {
+                let key = ScopeKey::File(uri.clone());
+                self.scopes
+                    .insert(key.clone(), Scope::file(&uri, project_error));
+                key
+            }
+        };
+        self.set_dirty(&key);
+        self.document_scopes.insert(uri, key);
+    }
+}
+
+/// The project file of the nearest directory containing the file, if any.
+fn project_file_of(uri: &Url) -> Option<PathBuf> {
+    let path = uri.to_file_path().ok()?;
+    path.ancestors()
+        .skip(1)
+        .map(|dir| dir.join(PROJECT_FILE_NAME))
+        .find(|project_file| project_file.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scopes() {
+        let dir = std::env::temp_dir().join(format!("til-lsp-workspace-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(
+            dir.join(PROJECT_FILE_NAME),
+            "name = \"proj\"\nfiles = [\"src/a.til\", \"src/b.til\"]\noutput_path = \"out\"\n[lints]\nunused-declaration = \"allow\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("src/a.til"),
+            "namespace a { type byte = Bits(8); }",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("src/b.til"),
+            "namespace b { import a::byte; type word = byte; }",
+        )
+        .unwrap();
+        std::fs::write(dir.join("c.til"), "namespace c { type t = Bits(0); }").unwrap();
+        let uri = |path: &str| Url::from_file_path(dir.join(path)).unwrap();
+
+        let mut workspace = Workspace::new();
+        workspace.open(
+            uri("src/b.til"),
+            std::fs::read_to_string(dir.join("src/b.til")).unwrap(),
+        );
+        let diagnostics = workspace.update();
+        // Both files of the project are analysed
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics
+            .iter()
+            .all(|(_, diagnostics)| diagnostics.is_empty()));
+        assert!(workspace.update().is_empty());
+
+        // Open documents take precedence over the files on disk
+        workspace.open(
+            uri("src/a.til"),
+            "namespace a { type bite = Bits(8); }".to_string(),
+        );
+        let diagnostics = workspace.update();
+        let b = diagnostics
+            .iter()
+            .find(|(u, _)| u == &uri("src/b.til"))
+            .unwrap();
+        assert_eq!(b.1.len(), 1);
+        workspace.close(&uri("src/a.til"));
+        let diagnostics = workspace.update();
+        assert!(diagnostics
+            .iter()
+            .all(|(_, diagnostics)| diagnostics.is_empty()));
+
+        // Files outside of the project are analysed on their own
+        workspace.open(
+            uri("c.til"),
+            std::fs::read_to_string(dir.join("c.til")).unwrap(),
+        );
+        let diagnostics = workspace.update();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].0, uri("c.til"));
+        assert_eq!(diagnostics[0].1.len(), 1);
+        assert!(workspace
+            .analysis(&uri("c.til"))
+            .unwrap()
+            .file(&uri("src/a.til"))
+            .is_none());
+        workspace.close(&uri("c.til"));
+        assert_eq!(workspace.update(), vec![(uri("c.til"), vec![])]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
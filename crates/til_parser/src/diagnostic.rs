@@ -0,0 +1,222 @@
+use core::fmt;
+use std::path::{Path, PathBuf};
+
+use chumsky::{error::SimpleReason, prelude::Simple};
+use serde::Serialize;
+
+use crate::{eval::EvalError, lex::Token, Span};
+
+/// How severe a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+/// A message attached to a span of the source.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Label {
+    span: Span,
+    message: String,
+}
+
+impl Label {
+    pub fn new(span: Span, message: impl Into<String>) -> Self {
+        Label {
+            span,
+            message: message.into(),
+        }
+    }
+
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+}
+
+/// An error or warning produced while parsing or evaluating a file.
+///
+/// Spans are character offsets into the source of the file. The first label
+/// is the primary label, which usually covers the span of the diagnostic
+/// itself.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Diagnostic {
+    severity: Severity,
+    message: String,
+    file: Option<PathBuf>,
+    span: Span,
+    labels: Vec<Label>,
+    notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, message: impl Into<String>, span: Span) -> Self {
+        Diagnostic {
+            severity,
+            message: message.into(),
+            file: None,
+            span,
+            labels: vec![],
+            notes: vec![],
+        }
+    }
+
+    pub fn error(message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Error, message, span)
+    }
+
+    pub fn warning(message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Warning, message, span)
+    }
+
+    pub fn with_file(mut self, file: impl Into<PathBuf>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label::new(span, message));
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+
+    /// The file the diagnostic refers to, if known.
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    pub fn labels(&self) -> &[Label] {
+        self.labels.as_ref()
+    }
+
+    pub fn notes(&self) -> &[String] {
+        self.notes.as_ref()
+    }
+
+    fn from_simple(err: Simple<String>) -> Self {
+        let found = err
+            .found()
+            .cloned()
+            .unwrap_or_else(|| "end of file".to_string());
+        match err.reason() {
+            SimpleReason::Unclosed { span, delimiter } => {
+                Diagnostic::error(format!("Unclosed delimiter {}", delimiter), err.span())
+                    .with_label(err.span(), format!("Must be closed before this {}", found))
+                    .with_label(span.clone(), format!("Unclosed delimiter {}", delimiter))
+            }
+            SimpleReason::Unexpected => {
+                let expected = if err.expected().len() == 0 {
+                    "something else".to_string()
+                } else {
+                    err.expected()
+                        .map(|expected| match expected {
+                            Some(expected) => expected.to_string(),
+                            None => "end of input".to_string(),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                let message = format!(
+                    "{}, expected {}",
+                    if err.found().is_some() {
+                        "Unexpected token in input"
+                    } else {
+                        "Unexpected end of input"
+                    },
+                    expected
+                );
+                Diagnostic::error(message, err.span())
+                    .with_label(err.span(), format!("Unexpected token {}", found))
+            }
+            SimpleReason::Custom(msg) => {
+                Diagnostic::error(msg, err.span()).with_label(err.span(), msg)
+            }
+        }
+    }
+}
+
+impl From<Simple<char>> for Diagnostic {
+    fn from(err: Simple<char>) -> Self {
+        Self::from_simple(err.map(|c| c.to_string()))
+    }
+}
+
+impl From<Simple<Token>> for Diagnostic {
+    fn from(err: Simple<Token>) -> Self {
+        Self::from_simple(err.map(|tok| tok.to_string()))
+    }
+}
+
+impl From<EvalError> for Diagnostic {
+    fn from(err: EvalError) -> Self {
+        Diagnostic::error(err.msg(), err.span()).with_label(err.span(), err.msg())
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity(), self.message())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use crate::lex::lexer;
+
+    use super::*;
+
+    #[test]
+    fn from_errors() {
+        let diagnostic = Diagnostic::from(EvalError::new(&(4..8), "Invalid identity"));
+        assert!(diagnostic.is_error());
+        assert_eq!(diagnostic.to_string(), "error: Invalid identity");
+        assert_eq!(diagnostic.labels(), &[Label::new(4..8, "Invalid identity")]);
+
+        let (_, errs) = lexer().parse_recovery("namespace a { \"unclosed }");
+        let diagnostic = Diagnostic::from(errs.into_iter().next().unwrap());
+        assert!(diagnostic
+            .message()
+            .starts_with("Unexpected end of input, expected"));
+        assert_eq!(
+            diagnostic.labels()[0].message(),
+            "Unexpected token end of file"
+        );
+    }
+}
@@ -1,17 +1,12 @@
-use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use std::path::PathBuf;
 
 use til_query::ir::{
-    implementation::Implementation,
-    project::{interface::Interface, type_declaration::TypeDeclaration},
-    streamlet::Streamlet,
-    traits::{GetSelf, InternSelf},
+    generics::{behavioral::BehavioralGenericKind, GenericKind},
+    project::{constant_declaration::ConstantDeclaration, type_declaration::TypeDeclaration},
+    traits::{GetSelf, InternArc, InternSelf},
     Ir,
 };
-use tydi_common::{
-    name::{Name, PathName},
-    traits::Documents,
-};
-use tydi_intern::Id;
+use tydi_common::{map::InsertionOrderedMap, traits::Documents};
 
 use crate::{
     eval::{
@@ -24,22 +19,18 @@ use crate::{
 };
 
 use super::{
-    eval_ident, eval_name, eval_params::eval_generic_params, eval_type::eval_type_expr, EvalError,
+    eval_name,
+    eval_params::{eval_generic_param_assignment, eval_generic_params},
+    eval_shadowing,
+    eval_type::{eval_field_docs, eval_forwarded_default, eval_type_expr},
+    Declarations, EvalContext, EvalError,
 };
 
 pub fn eval_declaration(
     db: &dyn Ir,
     link_root: &PathBuf,
     decl: &Decl,
-    namespace: &PathName,
-    streamlets: &mut HashMap<Name, Id<Arc<Streamlet>>>,
-    streamlet_imports: &HashMap<PathName, Id<Arc<Streamlet>>>,
-    implementations: &mut HashMap<Name, Id<Implementation>>,
-    implementation_imports: &HashMap<PathName, Id<Implementation>>,
-    interfaces: &mut HashMap<Name, Id<Arc<Interface>>>,
-    interface_imports: &HashMap<PathName, Id<Arc<Interface>>>,
-    types: &mut HashMap<Name, TypeDeclaration>,
-    type_imports: &HashMap<PathName, TypeDeclaration>,
+    decls: &mut Declarations,
 ) -> Result<(), EvalError> {
     // As everything is exported (public) by default, shadowing declarations would be confusing
     let dup_id = |n: &String, s: &Span, kind: &str| -> EvalError {
@@ -48,16 +39,20 @@ pub fn eval_declaration(
             msg: format!("Duplicate declaration for {} identity {}", kind, n),
         }
     };
+    let no_params = InsertionOrderedMap::new();
+    let ctx = EvalContext::new(decls, &no_params);
 
     match decl {
-        Decl::TypeDecl((n, s), expr, params) => {
+        Decl::TypeDecl(doc, (n, s), expr, params) => {
             let name = eval_name(n, s)?;
-            let generic_params = eval_generic_params(params)?;
-            let type_id =
-                eval_type_expr(db, (&expr.0, &expr.1), types, type_imports, &generic_params)?;
-            let type_decl = TypeDeclaration::try_new(
+            eval_shadowing(&name, s, &decls.type_imports, "type")?;
+            let generic_params = eval_generic_params(params, |param_name, param_span| {
+                eval_forwarded_default(param_name, param_span, (&expr.0, &expr.1), ctx)
+            })?;
+            let type_id = eval_type_expr(db, (&expr.0, &expr.1), ctx.with_params(&generic_params))?;
+            let mut type_decl = TypeDeclaration::try_new(
                 db,
-                namespace.with_child(&name),
+                decls.namespace.with_child(&name),
                 type_id,
                 generic_params.into_iter().map(|(_, v)| v),
             )
@@ -65,7 +60,18 @@ pub fn eval_declaration(
                 span: s.clone(),
                 msg: format!("Something went wrong declaring type {}: {}", n, err),
             })?;
-            if let Some(_) = types.insert(name, type_decl) {
+            if let Some((doc, _)) = doc {
+                type_decl.set_doc(doc);
+            }
+            for (field, field_doc) in eval_field_docs(&expr.0)? {
+                type_decl
+                    .set_field_doc(field, field_doc)
+                    .map_err(|err| EvalError {
+                        span: expr.1.clone(),
+                        msg: format!("Something went wrong declaring type {}: {}", n, err),
+                    })?;
+            }
+            if let Some(_) = decls.types.insert(name, type_decl) {
                 Err(dup_id(n, s, "type"))
             } else {
                 Ok(())
@@ -73,63 +79,61 @@ pub fn eval_declaration(
         }
         Decl::ImplDecl(doc, (n, s), expr) => {
             let name = eval_name(n, s)?;
+            eval_shadowing(&name, s, &decls.implementation_imports, "implementation")?;
+            eval_shadowing(&name, s, &decls.interface_imports, "interface")?;
             let (impl_id, interface_id) = match &expr.0 {
                 ImplDefExpr::Identity(ident) => {
-                    let mut implementation = eval_ident(
+                    let mut implementation = ctx.eval_ident(
                         ident,
                         &expr.1,
-                        implementations,
-                        implementation_imports,
+                        &ctx.decls().implementations,
+                        &ctx.decls().implementation_imports,
                         "implementation",
                     )?;
                     if let Some(doc) = doc {
                         implementation = implementation.get(db).with_doc(&doc.0).intern(db);
                     }
-                    let interface =
-                        eval_ident(ident, &expr.1, interfaces, interface_imports, "interface")?;
+                    let interface = ctx.eval_ident(
+                        ident,
+                        &expr.1,
+                        &ctx.decls().interfaces,
+                        &ctx.decls().interface_imports,
+                        "interface",
+                    )?;
                     (implementation, interface)
                 }
                 ImplDefExpr::Def(iface, body) => {
-                    let interface = eval_interface_expr(
-                        db,
-                        iface,
-                        interfaces,
-                        interface_imports,
-                        types,
-                        type_imports,
-                    )?;
+                    let interface = eval_interface_expr(db, iface, ctx)?;
                     eval_implementation_expr(
                         db,
                         link_root,
                         body,
-                        &namespace.with_child(name.clone()),
+                        &decls.namespace.with_child(name.clone()),
                         doc,
                         Some(interface),
-                        streamlets,
-                        streamlet_imports,
-                        implementations,
-                        implementation_imports,
-                        interfaces,
-                        interface_imports,
-                        types,
-                        type_imports,
+                        ctx,
                     )?
                 }
             };
 
-            if let Some(_) = interfaces.insert(name.clone(), interface_id) {
+            if let Some(_) = decls.interfaces.insert(name.clone(), interface_id) {
                 Err(dup_id(n, s, "interface"))
-            } else if let Some(_) = implementations.insert(name, impl_id) {
+            } else if let Some(_) = decls.implementations.insert(name, impl_id) {
                 Err(dup_id(n, s, "implementation"))
             } else {
                 Ok(())
             }
         }
-        Decl::InterfaceDecl((n, s), expr) => {
+        Decl::InterfaceDecl(doc, (n, s), expr) => {
             let name = eval_name(n, s)?;
-            let interface_id =
-                eval_interface_expr(db, expr, interfaces, interface_imports, types, type_imports)?;
-            if let Some(_) = interfaces.insert(name, interface_id) {
+            eval_shadowing(&name, s, &decls.interface_imports, "interface")?;
+            let mut interface_id = eval_interface_expr(db, expr, ctx)?;
+            if let Some((doc, _)) = doc {
+                let mut interface = interface_id.get(db).as_ref().clone();
+                interface.set_doc(doc);
+                interface_id = interface.intern_arc(db);
+            }
+            if let Some(_) = decls.interfaces.insert(name, interface_id) {
                 Err(dup_id(n, s, "interface"))
             } else {
                 Ok(())
@@ -137,29 +141,53 @@ pub fn eval_declaration(
         }
         Decl::StreamletDecl(doc, (n, s), expr) => {
             let name = eval_name(n, s)?;
+            eval_shadowing(&name, s, &decls.streamlet_imports, "streamlet")?;
+            eval_shadowing(&name, s, &decls.interface_imports, "interface")?;
             let (streamlet_id, interface_id) = eval_streamlet_expr(
                 db,
                 link_root,
                 expr,
-                &namespace.with_child(name.clone()),
+                &decls.namespace.with_child(name.clone()),
                 doc,
-                streamlets,
-                streamlet_imports,
-                implementations,
-                implementation_imports,
-                interfaces,
-                interface_imports,
-                types,
-                type_imports,
+                ctx,
             )?;
 
-            if let Some(_) = interfaces.insert(name.clone(), interface_id) {
+            if let Some(_) = decls.interfaces.insert(name.clone(), interface_id) {
                 Err(dup_id(n, s, "interface"))
-            } else if let Some(_) = streamlets.insert(name, streamlet_id) {
+            } else if let Some(_) = decls.streamlets.insert(name, streamlet_id) {
                 Err(dup_id(n, s, "streamlet"))
             } else {
                 Ok(())
             }
         }
+        Decl::ConstDecl(doc, (n, s), (kind, kind_span), value) => {
+            let name = eval_name(n, s)?;
+            eval_shadowing(&name, s, &decls.constant_imports, "constant")?;
+            let kind = match kind {
+                GenericKind::Behavioral(BehavioralGenericKind::Integer(i)) => Ok(i.clone()),
+                GenericKind::Interface(_) => Err(EvalError {
+                    span: kind_span.clone(),
+                    msg: format!(
+                        "Constant {} must be an integer, natural or positive, not a {}",
+                        n, kind
+                    ),
+                }),
+            }?;
+            let value = eval_generic_param_assignment(value, ctx)?;
+            let mut constant =
+                ConstantDeclaration::try_new(decls.namespace.with_child(&name), kind, value)
+                    .map_err(|err| EvalError {
+                        span: s.clone(),
+                        msg: format!("Something went wrong declaring constant {}: {}", n, err),
+                    })?;
+            if let Some((doc, _)) = doc {
+                constant.set_doc(doc);
+            }
+            if let Some(_) = decls.constants.insert(name, constant) {
+                Err(dup_id(n, s, "constant"))
+            } else {
+                Ok(())
+            }
+        }
     }
 }
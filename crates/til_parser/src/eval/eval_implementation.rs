@@ -1,10 +1,9 @@
-use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use std::{path::PathBuf, sync::Arc};
 
 use til_query::ir::{
     connection::InterfaceReference,
     implementation::{link::Link, structure::Structure, Implementation},
-    project::{interface::Interface, type_declaration::TypeDeclaration},
-    streamlet::Streamlet,
+    project::interface::Interface,
     traits::InternSelf,
     Ir,
 };
@@ -16,28 +15,21 @@ use tydi_intern::Id;
 
 use crate::{
     doc_expr::DocExpr,
-    eval::eval_ident,
     impl_expr::ImplBodyExpr,
     struct_parse::{InterfaceParamAssignments, PortSel, StructStat},
     Spanned,
 };
 
 use super::{
-    eval_common_error, eval_name, eval_params::eval_generic_param_assignments_list, EvalError,
+    eval_common_error, eval_name, eval_params::eval_generic_param_assignments_list, EvalContext,
+    EvalError,
 };
 
 pub fn eval_struct_stat(
     db: &dyn Ir,
     stat: &Spanned<StructStat>,
     structure: &mut Structure,
-    streamlets: &HashMap<Name, Id<Arc<Streamlet>>>,
-    streamlet_imports: &HashMap<PathName, Id<Arc<Streamlet>>>,
-    implementations: &HashMap<Name, Id<Implementation>>,
-    implementation_imports: &HashMap<PathName, Id<Implementation>>,
-    interfaces: &HashMap<Name, Id<Arc<Interface>>>,
-    interface_imports: &HashMap<PathName, Id<Arc<Interface>>>,
-    types: &HashMap<Name, TypeDeclaration>,
-    type_imports: &HashMap<PathName, TypeDeclaration>,
+    ctx: EvalContext,
 ) -> Result<(), EvalError> {
     match &stat.0 {
         StructStat::Error => Err(EvalError {
@@ -46,19 +38,7 @@ pub fn eval_struct_stat(
         }),
         StructStat::Documentation(_, sub_stat) => {
             // NOTE: We're not actually doing anything with documentation yet.
-            eval_struct_stat(
-                db,
-                sub_stat,
-                structure,
-                streamlets,
-                streamlet_imports,
-                implementations,
-                implementation_imports,
-                interfaces,
-                interface_imports,
-                types,
-                type_imports,
-            )?;
+            eval_struct_stat(db, sub_stat, structure, ctx)?;
             Ok(())
         }
         StructStat::Instance(
@@ -67,11 +47,11 @@ pub fn eval_struct_stat(
             domain_assignments,
         ) => {
             let name = eval_name(name_string, name_span)?;
-            let streamlet = eval_ident(
+            let streamlet = ctx.eval_ident(
                 ident_expr,
                 ident_span,
-                streamlets,
-                streamlet_imports,
+                &ctx.decls().streamlets,
+                &ctx.decls().streamlet_imports,
                 "streamlet",
             )?;
             match &domain_assignments.0 {
@@ -99,7 +79,7 @@ pub fn eval_struct_stat(
                 InterfaceParamAssignments::JustParams(param_assignments) => {
                     let assignments = eval_generic_param_assignments_list(
                         param_assignments,
-                        structure.interface(db).parameters(),
+                        ctx.with_params(structure.interface(db).parameters()),
                     )?;
                     eval_common_error(
                         structure
@@ -117,7 +97,7 @@ pub fn eval_struct_stat(
                     let name_list = eval_domains(domains)?;
                     let assignments = eval_generic_param_assignments_list(
                         param_assignments,
-                        structure.interface(db).parameters(),
+                        ctx.with_params(structure.interface(db).parameters()),
                     )?;
                     eval_common_error(
                         structure
@@ -180,14 +160,7 @@ pub fn eval_implementation_expr(
     name: &PathName,
     doc: &DocExpr,
     interface: Option<Id<Arc<Interface>>>,
-    streamlets: &HashMap<Name, Id<Arc<Streamlet>>>,
-    streamlet_imports: &HashMap<PathName, Id<Arc<Streamlet>>>,
-    implementations: &HashMap<Name, Id<Implementation>>,
-    implementation_imports: &HashMap<PathName, Id<Implementation>>,
-    interfaces: &HashMap<Name, Id<Arc<Interface>>>,
-    interface_imports: &HashMap<PathName, Id<Arc<Interface>>>,
-    types: &HashMap<Name, TypeDeclaration>,
-    type_imports: &HashMap<PathName, TypeDeclaration>,
+    ctx: EvalContext,
 ) -> Result<(Id<Implementation>, Id<Arc<Interface>>), EvalError> {
     match &expr.0 {
         ImplBodyExpr::Error => Err(EvalError {
@@ -198,19 +171,7 @@ pub fn eval_implementation_expr(
             if let Some(interface) = interface {
                 let mut structure = Structure::new(interface);
                 for stat in struct_stats.iter() {
-                    eval_struct_stat(
-                        db,
-                        stat,
-                        &mut structure,
-                        streamlets,
-                        streamlet_imports,
-                        implementations,
-                        implementation_imports,
-                        interfaces,
-                        interface_imports,
-                        types,
-                        type_imports,
-                    )?;
+                    eval_struct_stat(db, stat, &mut structure, ctx)?;
                 }
                 eval_common_error(structure.validate_connections(db), &expr.1)?;
                 let mut implementation = Implementation::from(structure).with_name(name.clone());
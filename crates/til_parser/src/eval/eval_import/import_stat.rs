@@ -1,17 +1,55 @@
 use tydi_common::{
-    name::{PathName, PathNameSelf},
+    error::Result,
+    name::{Name, PathName, PathNameSelf},
     traits::Identify,
 };
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum ImportStatement {
+    /// Import all declarations of a namespace, referred to by their full name.
     Full(PathName),
+    /// Import a single declaration of a namespace, referred to by its name.
+    Single(PathName, Name),
+    /// Import all declarations of a namespace, referred to by the alias
+    /// followed by their name.
+    Aliased(PathName, Name),
+    /// Import all declarations of a namespace, referred to by their name with
+    /// a prefix.
+    Prefixed(PathName, String),
+    /// Import all declarations of a namespace, referred to by their name.
+    Glob(PathName),
+}
+
+impl ImportStatement {
+    /// The identifier the declaration `name` of the imported namespace is
+    /// referred to by, or None if this statement does not import it.
+    pub fn reference(&self, name: &Name) -> Result<Option<PathName>> {
+        Ok(match self {
+            ImportStatement::Full(namespace) => Some(namespace.with_child(name.clone())),
+            ImportStatement::Single(_, decl) if decl == name => {
+                Some(PathName::new([name.clone()].into_iter()))
+            }
+            ImportStatement::Single(_, _) => None,
+            ImportStatement::Aliased(_, alias) => {
+                Some(PathName::new([alias.clone(), name.clone()].into_iter()))
+            }
+            ImportStatement::Prefixed(_, prefix) => Some(PathName::new(
+                [Name::try_new(format!("{}{}", prefix, name))?].into_iter(),
+            )),
+            ImportStatement::Glob(_) => Some(PathName::new([name.clone()].into_iter())),
+        })
+    }
 }
 
 impl PathNameSelf for ImportStatement {
+    /// The name of the imported namespace.
     fn path_name(&self) -> &PathName {
         match &self {
-            ImportStatement::Full(name) => name,
+            ImportStatement::Full(name)
+            | ImportStatement::Single(name, _)
+            | ImportStatement::Aliased(name, _)
+            | ImportStatement::Prefixed(name, _)
+            | ImportStatement::Glob(name) => name,
         }
     }
 }
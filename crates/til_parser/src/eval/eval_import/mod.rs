@@ -3,7 +3,8 @@ pub mod nodes;
 
 use std::collections::{BTreeMap, HashMap};
 
-use petgraph::{prelude::DiGraph, Graph};
+use petgraph::{algo::tarjan_scc, prelude::DiGraph, Graph};
+use til_query::ir::project::Project;
 use tydi_common::{
     error::Result,
     name::{PathName, PathNameSelf},
@@ -16,61 +17,105 @@ use crate::{
 
 use self::{import_stat::ImportStatement, nodes::NamespaceNode};
 
-use super::EvalError;
-
+use super::{eval_name, EvalError};
+
+/// Build a graph of the namespaces of a project's files, with edges to the
+/// namespaces they import.
+///
+/// `files` contains the parsed namespaces of every file, errors are added to
+/// `eval_errors` at the index of the file they occurred in.
+///
+/// Namespaces may also import the namespaces already declared in `project`,
+/// and the namespaces of projects imported by `project`, by qualified name
+/// (e.g. `common::types`). These have no node in the graph, as they were
+/// evaluated already.
 pub fn build_dependency_graph(
-    namespaces: Vec<Namespace>,
-    eval_errors: &mut Vec<EvalError>,
+    files: Vec<Vec<Namespace>>,
+    project: &Project,
+    eval_errors: &mut [Vec<EvalError>],
 ) -> Result<DiGraph<NamespaceNode, ()>> {
     let mut di_graph: DiGraph<NamespaceNode, ()> = Graph::new();
-    let mut unique_namespaces = HashMap::new();
-
-    for parsed_namespace in namespaces.into_iter() {
-        match PathName::try_new(parsed_namespace.name()) {
-            Ok(namespace_name) => {
-                let curr_span = parsed_namespace.name_span().clone();
-                if let Some(existing_name) =
-                    unique_namespaces.insert(namespace_name.clone(), parsed_namespace)
-                {
-                    eval_errors.push(EvalError::new(
-                        &curr_span,
-                        format!(
-                            "Namespace with name {} was already defined",
-                            &namespace_name,
-                        ),
-                    ));
-                    eval_errors.push(EvalError::new(
-                        existing_name.name_span(),
-                        format!("Previous definition of {}", &namespace_name),
-                    ));
+    // Namespaces in the order they were declared, by file
+    let mut unique_namespaces: Vec<(PathName, usize, Namespace)> = vec![];
+    let mut namespace_idx: HashMap<PathName, usize> = HashMap::new();
+
+    for (file, namespaces) in files.into_iter().enumerate() {
+        for parsed_namespace in namespaces.into_iter() {
+            match PathName::try_new(parsed_namespace.name()) {
+                Ok(namespace_name) => {
+                    let curr_span = parsed_namespace.name_span().clone();
+                    if let Some(existing_idx) = namespace_idx.get(&namespace_name) {
+                        let (_, existing_file, existing_name) = &unique_namespaces[*existing_idx];
+                        eval_errors[file].push(EvalError::new(
+                            &curr_span,
+                            format!(
+                                "Namespace with name {} was already defined",
+                                &namespace_name,
+                            ),
+                        ));
+                        eval_errors[*existing_file].push(EvalError::new(
+                            existing_name.name_span(),
+                            format!("Previous definition of {}", &namespace_name),
+                        ));
+                    } else if project.namespaces().get(&namespace_name).is_some() {
+                        eval_errors[file].push(EvalError::new(
+                            &curr_span,
+                            format!(
+                                "Namespace with name {} was already defined",
+                                &namespace_name,
+                            ),
+                        ));
+                    } else {
+                        namespace_idx.insert(namespace_name.clone(), unique_namespaces.len());
+                        unique_namespaces.push((namespace_name, file, parsed_namespace));
+                    }
                 }
+                Err(err) => eval_errors[file].push(EvalError::new(
+                    parsed_namespace.name_span(),
+                    err.to_string(),
+                )),
             }
-            Err(err) => eval_errors.push(EvalError::new(
-                parsed_namespace.name_span(),
-                err.to_string(),
-            )),
         }
     }
 
-    if eval_errors.len() > 0 {
+    if eval_errors.iter().any(|errors| !errors.is_empty()) {
         return Ok(di_graph);
     }
 
+    let namespace_exists = |path: &PathName| {
+        namespace_idx.contains_key(path)
+            || project.namespaces().get(path).is_some()
+            || project.imported_namespace(path).is_some()
+    };
     let mut namespace_nodes = vec![];
 
-    for (name, namespace) in unique_namespaces.iter() {
+    for (name, file, namespace) in unique_namespaces.iter() {
+        let file = *file;
         let mut imports: BTreeMap<PathName, Vec<Spanned<ImportStatement>>> = BTreeMap::new();
         for stat in namespace.stats() {
             if let (Statement::Import(parsed_import), span) = stat {
                 match eval_import_stat((parsed_import, span)) {
                     Ok(import_stat) => {
+                        // A path which does not refer to a namespace may refer
+                        // to a declaration in a namespace.
+                        let import_stat = match import_stat {
+                            (ImportStatement::Full(path), span)
+                                if path.len() > 1
+                                    && !namespace_exists(&path)
+                                    && namespace_exists(&path.root()) =>
+                            {
+                                let decl = path.last().unwrap().clone();
+                                (ImportStatement::Single(path.root(), decl), span)
+                            }
+                            import_stat => import_stat,
+                        };
                         if import_stat.0.path_name() == name {
-                            eval_errors.push(EvalError::new(
+                            eval_errors[file].push(EvalError::new(
                                 &import_stat.1,
                                 format!("Namespace {} is trying to import itself", name),
                             ))
-                        } else if !unique_namespaces.contains_key(import_stat.0.path_name()) {
-                            eval_errors.push(EvalError::new(
+                        } else if !namespace_exists(import_stat.0.path_name()) {
+                            eval_errors[file].push(EvalError::new(
                                 &import_stat.1,
                                 format!("Namespace {} does not exist", import_stat.0.path_name()),
                             ))
@@ -87,19 +132,20 @@ pub fn build_dependency_graph(
                         }
                     }
                     Err(eval_error) => {
-                        eval_errors.push(eval_error);
+                        eval_errors[file].push(eval_error);
                     }
                 }
             }
         }
         namespace_nodes.push(NamespaceNode {
             name: name.clone(),
+            file,
             imports,
             namespace: namespace.clone(),
         });
     }
 
-    if eval_errors.len() > 0 {
+    if eval_errors.iter().any(|errors| !errors.is_empty()) {
         return Ok(di_graph);
     }
 
@@ -117,7 +163,7 @@ pub fn build_dependency_graph(
         let deps = di_graph[node_idx]
             .imports
             .keys()
-            .map(|dep| *node_ids.get(dep).unwrap())
+            .filter_map(|dep| node_ids.get(dep).copied())
             .collect::<Vec<_>>();
         for dep in deps {
             di_graph.add_edge(node_idx, dep, ());
@@ -130,12 +176,67 @@ pub fn build_dependency_graph(
 pub fn eval_import_stat(
     import_stat: (&Import, &Span),
 ) -> std::result::Result<Spanned<ImportStatement>, EvalError> {
+    let (import_name, import_span) = import_stat.0.path();
+    let name = match PathName::try_new(import_name.iter().map(|(n, _)| n)) {
+        Ok(name) => name,
+        Err(err) => return Err(EvalError::new(import_span, err.to_string())),
+    };
     match &import_stat.0 {
-        Import::FullImport((import_name, import_span)) => {
-            match PathName::try_new(import_name.iter().map(|(n, _)| n)) {
-                Ok(name) => Ok((ImportStatement::Full(name), import_span.clone())),
-                Err(err) => Err(EvalError::new(import_span, err.to_string())),
+        Import::FullImport(_) => Ok((ImportStatement::Full(name), import_span.clone())),
+        Import::AliasedImport(_, (alias, alias_span)) => {
+            let alias = eval_name(alias, alias_span)?;
+            Ok((ImportStatement::Aliased(name, alias), import_span.clone()))
+        }
+        Import::PrefixedImport(_, (prefix, _)) => Ok((
+            ImportStatement::Prefixed(name, prefix.clone()),
+            import_span.clone(),
+        )),
+        Import::GlobImport(_) => Ok((ImportStatement::Glob(name), import_span.clone())),
+    }
+}
+
+/// Find the cycles of namespaces which (indirectly) import themselves, adding
+/// an error for every import statement which is part of a cycle.
+///
+/// Returns the names of the namespaces of every cycle.
+pub fn find_import_cycles(
+    di_graph: &DiGraph<NamespaceNode, ()>,
+    eval_errors: &mut [Vec<EvalError>],
+) -> Vec<Vec<PathName>> {
+    let mut cycles = vec![];
+    for component in tarjan_scc(di_graph) {
+        if component.len() < 2 {
+            continue;
+        }
+        let mut names = component
+            .iter()
+            .map(|idx| di_graph[*idx].path_name().clone())
+            .collect::<Vec<_>>();
+        names.sort();
+        let cycle = names
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        for idx in component.iter() {
+            let node = &di_graph[*idx];
+            for (import, import_stats) in node.imports() {
+                if names.contains(import) {
+                    for (_, span) in import_stats {
+                        eval_errors[node.file].push(EvalError::new(
+                            span,
+                            format!(
+                                "Import of {} by {} is part of a cyclical dependency between namespaces {}",
+                                import,
+                                node.path_name(),
+                                cycle
+                            ),
+                        ));
+                    }
+                }
             }
         }
+        cycles.push(names);
     }
+    cycles
 }
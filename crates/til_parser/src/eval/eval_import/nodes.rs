@@ -12,6 +12,8 @@ use super::import_stat::ImportStatement;
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct NamespaceNode {
     pub name: PathName,
+    /// The index of the file the namespace was declared in.
+    pub file: usize,
     pub imports: BTreeMap<PathName, Vec<Spanned<ImportStatement>>>,
     pub namespace: Namespace,
 }
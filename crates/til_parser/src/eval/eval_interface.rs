@@ -1,46 +1,37 @@
-use std::{
-    collections::{HashMap, HashSet},
-    convert::TryFrom,
-    sync::Arc,
-};
+use std::{collections::HashSet, convert::TryFrom, sync::Arc};
 
 use til_query::{
     common::logical::logicaltype::stream::Stream,
     ir::{
         interface_port::InterfacePort,
-        project::{interface::Interface, type_declaration::TypeDeclaration},
+        project::interface::Interface,
         traits::{GetSelf, InternArc},
         Ir,
     },
 };
-use tydi_common::{
-    error::TryResult,
-    map::{InsertionOrderedSet},
-    name::{Name, PathName},
-    traits::Documents,
-};
+use tydi_common::{error::TryResult, map::InsertionOrderedSet, name::Name, traits::Documents};
 use tydi_intern::Id;
 
 use crate::{
-    eval::eval_ident,
     interface_expr::{InterfaceDef, InterfaceExpr, InterfaceParameters, PortsDef},
     Spanned,
 };
 
-use super::{eval_common_error, eval_name, eval_type::eval_type_expr, EvalError};
+use super::{eval_common_error, eval_name, eval_type::eval_type_expr, EvalContext, EvalError};
 
 pub fn eval_interface_expr(
     db: &dyn Ir,
     expr: &Spanned<InterfaceExpr>,
-    interfaces: &HashMap<Name, Id<Arc<Interface>>>,
-    interface_imports: &HashMap<PathName, Id<Arc<Interface>>>,
-    types: &HashMap<Name, TypeDeclaration>,
-    type_imports: &HashMap<PathName, TypeDeclaration>,
+    ctx: EvalContext,
 ) -> Result<Id<Arc<Interface>>, EvalError> {
     match &expr.0 {
-        InterfaceExpr::Identifier(ident) => {
-            eval_ident(ident, &expr.1, interfaces, interface_imports, "interface")
-        }
+        InterfaceExpr::Identifier(ident) => ctx.eval_ident(
+            ident,
+            &expr.1,
+            &ctx.decls().interfaces,
+            &ctx.decls().interface_imports,
+            "interface",
+        ),
         InterfaceExpr::Definition((iface_def, span)) => match iface_def {
             InterfaceDef::Error => Err(EvalError {
                 span: span.clone(),
@@ -98,9 +89,7 @@ pub fn eval_interface_expr(
                                 eval_type_expr(
                                     db,
                                     (&port_def.props.0.typ.0, &port_def.props.0.typ.1),
-                                    types,
-                                    type_imports,
-                                    result.parameters(),
+                                    ctx.with_params(result.parameters()),
                                 )?
                                 .get(db)
                                 .try_result(),
@@ -166,11 +155,13 @@ fn eval_domains(domains: &Vec<Spanned<String>>) -> Result<InsertionOrderedSet<Na
 #[cfg(test)]
 pub(crate) mod tests {
     use chumsky::{prelude::Simple, Parser, Stream};
-    use til_query::ir::{db::Database, project::type_declaration::TypeDeclaration};
-    use tydi_common::error::TryResult;
+    use til_query::ir::db::Database;
+    use tydi_common::{error::TryResult, map::InsertionOrderedMap, name::PathName};
 
     use crate::{
-        eval::eval_type::tests::test_expr_parse_type, interface_expr::interface_expr, lex::lexer,
+        eval::{eval_type::tests::test_expr_parse_type, Declarations},
+        interface_expr::interface_expr,
+        lex::lexer,
         report::report_errors,
     };
 
@@ -180,8 +171,7 @@ pub(crate) mod tests {
         src: impl Into<String>,
         name: impl TryResult<Name>,
         db: &dyn Ir,
-        types: &HashMap<Name, TypeDeclaration>,
-        interfaces: &mut HashMap<Name, Id<Arc<Interface>>>,
+        decls: &mut Declarations,
     ) {
         let src = src.into();
         let (tokens, mut errs) = lexer().parse_recovery(src.as_str());
@@ -198,13 +188,12 @@ pub(crate) mod tests {
                 match eval_interface_expr(
                     db,
                     &expr,
-                    interfaces,
-                    &HashMap::new(),
-                    types,
-                    &HashMap::new(),
+                    EvalContext::new(decls, &InsertionOrderedMap::new()),
                 ) {
                     Ok(def) => {
-                        interfaces.insert(name.try_result().unwrap(), def.clone());
+                        decls
+                            .interfaces
+                            .insert(name.try_result().unwrap(), def.clone());
                         println!("{}", def.get(db));
                     }
                     Err(e) => errs.push(Simple::custom(e.span, e.msg)),
@@ -222,8 +211,7 @@ pub(crate) mod tests {
     #[test]
     fn test_interface_def() {
         let db = &Database::default();
-        let mut types = HashMap::new();
-        let mut interfaces = HashMap::new();
+        let mut decls = Declarations::new(PathName::new_empty());
         test_expr_parse_type(
             "Stream (
         data: Bits(4),
@@ -237,16 +225,15 @@ pub(crate) mod tests {
     )",
             "a",
             db,
-            &mut types,
+            &mut decls,
         );
-        test_expr_parse_interface("(a: in a, b: out a)", "a", db, &types, &mut interfaces);
+        test_expr_parse_interface("(a: in a, b: out a)", "a", db, &mut decls);
     }
 
     #[test]
     fn test_interface_ref() {
         let db = &Database::default();
-        let types = HashMap::new();
-        let mut interfaces = HashMap::new();
+        let mut decls = Declarations::new(PathName::new_empty());
         test_expr_parse_interface(
             "(a: in Stream (
         data: Bits(4),
@@ -260,17 +247,15 @@ pub(crate) mod tests {
     ))",
             "a",
             db,
-            &types,
-            &mut interfaces,
+            &mut decls,
         );
-        test_expr_parse_interface("a", "b", db, &types, &mut interfaces);
+        test_expr_parse_interface("a", "b", db, &mut decls);
     }
 
     #[test]
     fn test_invalid_interface_def_duplicate() {
         let db = &Database::default();
-        let mut types = HashMap::new();
-        let mut interfaces = HashMap::new();
+        let mut decls = Declarations::new(PathName::new_empty());
         test_expr_parse_type(
             "Stream (
         data: Bits(4),
@@ -284,16 +269,15 @@ pub(crate) mod tests {
     )",
             "a",
             db,
-            &mut types,
+            &mut decls,
         );
-        test_expr_parse_interface("(a: in a, a: out a)", "a", db, &types, &mut interfaces);
+        test_expr_parse_interface("(a: in a, a: out a)", "a", db, &mut decls);
     }
 
     #[test]
     fn test_interface_indirection() {
         let db = &Database::default();
-        let mut types = HashMap::new();
-        let mut interfaces = HashMap::new();
+        let mut decls = Declarations::new(PathName::new_empty());
         test_expr_parse_type(
             "Stream (
         data: Bits(4),
@@ -307,13 +291,13 @@ pub(crate) mod tests {
     )",
             "a",
             db,
-            &mut types,
+            &mut decls,
         );
-        test_expr_parse_interface("(a: in a)", "a", db, &types, &mut interfaces);
-        test_expr_parse_interface("a", "b", db, &types, &mut interfaces);
+        test_expr_parse_interface("(a: in a)", "a", db, &mut decls);
+        test_expr_parse_interface("a", "b", db, &mut decls);
         assert_eq!(
-            interfaces.get(&Name::try_new("a").unwrap()),
-            interfaces.get(&Name::try_new("b").unwrap()),
+            decls.interfaces.get(&Name::try_new("a").unwrap()),
+            decls.interfaces.get(&Name::try_new("b").unwrap()),
         )
     }
 }
@@ -7,18 +7,24 @@ use til_query::ir::generics::{
 };
 use tydi_common::{
     map::InsertionOrderedMap,
-    name::{Name, NameSelf},
+    name::{Name, NameSelf, PathName},
 };
 
 use crate::{
-    generic_param::{GenericParameterAssignments, GenericParameterList, GenericParameterValueExpr},
+    generic_param::{
+        GenericParameterAssignments, GenericParameterList, GenericParameterValueExpr,
+        TypeParameterDef,
+    },
     Span, Spanned,
 };
 
-use super::EvalError;
+use super::{eval_import, EvalContext, EvalError};
 
+/// Evaluate the parameters of a type declaration. `forwarded_default`
+/// determines the default value of parameters which were declared without one.
 pub fn eval_generic_params(
     expr: &Spanned<GenericParameterList>,
+    forwarded_default: impl Fn(&Name, &Span) -> Result<GenericParamValue, EvalError>,
 ) -> Result<InsertionOrderedMap<Name, GenericParameter>, EvalError> {
     match &expr.0 {
         GenericParameterList::None => Ok(InsertionOrderedMap::new()),
@@ -29,12 +35,21 @@ pub fn eval_generic_params(
         GenericParameterList::List(params) => {
             let params = params
                 .iter()
-                .map(|(param, span)| match param {
-                    Ok(param) => Ok((param.clone(), span.clone())),
-                    Err(err) => Err(EvalError {
+                .map(|(param, span)| {
+                    let param_err = |err| EvalError {
                         span: span.clone(),
                         msg: format!("There was an issue with a parameter: {}", err),
-                    }),
+                    };
+                    match param {
+                        Ok(TypeParameterDef::Parameter(param)) => Ok((param.clone(), span.clone())),
+                        Ok(TypeParameterDef::Forwarded(name, kind)) => {
+                            let default_value = forwarded_default(name, span)?;
+                            GenericParameter::try_new(name.clone(), kind.clone(), default_value)
+                                .map(|param| (param, span.clone()))
+                                .map_err(param_err)
+                        }
+                        Err(err) => Err(param_err(err.clone())),
+                    }
                 })
                 .collect::<Result<Vec<_>, EvalError>>()?;
             let mut map = InsertionOrderedMap::new();
@@ -54,7 +69,7 @@ pub fn eval_generic_params(
 pub fn eval_generic_param_value(
     expr: &GenericParameterValueExpr,
     expr_span: &Span,
-    parent_params: &InsertionOrderedMap<Name, GenericParameter>,
+    ctx: EvalContext,
 ) -> Result<GenericParamValue, EvalError> {
     let err_map = |e| EvalError {
         span: expr_span.clone(),
@@ -67,15 +82,27 @@ pub fn eval_generic_param_value(
         }),
         GenericParameterValueExpr::Integer(i) => Ok(GenericParamValue::Integer(*i)),
         GenericParameterValueExpr::Ref(r) => {
-            if let Some(p) = parent_params.get(r) {
+            // Parameters take precedence over constants
+            let reference = PathName::new([r.clone()].into_iter());
+            if let Some(p) = ctx.params().get(r) {
                 Ok(GenericParamValue::from(p))
+            } else if let Some(c) = ctx.decls().constants.get(r) {
+                Ok(GenericParamValue::from(c))
+            } else if !ctx.decls().constant_imports.get(&reference).is_empty() {
+                eval_import(
+                    &reference,
+                    expr_span,
+                    &ctx.decls().constant_imports,
+                    "constant",
+                )
+                .map(|(_, c)| GenericParamValue::from(&c))
             } else {
                 Err(EvalError {
                     span: expr_span.clone(),
                     msg: format!(
-                        "No parameter {} exists on the parent. Parameters are: {}",
+                        "No parameter or constant {} exists. Parameters are: {}",
                         r,
-                        parent_params
+                        ctx.params()
                             .keys()
                             .map(|k| k.to_string())
                             .collect::<Vec<String>>()
@@ -84,20 +111,22 @@ pub fn eval_generic_param_value(
                 })
             }
         }
+        GenericParameterValueExpr::PathRef(r) => {
+            eval_import(r, expr_span, &ctx.decls().constant_imports, "constant")
+                .map(|(_, c)| GenericParamValue::from(&c))
+        }
         GenericParameterValueExpr::Combination(l, op, r) => MathCombination::Combination(
-            Box::new(eval_generic_param_assignment(l, parent_params)?),
+            Box::new(eval_generic_param_assignment(l, ctx)?),
             *op,
-            Box::new(eval_generic_param_assignment(r, parent_params)?),
+            Box::new(eval_generic_param_assignment(r, ctx)?),
         )
         .verify_integer()
         .map(|x| GenericParamValue::from(x))
         .map_err(err_map),
-        GenericParameterValueExpr::Parentheses(p) => {
-            eval_generic_param_assignment(p, parent_params)?
-                .try_add_parens()
-                .map_err(err_map)
-        }
-        GenericParameterValueExpr::Negative(n) => eval_generic_param_assignment(n, parent_params)?
+        GenericParameterValueExpr::Parentheses(p) => eval_generic_param_assignment(p, ctx)?
+            .try_add_parens()
+            .map_err(err_map),
+        GenericParameterValueExpr::Negative(n) => eval_generic_param_assignment(n, ctx)?
             .g_negative()
             .map(|x| GenericParamValue::from(x))
             .map_err(err_map),
@@ -106,20 +135,20 @@ pub fn eval_generic_param_value(
 
 pub fn eval_generic_param_assignment(
     expr: &Spanned<GenericParameterValueExpr>,
-    parent_params: &InsertionOrderedMap<Name, GenericParameter>,
+    ctx: EvalContext,
 ) -> Result<GenericParamValue, EvalError> {
-    eval_generic_param_value(&expr.0, &expr.1, parent_params)
+    eval_generic_param_value(&expr.0, &expr.1, ctx)
 }
 
 pub fn eval_generic_param_assignments_list(
     list: &Vec<(Option<Name>, Spanned<GenericParameterValueExpr>)>,
-    parent_params: &InsertionOrderedMap<Name, GenericParameter>,
+    ctx: EvalContext,
 ) -> Result<Vec<(Option<Name>, GenericParamValue)>, EvalError> {
     list.iter()
         .map(|(opt_name, res_val)| {
             Ok((
                 opt_name.clone(),
-                eval_generic_param_assignment(res_val, parent_params)?,
+                eval_generic_param_assignment(res_val, ctx)?,
             ))
         })
         .collect::<Result<Vec<_>, EvalError>>()
@@ -127,7 +156,7 @@ pub fn eval_generic_param_assignments_list(
 
 pub fn eval_generic_param_assignments(
     expr: &Spanned<GenericParameterAssignments>,
-    parent_params: &InsertionOrderedMap<Name, GenericParameter>,
+    ctx: EvalContext,
 ) -> Result<Vec<(Option<Name>, GenericParamValue)>, EvalError> {
     match &expr.0 {
         GenericParameterAssignments::Error => Err(EvalError {
@@ -135,7 +164,7 @@ pub fn eval_generic_param_assignments(
             msg: "There's an issue with the parameter assignments".to_string(),
         }),
         GenericParameterAssignments::List(assignments) => {
-            eval_generic_param_assignments_list(assignments, parent_params)
+            eval_generic_param_assignments_list(assignments, ctx)
         }
     }
 }
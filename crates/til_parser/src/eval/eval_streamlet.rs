@@ -1,16 +1,12 @@
-use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use std::{path::PathBuf, sync::Arc};
 
 use til_query::ir::{
-    implementation::Implementation,
-    project::{interface::Interface, type_declaration::TypeDeclaration},
+    project::interface::Interface,
     streamlet::Streamlet,
     traits::{GetSelf, InternArc},
     Ir,
 };
-use tydi_common::{
-    name::{Name, PathName},
-    traits::Documents,
-};
+use tydi_common::{name::PathName, traits::Documents};
 use tydi_intern::Id;
 
 use crate::{
@@ -20,7 +16,7 @@ use crate::{
     Spanned,
 };
 
-use super::{eval_ident, eval_interface::eval_interface_expr, EvalError};
+use super::{eval_interface::eval_interface_expr, EvalContext, EvalError};
 
 pub fn eval_streamlet_expr(
     db: &dyn Ir,
@@ -28,28 +24,37 @@ pub fn eval_streamlet_expr(
     expr: &Spanned<Expr>,
     name: &PathName,
     doc: &Option<String>,
-    streamlets: &HashMap<Name, Id<Arc<Streamlet>>>,
-    streamlet_imports: &HashMap<PathName, Id<Arc<Streamlet>>>,
-    implementations: &HashMap<Name, Id<Implementation>>,
-    implementation_imports: &HashMap<PathName, Id<Implementation>>,
-    interfaces: &HashMap<Name, Id<Arc<Interface>>>,
-    interface_imports: &HashMap<PathName, Id<Arc<Interface>>>,
-    types: &HashMap<Name, TypeDeclaration>,
-    type_imports: &HashMap<PathName, TypeDeclaration>,
+    ctx: EvalContext,
 ) -> Result<(Id<Arc<Streamlet>>, Id<Arc<Interface>>), EvalError> {
     match &expr.0 {
         Expr::Ident(ident) => {
-            if let Ok(val) = eval_ident(ident, &expr.1, streamlets, streamlet_imports, "streamlet")
-            {
-                let interface =
-                    eval_ident(ident, &expr.1, interfaces, interface_imports, "interface")?;
+            if let Ok(val) = ctx.eval_ident(
+                ident,
+                &expr.1,
+                &ctx.decls().streamlets,
+                &ctx.decls().streamlet_imports,
+                "streamlet",
+            ) {
+                let interface = ctx.eval_ident(
+                    ident,
+                    &expr.1,
+                    &ctx.decls().interfaces,
+                    &ctx.decls().interface_imports,
+                    "interface",
+                )?;
                 let mut streamlet = val.get(db).as_ref().clone().with_name(name.clone());
                 if let Some(doc) = doc {
                     streamlet.set_doc(doc);
                 }
                 Ok((streamlet.intern_arc(db), interface))
             } else {
-                match eval_ident(ident, &expr.1, interfaces, interface_imports, "streamlet") {
+                match ctx.eval_ident(
+                    ident,
+                    &expr.1,
+                    &ctx.decls().interfaces,
+                    &ctx.decls().interface_imports,
+                    "streamlet",
+                ) {
                     Ok(interface) => {
                         let mut streamlet: Streamlet = interface.into();
                         if let Some(doc) = doc {
@@ -65,14 +70,7 @@ pub fn eval_streamlet_expr(
             }
         }
         Expr::StreamletDef(interface, properties) => {
-            let interface = eval_interface_expr(
-                db,
-                interface,
-                interfaces,
-                interface_imports,
-                types,
-                type_imports,
-            )?;
+            let interface = eval_interface_expr(db, interface, ctx)?;
             let mut streamlet = Streamlet::from(interface);
             if let Some(doc) = doc {
                 streamlet.set_doc(doc);
@@ -87,18 +85,18 @@ pub fn eval_streamlet_expr(
                                     if implementation == None {
                                         implementation = Some(match &impl_expr.0 {
                                             StreamletImplExpr::Identity(ident) => {
-                                                let implementation = eval_ident(
+                                                let implementation = ctx.eval_ident(
                                                     ident,
                                                     &expr.1,
-                                                    implementations,
-                                                    implementation_imports,
+                                                    &ctx.decls().implementations,
+                                                    &ctx.decls().implementation_imports,
                                                     "implementation",
                                                 )?;
-                                                let interface = eval_ident(
+                                                let interface = ctx.eval_ident(
                                                     ident,
                                                     &expr.1,
-                                                    interfaces,
-                                                    interface_imports,
+                                                    &ctx.decls().interfaces,
+                                                    &ctx.decls().interface_imports,
                                                     "interface",
                                                 )?;
                                                 (implementation, interface)
@@ -111,14 +109,7 @@ pub fn eval_streamlet_expr(
                                                     name,
                                                     &None,
                                                     Some(interface),
-                                                    streamlets,
-                                                    streamlet_imports,
-                                                    implementations,
-                                                    implementation_imports,
-                                                    interfaces,
-                                                    interface_imports,
-                                                    types,
-                                                    type_imports,
+                                                    ctx,
                                                 )?
                                             }
                                         });
@@ -1,26 +1,18 @@
-use std::{
-    collections::{HashMap, HashSet},
-    convert::TryFrom,
-    str::FromStr,
-};
+use std::{collections::HashSet, convert::TryFrom, str::FromStr};
 
 use til_query::{
     common::{
         logical::logicaltype::{
             genericproperty::GenericProperty,
-            stream::{Stream, Synchronicity, Throughput},
+            stream::{FromGenericValue, Stream, StreamProperty, Synchronicity, Throughput},
             LogicalType,
         },
         physical::complexity::Complexity,
         stream_direction::StreamDirection,
     },
-    ir::{
-        generics::GenericParameter, project::type_declaration::TypeDeclaration, traits::InternSelf,
-        Ir,
-    },
+    ir::{generics::param_value::GenericParamValue, traits::InternSelf, Ir},
 };
 use tydi_common::{
-    map::InsertionOrderedMap,
     name::{Name, PathName},
     numbers::NonNegative,
 };
@@ -28,34 +20,95 @@ use tydi_intern::Id;
 
 use crate::{
     expr::Value,
-    type_expr::{FieldsDef, LogicalTypeDef, StreamProp, StreamProps, TypeExpr},
+    generic_param::{GenericParameterAssignments, GenericParameterValueExpr},
+    type_expr::{
+        FieldsDef, LogicalTypeDef, StreamProp, StreamPropValue, StreamProps, TypeExpr, VariantsDef,
+    },
     Span, Spanned,
 };
 
 use super::{
-    eval_common_error, eval_ident, eval_name,
+    eval_common_error, eval_name,
     eval_params::{eval_generic_param_assignments, eval_generic_param_value},
-    EvalError,
+    EvalContext, EvalError,
 };
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct StreamTypeDef {
     data: Option<Id<LogicalType>>,
-    throughput: Option<Throughput>,
+    throughput: Option<StreamProperty<Throughput>>,
     dimensionality: Option<GenericProperty<NonNegative>>,
     synchronicity: Option<Synchronicity>,
-    complexity: Option<Complexity>,
+    complexity: Option<StreamProperty<Complexity>>,
     direction: Option<StreamDirection>,
     user: Option<Id<LogicalType>>,
     keep: Option<bool>,
 }
 
+/// Evaluate a Stream property which may depend on generic parameters.
+fn eval_stream_property<T: FromGenericValue + Clone>(
+    label: &str,
+    expr: &Spanned<GenericParameterValueExpr>,
+    span: &Span,
+    ctx: EvalContext,
+) -> Result<StreamProperty<T>, EvalError> {
+    let val = eval_generic_param_value(&expr.0, &expr.1, ctx)?;
+    GenericProperty::try_from(val)
+        .and_then(StreamProperty::try_new)
+        .map_err(|e| EvalError {
+            span: span.clone(),
+            msg: format!("Unable to assign to {}: {}", label, e),
+        })
+}
+
+/// Determine the default value of a type parameter which was declared without
+/// one, from the parameter of the referenced type it is forwarded to. E.g., `d`
+/// in `type narrow<d: dimensionality> = wide<d, 8>;` takes on the default
+/// value of the first parameter of `wide`.
+pub fn eval_forwarded_default(
+    name: &Name,
+    span: &Span,
+    expr: (&TypeExpr, &Span),
+    ctx: EvalContext,
+) -> Result<GenericParamValue, EvalError> {
+    if let TypeExpr::Assigned(ident, (GenericParameterAssignments::List(assignments), _)) = expr.0 {
+        let forwarded = GenericParameterValueExpr::Ref(name.clone());
+        if let Some(position) = assignments
+            .iter()
+            .position(|(_, (value, _))| value == &forwarded)
+        {
+            return ctx
+                .eval_ident(
+                    ident,
+                    expr.1,
+                    &ctx.decls().types,
+                    &ctx.decls().type_imports,
+                    "type",
+                )?
+                .assigned_parameter(position, assignments[position].0.as_ref())
+                .map(|param| param.default_value().clone())
+                .map_err(|err| EvalError {
+                    span: span.clone(),
+                    msg: format!(
+                        "Unable to determine the default value of parameter {}: {}",
+                        name, err
+                    ),
+                });
+        }
+    }
+    Err(EvalError {
+        span: span.clone(),
+        msg: format!(
+            "Parameter {} has no default value, and is not forwarded to a parameter of the referenced type",
+            name
+        ),
+    })
+}
+
 pub fn eval_type_expr(
     db: &dyn Ir,
     expr: (&TypeExpr, &Span),
-    types: &HashMap<Name, TypeDeclaration>,
-    type_imports: &HashMap<PathName, TypeDeclaration>,
-    parent_params: &InsertionOrderedMap<Name, GenericParameter>,
+    ctx: EvalContext,
 ) -> Result<Id<LogicalType>, EvalError> {
     let eval_fields =
         |fields: &Spanned<FieldsDef>| -> Result<Vec<(Name, Id<LogicalType>)>, EvalError> {
@@ -67,7 +120,7 @@ pub fn eval_type_expr(
                 FieldsDef::Fields(field_list) => {
                     let mut dups = HashSet::new();
                     let mut result = vec![];
-                    for ((name_string, name_span), el_expr) in field_list {
+                    for (_, (name_string, name_span), el_expr) in field_list {
                         let name = eval_name(name_string, name_span)?;
                         if dups.contains(&name) {
                             return Err(EvalError {
@@ -76,16 +129,7 @@ pub fn eval_type_expr(
                             });
                         } else {
                             dups.insert(name.clone());
-                            result.push((
-                                name,
-                                eval_type_expr(
-                                    db,
-                                    (&el_expr.0, &el_expr.1),
-                                    types,
-                                    type_imports,
-                                    parent_params,
-                                )?,
-                            ));
+                            result.push((name, eval_type_expr(db, (&el_expr.0, &el_expr.1), ctx)?));
                         }
                     }
                     Ok(result)
@@ -144,34 +188,39 @@ pub fn eval_type_expr(
                     match &prop.0 {
                         StreamProp::Data(t) => {
                             if stream.data == None {
-                                stream.data = Some(eval_type_expr(
-                                    db,
-                                    (&t.0, &t.1),
-                                    types,
-                                    type_imports,
-                                    parent_params,
-                                )?)
+                                stream.data = Some(eval_type_expr(db, (&t.0, &t.1), ctx)?)
                             } else {
                                 return duplicate_error("data", &prop.1);
                             }
                         }
                         StreamProp::Throughput(v) => {
                             if stream.throughput == None {
-                                match &v.0 {
-                                    Value::NonNegative(i) => match Throughput::try_new(*i) {
-                                        Ok(t) => {
-                                            stream.throughput = Some(t);
-                                        }
-                                        Err(err) => {
-                                            return custom_error(
-                                                "throughput",
-                                                &prop.1,
-                                                err.to_string(),
-                                            )
+                                match v {
+                                    StreamPropValue::Generic(g) => {
+                                        stream.throughput = Some(eval_stream_property(
+                                            "throughput",
+                                            g,
+                                            &prop.1,
+                                            ctx,
+                                        )?)
+                                    }
+                                    StreamPropValue::Value((Value::NonNegative(i), _)) => {
+                                        match Throughput::try_new(*i) {
+                                            Ok(t) => {
+                                                stream.throughput = Some(t.into());
+                                            }
+                                            Err(err) => {
+                                                return custom_error(
+                                                    "throughput",
+                                                    &prop.1,
+                                                    err.to_string(),
+                                                )
+                                            }
                                         }
-                                    },
-                                    Value::PositiveReal(f) => {
-                                        stream.throughput = Some(f.positive_real().into())
+                                    }
+                                    StreamPropValue::Value((Value::PositiveReal(f), _)) => {
+                                        stream.throughput =
+                                            Some(Throughput::from(f.positive_real()).into())
                                     }
                                     _ => return invalid_prop("throughput", prop),
                                 }
@@ -181,7 +230,7 @@ pub fn eval_type_expr(
                         }
                         StreamProp::Dimensionality(g) => {
                             if stream.dimensionality == None {
-                                let val = eval_generic_param_value(&g.0, &g.1, &parent_params)?;
+                                let val = eval_generic_param_value(&g.0, &g.1, ctx)?;
                                 stream.dimensionality =
                                     Some(GenericProperty::try_from(val).map_err(|e| {
                                         EvalError {
@@ -209,14 +258,22 @@ pub fn eval_type_expr(
                         }
                         StreamProp::Complexity(v) => {
                             if stream.complexity == None {
-                                match &v.0 {
-                                    Value::NonNegative(i) => {
-                                        stream.complexity = Some(Complexity::from(*i))
+                                match v {
+                                    StreamPropValue::Generic(g) => {
+                                        stream.complexity = Some(eval_stream_property(
+                                            "complexity",
+                                            g,
+                                            &prop.1,
+                                            ctx,
+                                        )?)
+                                    }
+                                    StreamPropValue::Value((Value::NonNegative(i), _)) => {
+                                        stream.complexity = Some(Complexity::from(*i).into())
                                     }
-                                    Value::PositiveReal(f) => {
+                                    StreamPropValue::Value((Value::PositiveReal(f), _)) => {
                                         match Complexity::try_from(f.positive_real()) {
                                             Ok(c) => {
-                                                stream.complexity = Some(c);
+                                                stream.complexity = Some(c.into());
                                             }
                                             Err(err) => {
                                                 return custom_error(
@@ -227,10 +284,10 @@ pub fn eval_type_expr(
                                             }
                                         }
                                     }
-                                    Value::Version(ver) => {
+                                    StreamPropValue::Value((Value::Version(ver), _)) => {
                                         match Complexity::from_str(ver.as_str()) {
                                             Ok(c) => {
-                                                stream.complexity = Some(c);
+                                                stream.complexity = Some(c.into());
                                             }
                                             Err(err) => {
                                                 return custom_error(
@@ -260,13 +317,7 @@ pub fn eval_type_expr(
                         }
                         StreamProp::User(t) => {
                             if stream.user == None {
-                                stream.user = Some(eval_type_expr(
-                                    db,
-                                    (&t.0, &t.1),
-                                    types,
-                                    type_imports,
-                                    parent_params,
-                                )?)
+                                stream.user = Some(eval_type_expr(db, (&t.0, &t.1), ctx)?)
                             } else {
                                 return duplicate_error("user", &prop.1);
                             }
@@ -297,7 +348,9 @@ pub fn eval_type_expr(
                     Stream::try_new(
                         db,
                         stream.data.ok_or(missing_err("data"))?,
-                        stream.throughput.unwrap_or_default(),
+                        stream
+                            .throughput
+                            .unwrap_or_else(|| Throughput::default().into()),
                         stream.dimensionality.ok_or(missing_err("dimensionality"))?,
                         stream.synchronicity.ok_or(missing_err("synchronicity"))?,
                         stream.complexity.ok_or(missing_err("complexity"))?,
@@ -316,7 +369,14 @@ pub fn eval_type_expr(
             span: expr.1.clone(),
             msg: format!("Invalid expression {:#?} for type definition", &expr.0),
         }),
-        TypeExpr::Identifier(ident) => eval_ident(ident, &expr.1, types, type_imports, "type")?
+        TypeExpr::Identifier(ident) => ctx
+            .eval_ident(
+                ident,
+                &expr.1,
+                &ctx.decls().types,
+                &ctx.decls().type_imports,
+                "type",
+            )?
             .type_id(db)
             .map_err(|err| EvalError {
                 span: expr.1.clone(),
@@ -324,15 +384,15 @@ pub fn eval_type_expr(
             }),
         TypeExpr::Definition(typ_def) => match &typ_def.as_ref().0 {
             LogicalTypeDef::Null => Ok(LogicalType::null_id(db)),
-            LogicalTypeDef::Bits((num, num_span)) => {
-                if let Ok(p) = num.parse() {
-                    Ok(LogicalType::Bits(p).intern(db))
-                } else {
-                    Err(EvalError {
-                        span: num_span.clone(),
-                        msg: format!("{} is not a positive integer", num),
+            LogicalTypeDef::Bits((width, width_span)) => {
+                let val = eval_generic_param_value(width, width_span, ctx)?;
+                GenericProperty::try_from(val)
+                    .and_then(LogicalType::try_new_generic_bits)
+                    .map(|bits| bits.intern(db))
+                    .map_err(|err| EvalError {
+                        span: width_span.clone(),
+                        msg: format!("Invalid width for Bits: {}", err),
                     })
-                }
             }
             LogicalTypeDef::Group(fields) => Ok(eval_common_error(
                 LogicalType::try_new_group(None, eval_fields(fields)?),
@@ -344,13 +404,66 @@ pub fn eval_type_expr(
                 &expr.1,
             )?
             .intern(db)),
+            LogicalTypeDef::Enum((variants, variants_span)) => match variants {
+                VariantsDef::Error => Err(EvalError {
+                    span: variants_span.clone(),
+                    msg: "Invalid variants expression.".to_string(),
+                }),
+                VariantsDef::Variants(variants) if variants.is_empty() => Err(EvalError {
+                    span: variants_span.clone(),
+                    msg: "An Enum requires at least one variant.".to_string(),
+                }),
+                VariantsDef::Variants(variants) => {
+                    let mut dups = HashSet::new();
+                    let mut fields = vec![];
+                    for (_, (variant_string, variant_span)) in variants {
+                        let variant = eval_name(variant_string, variant_span)?;
+                        if !dups.insert(variant.clone()) {
+                            return Err(EvalError {
+                                span: variant_span.clone(),
+                                msg: format!("Duplicate variant in Enum, \"{}\"", variant),
+                            });
+                        }
+                        fields.push((variant, LogicalType::null_id(db)));
+                    }
+                    Ok(
+                        eval_common_error(LogicalType::try_new_union(None, fields), &expr.1)?
+                            .intern(db),
+                    )
+                }
+            },
+            LogicalTypeDef::Array((element, element_span), (count, count_span)) => {
+                let element = eval_type_expr(db, (element, element_span), ctx)?;
+                let count_value = eval_generic_param_value(count, count_span, ctx)?;
+                if let GenericParamValue::Integer(i) = count_value.reduce().remove_outer_parens() {
+                    if i <= 0 {
+                        return Err(EvalError {
+                            span: count_span.clone(),
+                            msg: format!("The length of an Array must be positive, not {}", i),
+                        });
+                    }
+                }
+                GenericProperty::try_from(count_value)
+                    .and_then(|count| LogicalType::try_new_generic_array(element, count))
+                    .map(|array| array.intern(db))
+                    .map_err(|err| EvalError {
+                        span: count_span.clone(),
+                        msg: format!("Invalid length for Array: {}", err),
+                    })
+            }
             LogicalTypeDef::Stream(props) => {
                 Ok(LogicalType::Stream(eval_stream(&props.1, &props.0)?).intern(db))
             }
         },
         TypeExpr::Assigned(ident, assignments) => {
-            let ident_typ = eval_ident(ident, &expr.1, types, type_imports, "type")?;
-            let parameter_assignments = eval_generic_param_assignments(assignments, parent_params)?;
+            let ident_typ = ctx.eval_ident(
+                ident,
+                &expr.1,
+                &ctx.decls().types,
+                &ctx.decls().type_imports,
+                "type",
+            )?;
+            let parameter_assignments = eval_generic_param_assignments(assignments, ctx)?;
             ident_typ
                 .with_assignments(parameter_assignments)
                 .map_err(|err| EvalError {
@@ -366,13 +479,48 @@ pub fn eval_type_expr(
     }
 }
 
+/// Collect the documentation of the fields of a type definition, including
+/// the fields of nested Group and Union definitions and the variants of
+/// Enums, by their path.
+pub fn eval_field_docs(expr: &TypeExpr) -> Result<Vec<(PathName, String)>, EvalError> {
+    let mut result = vec![];
+    if let TypeExpr::Definition(def) = expr {
+        match &def.0 {
+            LogicalTypeDef::Group((FieldsDef::Fields(fields), _))
+            | LogicalTypeDef::Union((FieldsDef::Fields(fields), _)) => {
+                for (doc, (name_string, name_span), el_expr) in fields {
+                    let name = PathName::from(eval_name(name_string, name_span)?);
+                    if let Some((doc, _)) = doc {
+                        result.push((name.clone(), doc.clone()));
+                    }
+                    for (path, doc) in eval_field_docs(&el_expr.0)? {
+                        result.push((path.with_parents(name.clone()), doc));
+                    }
+                }
+            }
+            LogicalTypeDef::Enum((VariantsDef::Variants(variants), _)) => {
+                for (doc, (name_string, name_span)) in variants {
+                    if let Some((doc, _)) = doc {
+                        let name = PathName::from(eval_name(name_string, name_span)?);
+                        result.push((name, doc.clone()));
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    Ok(result)
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use chumsky::{prelude::Simple, Parser, Stream};
-    use til_query::ir::{db::Database, traits::GetSelf};
-    use tydi_common::error::TryResult;
+    use til_query::ir::{
+        db::Database, project::type_declaration::TypeDeclaration, traits::GetSelf,
+    };
+    use tydi_common::{error::TryResult, map::InsertionOrderedMap};
 
-    use crate::{lex::lexer, report::report_errors, type_expr::type_expr};
+    use crate::{eval::Declarations, lex::lexer, report::report_errors, type_expr::type_expr};
 
     use super::*;
 
@@ -380,7 +528,7 @@ pub(crate) mod tests {
         src: impl Into<String>,
         name: impl TryResult<Name>,
         db: &dyn Ir,
-        types: &mut HashMap<Name, TypeDeclaration>,
+        decls: &mut Declarations,
     ) {
         let src = src.into();
         let (tokens, mut errs) = lexer().parse_recovery(src.as_str());
@@ -398,12 +546,10 @@ pub(crate) mod tests {
                 match eval_type_expr(
                     db,
                     (&expr.0, &expr.1),
-                    types,
-                    &HashMap::new(),
-                    &InsertionOrderedMap::new(),
+                    EvalContext::new(decls, &InsertionOrderedMap::new()),
                 ) {
                     Ok(def) => {
-                        types.insert(
+                        decls.types.insert(
                             name.clone(),
                             TypeDeclaration::try_new_no_params(db, name, def).unwrap(),
                         );
@@ -424,69 +570,128 @@ pub(crate) mod tests {
     #[test]
     fn test_null_def() {
         let db = &Database::default();
-        let mut types = HashMap::new();
-        test_expr_parse_type("Null", "a", db, &mut types);
+        let mut decls = Declarations::new(PathName::new_empty());
+        test_expr_parse_type("Null", "a", db, &mut decls);
     }
 
     #[test]
     fn test_type_ref() {
         let db = &Database::default();
-        let mut types = HashMap::new();
-        test_expr_parse_type("Null", "a", db, &mut types);
-        test_expr_parse_type("a", "b", db, &mut types);
+        let mut decls = Declarations::new(PathName::new_empty());
+        test_expr_parse_type("Null", "a", db, &mut decls);
+        test_expr_parse_type("a", "b", db, &mut decls);
     }
 
     #[test]
     fn test_bits_def() {
         let db = &Database::default();
-        let mut types = HashMap::new();
-        test_expr_parse_type("Bits(3)", "a", db, &mut types);
+        let mut decls = Declarations::new(PathName::new_empty());
+        test_expr_parse_type("Bits(3)", "a", db, &mut decls);
     }
 
     #[test]
     fn test_bits_invalid_def() {
         let db = &Database::default();
-        let mut types = HashMap::new();
-        test_expr_parse_type("Bits(0)", "a", db, &mut types);
+        let mut decls = Declarations::new(PathName::new_empty());
+        test_expr_parse_type("Bits(0)", "a", db, &mut decls);
     }
 
     #[test]
     fn test_group_def() {
         let db = &Database::default();
-        let mut types = HashMap::new();
-        test_expr_parse_type("Bits(3)", "a", db, &mut types);
-        test_expr_parse_type("Group(a: Bits(1), b: a)", "b", db, &mut types);
+        let mut decls = Declarations::new(PathName::new_empty());
+        test_expr_parse_type("Bits(3)", "a", db, &mut decls);
+        test_expr_parse_type("Group(a: Bits(1), b: a)", "b", db, &mut decls);
     }
 
     #[test]
     fn test_union_def() {
         let db = &Database::default();
-        let mut types = HashMap::new();
-        test_expr_parse_type("Bits(3)", "a", db, &mut types);
-        test_expr_parse_type("Union(a: Bits(1), b: a)", "b", db, &mut types);
+        let mut decls = Declarations::new(PathName::new_empty());
+        test_expr_parse_type("Bits(3)", "a", db, &mut decls);
+        test_expr_parse_type("Union(a: Bits(1), b: a)", "b", db, &mut decls);
     }
 
     #[test]
     fn test_invalid_union_def() {
         let db = &Database::default();
-        let mut types = HashMap::new();
-        test_expr_parse_type("Bits(3)", "a", db, &mut types);
-        test_expr_parse_type("Union(a: Bits(1), a: a)", "b", db, &mut types);
+        let mut decls = Declarations::new(PathName::new_empty());
+        test_expr_parse_type("Bits(3)", "a", db, &mut decls);
+        test_expr_parse_type("Union(a: Bits(1), a: a)", "b", db, &mut decls);
     }
 
     #[test]
     fn test_invalid_union_def_names() {
         let db = &Database::default();
-        let mut types = HashMap::new();
-        test_expr_parse_type("Bits(3)", "a", db, &mut types);
-        test_expr_parse_type("Union(a: Bits(1), b__b: a)", "b", db, &mut types);
+        let mut decls = Declarations::new(PathName::new_empty());
+        test_expr_parse_type("Bits(3)", "a", db, &mut decls);
+        test_expr_parse_type("Union(a: Bits(1), b__b: a)", "b", db, &mut decls);
+    }
+
+    #[test]
+    fn test_enum_def() {
+        let db = &Database::default();
+        let mut decls = Declarations::new(PathName::new_empty());
+        test_expr_parse_type("Enum(add, sub, mul)", "a", db, &mut decls);
+        test_expr_parse_type("Enum(add, add)", "b", db, &mut decls);
+        test_expr_parse_type("Enum()", "c", db, &mut decls);
+        assert_eq!(
+            decls
+                .types
+                .get(&Name::try_new("a").unwrap())
+                .unwrap()
+                .type_id(db)
+                .unwrap(),
+            LogicalType::try_new_union(
+                None,
+                vec![
+                    ("add", LogicalType::null_id(db)),
+                    ("sub", LogicalType::null_id(db)),
+                    ("mul", LogicalType::null_id(db)),
+                ],
+            )
+            .unwrap()
+            .intern(db)
+        );
+        assert!(!decls.types.contains_key(&Name::try_new("b").unwrap()));
+        assert!(!decls.types.contains_key(&Name::try_new("c").unwrap()));
+    }
+
+    #[test]
+    fn test_array_def() {
+        let db = &Database::default();
+        let mut decls = Declarations::new(PathName::new_empty());
+        test_expr_parse_type("Bits(8)", "a", db, &mut decls);
+        test_expr_parse_type("Array(a, 2 * 2)", "b", db, &mut decls);
+        test_expr_parse_type("Array(a, 1 - 1)", "c", db, &mut decls);
+        let a = decls
+            .types
+            .get(&Name::try_new("a").unwrap())
+            .unwrap()
+            .type_id(db)
+            .unwrap();
+        assert_eq!(
+            decls
+                .types
+                .get(&Name::try_new("b").unwrap())
+                .unwrap()
+                .type_id(db)
+                .unwrap(),
+            LogicalType::try_new_group(
+                None,
+                vec![("item0", a), ("item1", a), ("item2", a), ("item3", a)],
+            )
+            .unwrap()
+            .intern(db)
+        );
+        assert!(!decls.types.contains_key(&Name::try_new("c").unwrap()));
     }
 
     #[test]
     fn test_stream_def() {
         let db = &Database::default();
-        let mut types = HashMap::new();
-        test_expr_parse_type("Bits(3)", "a", db, &mut types);
+        let mut decls = Declarations::new(PathName::new_empty());
+        test_expr_parse_type("Bits(3)", "a", db, &mut decls);
         test_expr_parse_type(
             "Stream (
         data: a,
@@ -500,15 +705,15 @@ pub(crate) mod tests {
     )",
             "b",
             db,
-            &mut types,
+            &mut decls,
         );
     }
 
     #[test]
     fn test_invalid_stream_def_duplicate() {
         let db = &Database::default();
-        let mut types = HashMap::new();
-        test_expr_parse_type("Bits(3)", "a", db, &mut types);
+        let mut decls = Declarations::new(PathName::new_empty());
+        test_expr_parse_type("Bits(3)", "a", db, &mut decls);
         test_expr_parse_type(
             "Stream (
         data: a,
@@ -523,15 +728,15 @@ pub(crate) mod tests {
     )",
             "b",
             db,
-            &mut types,
+            &mut decls,
         );
     }
 
     #[test]
     fn test_invalid_stream_def_invalid_property() {
         let db = &Database::default();
-        let mut types = HashMap::new();
-        test_expr_parse_type("Bits(3)", "a", db, &mut types);
+        let mut decls = Declarations::new(PathName::new_empty());
+        test_expr_parse_type("Bits(3)", "a", db, &mut decls);
         test_expr_parse_type(
             "Stream (
         data: a,
@@ -546,28 +751,28 @@ pub(crate) mod tests {
     )",
             "b",
             db,
-            &mut types,
+            &mut decls,
         );
     }
 
     #[test]
     fn test_stream_def_empty() {
         let db = &Database::default();
-        let mut types = HashMap::new();
+        let mut decls = Declarations::new(PathName::new_empty());
         test_expr_parse_type(
             "Stream (
     )",
             "b",
             db,
-            &mut types,
+            &mut decls,
         );
     }
 
     #[test]
     fn test_stream_def_order() {
         let db = &Database::default();
-        let mut types = HashMap::new();
-        test_expr_parse_type("Bits(3)", "a", db, &mut types);
+        let mut decls = Declarations::new(PathName::new_empty());
+        test_expr_parse_type("Bits(3)", "a", db, &mut decls);
         test_expr_parse_type(
             "Stream (
         synchronicity: Sync,
@@ -581,7 +786,7 @@ pub(crate) mod tests {
     )",
             "b",
             db,
-            &mut types,
+            &mut decls,
         );
     }
 }
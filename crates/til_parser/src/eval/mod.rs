@@ -1,6 +1,23 @@
-use std::collections::HashMap;
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
 
-use tydi_common::name::{Name, PathName};
+use til_query::ir::{
+    generics::GenericParameter,
+    implementation::Implementation,
+    project::{
+        constant_declaration::ConstantDeclaration, interface::Interface,
+        type_declaration::TypeDeclaration,
+    },
+    streamlet::Streamlet,
+};
+use tydi_common::{
+    map::InsertionOrderedMap,
+    name::{Name, PathName},
+};
+use tydi_intern::Id;
 
 use crate::{ident_expr::IdentExpr, Span};
 
@@ -35,6 +52,132 @@ impl EvalError {
     }
 }
 
+/// Declarations imported into a namespace, by the identifier they are referred
+/// to by within the namespace.
+///
+/// Multiple declarations can be imported under the same identifier, in which
+/// case referring to that identifier is ambiguous.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Imports<T> {
+    decls: HashMap<PathName, Vec<(PathName, T)>>,
+}
+
+impl<T> Default for Imports<T> {
+    fn default() -> Self {
+        Imports {
+            decls: HashMap::new(),
+        }
+    }
+}
+
+impl<T> Imports<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Import the declaration with the full name `decl_name` as `reference`.
+    /// Importing the same declaration as the same reference more than once has
+    /// no effect.
+    pub fn insert(&mut self, reference: PathName, decl_name: PathName, decl: T) {
+        let candidates = self.decls.entry(reference).or_default();
+        if !candidates.iter().any(|(name, _)| name == &decl_name) {
+            candidates.push((decl_name, decl));
+        }
+    }
+
+    /// The full names and declarations imported as `reference`.
+    pub fn get(&self, reference: &PathName) -> &[(PathName, T)] {
+        self.decls
+            .get(reference)
+            .map(|candidates| candidates.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// The declarations of a namespace which have been evaluated so far, and the
+/// declarations imported into it.
+#[derive(Clone, Debug)]
+pub struct Declarations {
+    pub namespace: PathName,
+    pub types: HashMap<Name, TypeDeclaration>,
+    pub type_imports: Imports<TypeDeclaration>,
+    pub interfaces: HashMap<Name, Id<Arc<Interface>>>,
+    pub interface_imports: Imports<Id<Arc<Interface>>>,
+    pub implementations: HashMap<Name, Id<Implementation>>,
+    pub implementation_imports: Imports<Id<Implementation>>,
+    pub streamlets: HashMap<Name, Id<Arc<Streamlet>>>,
+    pub streamlet_imports: Imports<Id<Arc<Streamlet>>>,
+    pub constants: HashMap<Name, ConstantDeclaration>,
+    pub constant_imports: Imports<ConstantDeclaration>,
+    /// The full names of the declarations referred to by name so far.
+    pub references: RefCell<BTreeSet<PathName>>,
+}
+
+impl Declarations {
+    pub fn new(namespace: PathName) -> Self {
+        Declarations {
+            namespace,
+            types: HashMap::new(),
+            type_imports: Imports::new(),
+            interfaces: HashMap::new(),
+            interface_imports: Imports::new(),
+            implementations: HashMap::new(),
+            implementation_imports: Imports::new(),
+            streamlets: HashMap::new(),
+            streamlet_imports: Imports::new(),
+            constants: HashMap::new(),
+            constant_imports: Imports::new(),
+            references: RefCell::new(BTreeSet::new()),
+        }
+    }
+}
+
+/// The context an expression is evaluated in: the declarations of its
+/// namespace, and the generic parameters of the declaration it is part of.
+#[derive(Clone, Copy, Debug)]
+pub struct EvalContext<'a> {
+    decls: &'a Declarations,
+    params: &'a InsertionOrderedMap<Name, GenericParameter>,
+}
+
+impl<'a> EvalContext<'a> {
+    pub fn new(
+        decls: &'a Declarations,
+        params: &'a InsertionOrderedMap<Name, GenericParameter>,
+    ) -> Self {
+        EvalContext { decls, params }
+    }
+
+    pub fn decls(&self) -> &'a Declarations {
+        self.decls
+    }
+
+    pub fn params(&self) -> &'a InsertionOrderedMap<Name, GenericParameter> {
+        self.params
+    }
+
+    /// The same declarations, with the generic parameters `params` instead.
+    pub fn with_params(self, params: &'a InsertionOrderedMap<Name, GenericParameter>) -> Self {
+        EvalContext { params, ..self }
+    }
+
+    /// Look up the declaration `ident` refers to, like [`eval_ident`], and
+    /// record the reference to it.
+    pub fn eval_ident<T: Clone>(
+        &self,
+        ident: &IdentExpr,
+        span: &Span,
+        defs: &HashMap<Name, T>,
+        imports: &Imports<T>,
+        decl_name: &str,
+    ) -> Result<T, EvalError> {
+        let (path, decl) =
+            eval_ident(&self.decls.namespace, ident, span, defs, imports, decl_name)?;
+        self.decls.references.borrow_mut().insert(path);
+        Ok(decl)
+    }
+}
+
 pub fn eval_common_error<T>(
     res: Result<T, tydi_common::error::Error>,
     span: &Span,
@@ -48,23 +191,34 @@ pub fn eval_common_error<T>(
     }
 }
 
+/// Look up the declaration `ident` refers to, among the declarations `defs` of
+/// the namespace `namespace` and the declarations `imports` imported into it.
+///
+/// Returns the full name of the declaration, and the declaration itself.
 pub fn eval_ident<T: Clone>(
+    namespace: &PathName,
     ident: &IdentExpr,
     span: &Span,
     defs: &HashMap<Name, T>,
-    imports: &HashMap<PathName, T>,
+    imports: &Imports<T>,
     decl_name: &str,
-) -> Result<T, EvalError> {
+) -> Result<(PathName, T), EvalError> {
     match ident {
         IdentExpr::Name((n, s)) => {
             let name = eval_name(n, s)?;
             if let Some(val) = defs.get(&name) {
-                Ok(val.clone())
+                Ok((namespace.with_child(name), val.clone()))
             } else {
-                Err(EvalError {
-                    span: s.clone(),
-                    msg: format!("No {} with identity {}", decl_name, &name),
-                })
+                // Declarations can be imported by name as well
+                let reference = PathName::new([name.clone()].into_iter());
+                if imports.get(&reference).is_empty() {
+                    Err(EvalError {
+                        span: s.clone(),
+                        msg: format!("No {} with identity {}", decl_name, &name),
+                    })
+                } else {
+                    eval_import(&reference, s, imports, decl_name)
+                }
             }
         }
         IdentExpr::PathName(pth) => {
@@ -74,18 +228,69 @@ pub fn eval_ident<T: Clone>(
                 pthn.push(name_span);
             }
             let pthn = PathName::new(pthn.into_iter());
-            if let Some(val) = imports.get(&pthn) {
-                Ok(val.clone())
-            } else {
-                Err(EvalError {
-                    span: span.clone(),
-                    msg: format!("No imported {} with identity {}", decl_name, &pthn),
-                })
-            }
+            eval_import(&pthn, span, imports, decl_name)
         }
     }
 }
 
+/// Look up an imported declaration, which must be unambiguous.
+///
+/// Returns the full name of the declaration, and the declaration itself.
+fn eval_import<T: Clone>(
+    reference: &PathName,
+    span: &Span,
+    imports: &Imports<T>,
+    decl_name: &str,
+) -> Result<(PathName, T), EvalError> {
+    match imports.get(reference) {
+        [(name, val)] => Ok((name.clone(), val.clone())),
+        [] => Err(EvalError {
+            span: span.clone(),
+            msg: format!("No imported {} with identity {}", decl_name, reference),
+        }),
+        candidates => Err(EvalError {
+            span: span.clone(),
+            msg: format!(
+                "Ambiguous {} {}, could refer to {}",
+                decl_name,
+                reference,
+                candidates
+                    .iter()
+                    .map(|(name, _)| name.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" or ")
+            ),
+        }),
+    }
+}
+
+/// Declarations can not shadow declarations which are imported by their name,
+/// as referring to either would be ambiguous.
+pub fn eval_shadowing<T>(
+    name: &Name,
+    span: &Span,
+    imports: &Imports<T>,
+    decl_name: &str,
+) -> Result<(), EvalError> {
+    match imports.get(&PathName::new([name.clone()].into_iter())) {
+        [] => Ok(()),
+        candidates => Err(EvalError {
+            span: span.clone(),
+            msg: format!(
+                "Declaration of {} {} shadows imported {} {}",
+                decl_name,
+                name,
+                decl_name,
+                candidates
+                    .iter()
+                    .map(|(name, _)| name.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" and ")
+            ),
+        }),
+    }
+}
+
 pub fn eval_name(n: &String, s: &Span) -> Result<Name, EvalError> {
     match Name::try_new(n) {
         Ok(name) => Ok(name),
@@ -95,3 +300,46 @@ pub fn eval_name(n: &String, s: &Span) -> Result<Name, EvalError> {
         }),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ambiguous_imports() -> tydi_common::error::Result<()> {
+        let mut imports = Imports::new();
+        let reference = PathName::try_new(["stream_t"])?;
+        imports.insert(reference.clone(), PathName::try_new(["a", "stream_t"])?, 1);
+        imports.insert(reference.clone(), PathName::try_new(["a", "stream_t"])?, 1);
+        let namespace = PathName::try_new(["c"])?;
+        let ident = IdentExpr::Name(("stream_t".to_string(), 0..8));
+        assert_eq!(
+            eval_ident(
+                &namespace,
+                &ident,
+                &(0..8),
+                &HashMap::new(),
+                &imports,
+                "type"
+            ),
+            Ok((PathName::try_new(["a", "stream_t"])?, 1))
+        );
+
+        imports.insert(reference, PathName::try_new(["b", "stream_t"])?, 2);
+        let err = eval_ident(
+            &namespace,
+            &ident,
+            &(0..8),
+            &HashMap::new(),
+            &imports,
+            "type",
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            err.msg(),
+            "Ambiguous type stream_t, could refer to a__stream_t or b__stream_t"
+        );
+        Ok(())
+    }
+}
@@ -330,6 +330,8 @@ doc doc# some_port: in a)"#,
         test_expr_parse("Bits(23)");
         test_expr_parse("Group(a: Bits(32), b: path::name)");
         test_expr_parse("Union()");
+        test_expr_parse("Enum(add, sub, mul)");
+        test_expr_parse("Array(Bits(8), 4 * 4)");
         test_expr_parse(
             "Stream (
         data: rgb,
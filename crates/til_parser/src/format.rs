@@ -0,0 +1,1418 @@
+use chumsky::Parser;
+use tydi_common::name::Name;
+
+use crate::{
+    diagnostic::Diagnostic,
+    doc_expr::DocExpr,
+    expr::{Expr, StreamletProperty},
+    generic_param::{GenericParameterAssignments, GenericParameterList, GenericParameterValueExpr},
+    ident_expr::IdentExpr,
+    impl_expr::{ImplBodyExpr, ImplDefExpr, StreamletImplExpr},
+    interface_expr::{InterfaceDef, InterfaceExpr, InterfaceParameters, PortDef, PortsDef},
+    lex::{lexer, ConditionKeyword, Operator, Token},
+    namespace::{Decl, Import, Namespace, Statement},
+    query::parse_file,
+    struct_parse::{InterfaceParamAssignments, PortSel, StructStat},
+    type_expr::{
+        FieldsDef, LogicalTypeDef, StreamProp, StreamPropValue, StreamProps, TypeExpr, VariantsDef,
+    },
+    Span, Spanned,
+};
+
+/// The number of columns a list may take up before it is split across lines.
+const MAX_WIDTH: usize = 100;
+
+const INDENT: &str = "    ";
+
+type FmtResult<T> = std::result::Result<T, Diagnostic>;
+
+type Field = (DocExpr, Spanned<String>, Spanned<TypeExpr>);
+type Variant = (DocExpr, Spanned<String>);
+type StreamletProp = (Spanned<Token>, StreamletProperty);
+
+/// Format the source of a TIL file into its canonical layout.
+///
+/// Declarations are indented by four spaces, lists which do not fit on a
+/// single line (or which contain comments or documentation) are written with
+/// one item per line followed by a comma, and the properties of Streams are
+/// written in a fixed order. Comments and documentation are preserved.
+///
+/// Files which can not be parsed are not formatted, instead the diagnostics
+/// of the file are returned.
+pub fn format_source(src: &str) -> std::result::Result<String, Vec<Diagnostic>> {
+    let (ast, errs, parse_errs) = parse_file(src);
+    if !errs.is_empty() || !parse_errs.is_empty() {
+        return Err(errs
+            .into_iter()
+            .map(Diagnostic::from)
+            .chain(parse_errs.into_iter().map(Diagnostic::from))
+            .collect());
+    }
+    let tokens = lexer()
+        .parse(src)
+        .map_err(|errs| errs.into_iter().map(Diagnostic::from).collect::<Vec<_>>())?;
+
+    let mut formatter = Formatter::new(src, &tokens);
+    formatter
+        .namespaces(&ast.unwrap_or_default())
+        .map_err(|diagnostic| vec![diagnostic])?;
+    let formatted = formatter.finish();
+
+    // Never replace a file with something which can no longer be parsed
+    let (_, errs, parse_errs) = parse_file(&formatted);
+    if !errs.is_empty() || !parse_errs.is_empty() {
+        return Err(vec![Diagnostic::error(
+            "Unable to format this file, the result could not be parsed",
+            0..0,
+        )]);
+    }
+    Ok(formatted)
+}
+
+/// A comment, which is not part of the tokens (and AST) of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Comment {
+    span: Span,
+    text: String,
+}
+
+/// Find the comments in `src`, skipping over documentation and paths, which
+/// may contain text resembling comments.
+fn comments(src: &[char]) -> Vec<Comment> {
+    let starts_with = |idx: usize, pattern: &str| {
+        pattern
+            .chars()
+            .enumerate()
+            .all(|(offset, c)| src.get(idx + offset) == Some(&c))
+    };
+    let line_end = |idx: usize| {
+        (idx..src.len())
+            .find(|i| src[*i] == '\n')
+            .unwrap_or(src.len())
+    };
+
+    let mut result = vec![];
+    let mut idx = 0;
+    while idx < src.len() {
+        if starts_with(idx, "///") {
+            idx = line_end(idx);
+        } else if starts_with(idx, "//") {
+            let start = idx;
+            idx = line_end(idx);
+            while src[idx - 1].is_whitespace() {
+                idx -= 1;
+            }
+            result.push(Comment {
+                span: start..idx,
+                text: src[start..idx].iter().collect(),
+            });
+        } else if starts_with(idx, "/*") {
+            // Block comments can be nested
+            let start = idx;
+            let mut depth = 0;
+            while idx < src.len() {
+                if starts_with(idx, "/*") {
+                    depth += 1;
+                    idx += 2;
+                } else if starts_with(idx, "*/") {
+                    depth -= 1;
+                    idx += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    idx += 1;
+                }
+            }
+            result.push(Comment {
+                span: start..idx,
+                text: src[start..idx].iter().collect(),
+            });
+        } else if src[idx] == '#' || src[idx] == '"' {
+            let delimiter = src[idx];
+            idx = (idx + 1..src.len())
+                .find(|i| src[*i] == delimiter)
+                .map(|i| i + 1)
+                .unwrap_or(src.len());
+        } else {
+            idx += 1;
+        }
+    }
+    result
+}
+
+fn invalid(span: &Span) -> Diagnostic {
+    Diagnostic::error("Unable to format invalid syntax", span.clone())
+}
+
+struct Formatter<'a> {
+    src: Vec<char>,
+    tokens: &'a [Spanned<Token>],
+    comments: Vec<Comment>,
+    /// Whether each of the comments has been written
+    written: Vec<bool>,
+    /// Comments before this position belong to items which are written
+    /// elsewhere, used when the items of a list are reordered
+    floor: usize,
+    out: String,
+    indent: usize,
+    /// Whether nothing has been written since the current block was opened
+    fresh: bool,
+    /// The end of the last item or comment which was written, used to
+    /// preserve empty lines between items
+    last: usize,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(src: &str, tokens: &'a [Spanned<Token>]) -> Self {
+        let src = src.chars().collect::<Vec<_>>();
+        let comments = comments(&src);
+        Formatter {
+            written: vec![false; comments.len()],
+            src,
+            tokens,
+            comments,
+            floor: 0,
+            out: String::new(),
+            indent: 0,
+            fresh: true,
+            last: 0,
+        }
+    }
+
+    fn finish(mut self) -> String {
+        // Comments at the end of the file
+        self.leading(usize::MAX, true);
+        let mut result = self.out.trim_end().to_string();
+        if !result.is_empty() {
+            result.push('\n');
+        }
+        result
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n')
+    }
+
+    fn write(&mut self, text: &str) {
+        if self.at_line_start() {
+            for _ in 0..self.indent {
+                self.out.push_str(INDENT);
+            }
+        }
+        self.out.push_str(text);
+        self.fresh = false;
+    }
+
+    fn newline(&mut self) {
+        let len = self.out.trim_end_matches(' ').len();
+        self.out.truncate(len);
+        self.out.push('\n');
+    }
+
+    /// End the current line, if anything was written to it.
+    fn line(&mut self) {
+        if !self.at_line_start() {
+            self.newline();
+        }
+    }
+
+    fn blank_line(&mut self) {
+        if !self.fresh && !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+
+    /// Start an indented block, after its opening delimiter.
+    fn open(&mut self) {
+        self.newline();
+        self.indent += 1;
+        self.fresh = true;
+    }
+
+    fn close(&mut self, delimiter: &str) {
+        self.line();
+        self.indent -= 1;
+        self.write(delimiter);
+    }
+
+    fn column(&self) -> usize {
+        if self.at_line_start() {
+            self.indent * INDENT.len()
+        } else {
+            self.out.rsplit('\n').next().unwrap_or("").chars().count()
+        }
+    }
+
+    fn fits(&self, text: &str) -> bool {
+        !text.contains('\n') && self.column() + text.chars().count() <= MAX_WIDTH
+    }
+
+    /// The end of the code in `span`, without the whitespace and comments
+    /// which follow it.
+    fn content_end(&self, span: &Span) -> usize {
+        let mut end = span.end.min(self.src.len());
+        loop {
+            while end > span.start && self.src[end - 1].is_whitespace() {
+                end -= 1;
+            }
+            match self
+                .comments
+                .iter()
+                .find(|comment| comment.span.end == end && comment.span.start >= span.start)
+            {
+                Some(comment) => end = comment.span.start,
+                None => return end,
+            }
+        }
+    }
+
+    /// The start and end of the code of an item.
+    fn item_span(&self, span: &Span) -> (usize, usize) {
+        (span.start, self.content_end(span))
+    }
+
+    /// The position of the delimiter closing `span`, e.g. the parenthesis of
+    /// `Group(...)`.
+    fn closing(&self, span: &Span, delimiter: char) -> FmtResult<usize> {
+        let end = self.content_end(span);
+        if end > span.start && self.src[end - 1] == delimiter {
+            Ok(end - 1)
+        } else {
+            Err(invalid(span))
+        }
+    }
+
+    /// The position of the brace closing a namespace.
+    fn namespace_end(&self, namespace: &Namespace) -> FmtResult<usize> {
+        let after = match namespace.stats().last() {
+            Some((_, span)) => span.end,
+            None => namespace.name_span().end,
+        };
+        self.tokens
+            .iter()
+            .find(|(token, span)| *token == Token::Ctrl('}') && span.start >= after)
+            .map(|(_, span)| span.start)
+            .ok_or_else(|| invalid(namespace.name_span()))
+    }
+
+    fn has_comments(&self, start: usize, end: usize) -> bool {
+        self.comments
+            .iter()
+            .any(|comment| comment.span.start >= start && comment.span.start < end)
+    }
+
+    fn blank_between(&self, start: usize, end: usize) -> bool {
+        let mut newlines = 0;
+        for c in self.src.iter().take(end).skip(start) {
+            if *c == '\n' {
+                newlines += 1;
+                if newlines > 1 {
+                    return true;
+                }
+            } else if !c.is_whitespace() {
+                newlines = 0;
+            }
+        }
+        false
+    }
+
+    /// Whether a comment starting at `start` is on the same line as the code
+    /// ending at `end`.
+    fn same_line(&self, end: usize, start: usize) -> bool {
+        start >= end && !self.src[end..start].contains(&'\n')
+    }
+
+    /// The end of the comments following the code ending at `end` on the same
+    /// line, before `limit`.
+    fn trailing_end(&self, end: usize, limit: usize) -> usize {
+        self.comments
+            .iter()
+            .filter(|comment| comment.span.start < limit && self.same_line(end, comment.span.start))
+            .map(|comment| comment.span.end)
+            .max()
+            .unwrap_or(end)
+    }
+
+    /// Write the comments before `pos` which have not been written yet, each
+    /// on its own line.
+    fn leading(&mut self, pos: usize, blank_lines: bool) {
+        for idx in 0..self.comments.len() {
+            let span = self.comments[idx].span.clone();
+            if self.written[idx] || span.start < self.floor || span.start >= pos {
+                continue;
+            }
+            self.line();
+            if blank_lines && self.blank_between(self.last, span.start) {
+                self.blank_line();
+            }
+            let text = self.comments[idx].text.clone();
+            self.write(&text);
+            self.newline();
+            self.written[idx] = true;
+            self.last = span.end;
+        }
+    }
+
+    /// Append the comments of an item of which the code ends at `end`: those
+    /// within the item, and those following it on the same line (before
+    /// `limit`).
+    fn trailing(&mut self, end: usize, limit: usize) {
+        let mut line_comment = false;
+        for idx in 0..self.comments.len() {
+            let span = self.comments[idx].span.clone();
+            if self.written[idx]
+                || span.start < self.floor
+                || span.start >= limit
+                || (span.start >= end && !self.same_line(end, span.start))
+            {
+                continue;
+            }
+            // Nothing can follow a line comment on the same line
+            if line_comment {
+                self.newline();
+            } else {
+                self.write(" ");
+            }
+            let text = self.comments[idx].text.clone();
+            self.write(&text);
+            line_comment = text.starts_with("//");
+            self.written[idx] = true;
+            self.last = self.last.max(span.end);
+        }
+    }
+
+    /// Write an item on its own line, preceded by the comments before it and
+    /// followed by `separator` and the comments on the same line.
+    fn item(
+        &mut self,
+        (start, end): (usize, usize),
+        limit: usize,
+        blank_lines: bool,
+        separator: &str,
+        print: impl FnOnce(&mut Self) -> FmtResult<()>,
+    ) -> FmtResult<()> {
+        self.leading(start, blank_lines);
+        self.line();
+        if blank_lines && self.blank_between(self.last, start) {
+            self.blank_line();
+        }
+        print(self)?;
+        self.write(separator);
+        self.last = end;
+        self.trailing(end, limit);
+        self.newline();
+        Ok(())
+    }
+
+    /// Write a delimited, comma-separated list.
+    ///
+    /// The list is written on a single line if it fits and contains no
+    /// comments or documentation. Otherwise, every item is written on its own
+    /// line, followed by a comma.
+    ///
+    /// `region` is the part of the source containing the items, up to the
+    /// closing delimiter. The items (and the code they span) may be given in
+    /// a different order than they appear in, comments stay with the items
+    /// they belong to.
+    fn list<T>(
+        &mut self,
+        (open, close): (&str, &str),
+        spaced: bool,
+        (start, end): (usize, usize),
+        items: Vec<(&T, (usize, usize))>,
+        inline: impl Fn(&Self, &T) -> FmtResult<Option<String>>,
+        print: impl Fn(&mut Self, &T) -> FmtResult<()>,
+    ) -> FmtResult<()> {
+        let texts = items
+            .iter()
+            .map(|(item, _)| inline(self, item))
+            .collect::<FmtResult<Option<Vec<_>>>>()?;
+        if let Some(texts) = texts {
+            let text = if texts.is_empty() {
+                format!("{}{}", open, close)
+            } else if spaced {
+                format!("{} {} {}", open, texts.join(", "), close)
+            } else {
+                format!("{}{}{}", open, texts.join(", "), close)
+            };
+            if !self.has_comments(start, end) && self.fits(&text) {
+                self.write(&text);
+                return Ok(());
+            }
+        }
+
+        self.write(open);
+        self.open();
+        let mut in_source = items.iter().map(|(_, span)| *span).collect::<Vec<_>>();
+        in_source.sort_unstable();
+        let floor = self.floor;
+        for (item, span) in &items {
+            let idx = in_source.binary_search(span).unwrap_or_default();
+            let limit = in_source.get(idx + 1).map(|(s, _)| *s).unwrap_or(end);
+            if idx > 0 {
+                self.floor = floor.max(self.trailing_end(in_source[idx - 1].1, span.0));
+            }
+            self.item(*span, limit, false, ",", |f| print(f, item))?;
+            self.floor = floor;
+        }
+        self.leading(end, false);
+        self.close(close);
+        Ok(())
+    }
+
+    /// Write documentation on its own line(s), in the style it was written in
+    /// (`#...#` or `///`).
+    fn doc_at(&mut self, doc: &str, start: usize) {
+        if self.src.get(start) == Some(&'#') {
+            self.write(&format!("#{}#", doc));
+            self.newline();
+        } else {
+            for line in doc.split('\n') {
+                if line.is_empty() {
+                    self.write("///");
+                } else {
+                    self.write(&format!("/// {}", line));
+                }
+                self.newline();
+            }
+        }
+    }
+
+    fn doc(&mut self, doc: &DocExpr) {
+        if let Some((doc, span)) = doc {
+            self.doc_at(doc, span.start);
+        }
+    }
+
+    /// The tokens of `span`, separated by a single space where needed.
+    fn source_tokens(&self, span: &Span) -> FmtResult<String> {
+        let src = self.src[span.start..span.end.min(self.src.len())]
+            .iter()
+            .collect::<String>();
+        let tokens = lexer().parse(src.as_str()).map_err(|_| invalid(span))?;
+        let mut result = String::new();
+        let mut space = false;
+        for (idx, (token, _)) in tokens.iter().enumerate() {
+            if space && !matches!(token, Token::Ctrl(':' | ',' | ';' | ')')) {
+                result.push(' ');
+            }
+            result.push_str(&token.to_string());
+            // A minus following anything but a value negates the next value
+            let unary = *token == Token::Op(Operator::Sub)
+                && (idx == 0
+                    || matches!(
+                        tokens[idx - 1].0,
+                        Token::Ctrl(_) | Token::Op(_) | Token::Condition(_)
+                    ));
+            space = !unary
+                && !matches!(
+                    token,
+                    Token::Ctrl('(') | Token::Condition(ConditionKeyword::OneOf)
+                );
+        }
+        Ok(result)
+    }
+
+    fn namespaces(&mut self, namespaces: &[Namespace]) -> FmtResult<()> {
+        for (idx, namespace) in namespaces.iter().enumerate() {
+            let start = namespace.name_span().start;
+            if idx > 0 {
+                self.blank_line();
+            }
+            self.leading(start, true);
+            self.line();
+            if self.blank_between(self.last, start) {
+                self.blank_line();
+            }
+            self.write(&format!("namespace {} {{", namespace.name().join("::")));
+            self.open();
+
+            let end = self.namespace_end(namespace)?;
+            let stats = namespace.stats();
+            for (idx, (stat, span)) in stats.iter().enumerate() {
+                let limit = stats.get(idx + 1).map(|(_, s)| s.start).unwrap_or(end);
+                self.item(self.item_span(span), limit, true, ";", |f| {
+                    f.statement(stat, span)
+                })?;
+            }
+            self.leading(end, false);
+            self.close("}");
+            self.last = end + 1;
+            let limit = namespaces
+                .get(idx + 1)
+                .map(|namespace| namespace.name_span().start)
+                .unwrap_or(usize::MAX);
+            self.trailing(end + 1, limit);
+            self.newline();
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, stat: &Statement, span: &Span) -> FmtResult<()> {
+        match stat {
+            Statement::Import(import) => {
+                let path = path(&import.path().0);
+                self.write(&match import {
+                    Import::FullImport(_) => format!("import {}", path),
+                    Import::AliasedImport(_, (alias, _)) => format!("import {} as {}", path, alias),
+                    Import::PrefixedImport(_, (prefix, _)) => {
+                        format!("import {} prefixed {}", path, prefix)
+                    }
+                    Import::GlobImport(_) => format!("import {}::*", path),
+                });
+                Ok(())
+            }
+            Statement::Decl(decl) => self.decl(decl, span),
+        }
+    }
+
+    fn decl(&mut self, decl: &Decl, span: &Span) -> FmtResult<()> {
+        match decl {
+            Decl::TypeDecl(doc, (name, _), typ, params) => {
+                self.doc(doc);
+                self.write(&format!("type {}", name));
+                self.type_parameters(params)?;
+                self.write(" = ");
+                self.type_expr(typ)
+            }
+            Decl::ImplDecl(doc, (name, _), (def, _)) => {
+                self.doc(doc);
+                self.write(&format!("impl {} = ", name));
+                match def {
+                    ImplDefExpr::Identity(ident) => {
+                        self.write(&ident_expr(ident));
+                        Ok(())
+                    }
+                    ImplDefExpr::Def(interface, body) => {
+                        self.interface(interface)?;
+                        self.write(" ");
+                        self.impl_body(body)
+                    }
+                }
+            }
+            Decl::InterfaceDecl(doc, (name, _), interface) => {
+                self.doc(doc);
+                self.write(&format!("interface {} = ", name));
+                self.interface(interface)
+            }
+            Decl::StreamletDecl(doc, (name, _), expr) => {
+                // The documentation of a streamlet is where its declaration starts
+                if let Some(doc) = doc {
+                    self.doc_at(doc, span.start);
+                }
+                self.write(&format!("streamlet {} = ", name));
+                self.streamlet(expr)
+            }
+            Decl::ConstDecl(doc, (name, _), (_, kind_span), value) => {
+                self.doc(doc);
+                let text = format!(
+                    "const {}: {} = {}",
+                    name,
+                    self.source_tokens(kind_span)?,
+                    value_expr(value)?
+                );
+                self.write(&text);
+                Ok(())
+            }
+        }
+    }
+
+    /// Write a list of parameters, of which the text is already known.
+    fn parameters(&mut self, items: Vec<(String, (usize, usize))>, span: &Span) -> FmtResult<()> {
+        let end = self.closing(span, '>')?;
+        let items = items
+            .iter()
+            .map(|(text, span)| (text, *span))
+            .collect::<Vec<_>>();
+        self.list(
+            ("<", ">"),
+            false,
+            (span.start, end),
+            items,
+            |_, text| Ok(Some(text.to_string())),
+            |f, text| {
+                f.write(text);
+                Ok(())
+            },
+        )
+    }
+
+    fn type_parameters(&mut self, (params, span): &Spanned<GenericParameterList>) -> FmtResult<()> {
+        match params {
+            GenericParameterList::None => Ok(()),
+            GenericParameterList::Error => Err(invalid(span)),
+            GenericParameterList::List(params) => {
+                // Conditions are folded into the kinds of parameters when
+                // parsing, so parameters are written as they are in the source.
+                let items = params
+                    .iter()
+                    .map(|(_, span)| Ok((self.source_tokens(span)?, self.item_span(span))))
+                    .collect::<FmtResult<Vec<_>>>()?;
+                self.parameters(items, span)
+            }
+        }
+    }
+
+    fn interface_parameters(
+        &mut self,
+        (params, span): &Spanned<InterfaceParameters>,
+    ) -> FmtResult<()> {
+        let (domains, params) = match params {
+            InterfaceParameters::Error => return Err(invalid(span)),
+            InterfaceParameters::JustDomains(domains) => (domains.as_slice(), &[][..]),
+            InterfaceParameters::JustGenericParams(params) => (&[][..], params.as_slice()),
+            InterfaceParameters::Parameters(domains, params) => {
+                (domains.as_slice(), params.as_slice())
+            }
+        };
+        let mut items = domains
+            .iter()
+            .map(|(domain, span)| (format!("'{}", domain), self.item_span(span)))
+            .collect::<Vec<_>>();
+        for (_, span) in params {
+            items.push((self.source_tokens(span)?, self.item_span(span)));
+        }
+        self.parameters(items, span)
+    }
+
+    fn type_expr(&mut self, typ: &Spanned<TypeExpr>) -> FmtResult<()> {
+        match &typ.0 {
+            TypeExpr::Definition(def) => self.type_def(def),
+            _ => {
+                let text = self.inline_type(typ)?.unwrap_or_default();
+                self.write(&text);
+                Ok(())
+            }
+        }
+    }
+
+    fn type_def(&mut self, def: &Spanned<LogicalTypeDef>) -> FmtResult<()> {
+        let span = &def.1;
+        match &def.0 {
+            LogicalTypeDef::Null | LogicalTypeDef::Bits(_) => {
+                let text = self.inline_type_def(def)?.unwrap_or_default();
+                self.write(&text);
+                Ok(())
+            }
+            LogicalTypeDef::Group(fields) | LogicalTypeDef::Union(fields) => {
+                let fields = fields_of(fields)?;
+                self.write(match def.0 {
+                    LogicalTypeDef::Group(_) => "Group",
+                    _ => "Union",
+                });
+                let items = fields
+                    .iter()
+                    .map(|field| (field, self.field_span(field)))
+                    .collect();
+                let region = (span.start, self.closing(span, ')')?);
+                self.list(
+                    ("(", ")"),
+                    false,
+                    region,
+                    items,
+                    Self::inline_field,
+                    Self::field,
+                )
+            }
+            LogicalTypeDef::Enum(variants) => {
+                let variants = variants_of(variants)?;
+                self.write("Enum");
+                let items = variants
+                    .iter()
+                    .map(|variant| {
+                        let (doc, (_, name_span)) = variant;
+                        let start = doc
+                            .as_ref()
+                            .map(|(_, s)| s.start)
+                            .unwrap_or(name_span.start);
+                        (variant, (start, self.content_end(name_span)))
+                    })
+                    .collect();
+                let region = (span.start, self.closing(span, ')')?);
+                self.list(
+                    ("(", ")"),
+                    false,
+                    region,
+                    items,
+                    |_, (doc, (name, _)): &Variant| Ok(doc.is_none().then(|| name.clone())),
+                    |f, (doc, (name, _)): &Variant| {
+                        f.doc(doc);
+                        f.write(name);
+                        Ok(())
+                    },
+                )
+            }
+            LogicalTypeDef::Array(element, count) => {
+                self.write("Array(");
+                self.type_expr(element)?;
+                self.write(&format!(", {})", value_expr(count)?));
+                Ok(())
+            }
+            LogicalTypeDef::Stream((props, props_span)) => {
+                let props = match props {
+                    StreamProps::Props(props) => props,
+                    StreamProps::Error => return Err(invalid(props_span)),
+                };
+                self.write("Stream");
+                let mut items = props
+                    .iter()
+                    .map(|prop| (prop, self.item_span(&prop.1)))
+                    .collect::<Vec<_>>();
+                items.sort_by_key(|(prop, _)| stream_prop_order(&prop.0));
+                let region = (span.start, self.closing(span, ')')?);
+                self.list(
+                    ("(", ")"),
+                    false,
+                    region,
+                    items,
+                    Self::inline_stream_prop,
+                    Self::stream_prop,
+                )
+            }
+        }
+    }
+
+    /// A type on a single line, if it contains no documentation.
+    fn inline_type(&self, (typ, span): &Spanned<TypeExpr>) -> FmtResult<Option<String>> {
+        match typ {
+            TypeExpr::Error => Err(invalid(span)),
+            TypeExpr::Identifier(ident) => Ok(Some(ident_expr(ident))),
+            TypeExpr::Assigned(ident, (assignments, span)) => match assignments {
+                GenericParameterAssignments::List(assignments) => Ok(Some(format!(
+                    "{}<{}>",
+                    ident_expr(ident),
+                    param_assignments(assignments)?
+                ))),
+                GenericParameterAssignments::Error => Err(invalid(span)),
+            },
+            TypeExpr::Definition(def) => self.inline_type_def(def),
+        }
+    }
+
+    fn inline_type_def(&self, (def, span): &Spanned<LogicalTypeDef>) -> FmtResult<Option<String>> {
+        let items = |items: Vec<Option<String>>| -> Option<String> {
+            items
+                .into_iter()
+                .collect::<Option<Vec<_>>>()
+                .map(|items| items.join(", "))
+        };
+        let text = match def {
+            LogicalTypeDef::Null => Some("Null".to_string()),
+            LogicalTypeDef::Bits(width) => Some(format!("Bits({})", value_expr(width)?)),
+            LogicalTypeDef::Group(fields) | LogicalTypeDef::Union(fields) => {
+                let keyword = match def {
+                    LogicalTypeDef::Group(_) => "Group",
+                    _ => "Union",
+                };
+                let fields = fields_of(fields)?
+                    .iter()
+                    .map(|field| self.inline_field(field))
+                    .collect::<FmtResult<Vec<_>>>()?;
+                items(fields).map(|fields| format!("{}({})", keyword, fields))
+            }
+            LogicalTypeDef::Enum(variants) => {
+                let variants = variants_of(variants)?
+                    .iter()
+                    .map(|(doc, (name, _))| doc.is_none().then(|| name.clone()))
+                    .collect();
+                items(variants).map(|variants| format!("Enum({})", variants))
+            }
+            LogicalTypeDef::Array(element, count) => match self.inline_type(element)? {
+                Some(element) => Some(format!("Array({}, {})", element, value_expr(count)?)),
+                None => None,
+            },
+            LogicalTypeDef::Stream((StreamProps::Props(props), _)) => {
+                let mut props = props.iter().collect::<Vec<_>>();
+                props.sort_by_key(|(prop, _)| stream_prop_order(prop));
+                let props = props
+                    .into_iter()
+                    .map(|prop| self.inline_stream_prop(prop))
+                    .collect::<FmtResult<Vec<_>>>()?;
+                items(props).map(|props| format!("Stream({})", props))
+            }
+            LogicalTypeDef::Stream((StreamProps::Error, _)) => return Err(invalid(span)),
+        };
+        Ok(text)
+    }
+
+    fn field_span(&self, (doc, (_, name_span), (_, typ_span)): &Field) -> (usize, usize) {
+        let start = doc
+            .as_ref()
+            .map(|(_, s)| s.start)
+            .unwrap_or(name_span.start);
+        (start, self.content_end(typ_span))
+    }
+
+    fn inline_field(&self, (doc, (name, _), typ): &Field) -> FmtResult<Option<String>> {
+        if doc.is_some() {
+            return Ok(None);
+        }
+        Ok(self
+            .inline_type(typ)?
+            .map(|typ| format!("{}: {}", name, typ)))
+    }
+
+    fn field(&mut self, (doc, (name, _), typ): &Field) -> FmtResult<()> {
+        self.doc(doc);
+        self.write(&format!("{}: ", name));
+        self.type_expr(typ)
+    }
+
+    fn inline_stream_prop(&self, (prop, _): &Spanned<StreamProp>) -> FmtResult<Option<String>> {
+        let (name, value) = match prop {
+            StreamProp::Data(typ) => ("data", self.inline_type(typ)?),
+            StreamProp::Throughput(value) => ("throughput", Some(self.stream_prop_value(value)?)),
+            StreamProp::Dimensionality(value) => ("dimensionality", Some(value_expr(value)?)),
+            StreamProp::Synchronicity((_, span)) => {
+                ("synchronicity", Some(self.source_tokens(span)?))
+            }
+            StreamProp::Complexity(value) => ("complexity", Some(self.stream_prop_value(value)?)),
+            StreamProp::Direction((_, span)) => ("direction", Some(self.source_tokens(span)?)),
+            StreamProp::User(typ) => ("user", self.inline_type(typ)?),
+            StreamProp::Keep((_, span)) => ("keep", Some(self.source_tokens(span)?)),
+        };
+        Ok(value.map(|value| format!("{}: {}", name, value)))
+    }
+
+    fn stream_prop(&mut self, prop: &Spanned<StreamProp>) -> FmtResult<()> {
+        match &prop.0 {
+            StreamProp::Data(typ) => {
+                self.write("data: ");
+                self.type_expr(typ)
+            }
+            StreamProp::User(typ) => {
+                self.write("user: ");
+                self.type_expr(typ)
+            }
+            _ => {
+                let text = self.inline_stream_prop(prop)?.unwrap_or_default();
+                self.write(&text);
+                Ok(())
+            }
+        }
+    }
+
+    fn stream_prop_value(&self, value: &StreamPropValue) -> FmtResult<String> {
+        match value {
+            // Written as they are in the source, to keep e.g. the `.0` of `2.0`
+            StreamPropValue::Value((_, span)) => self.source_tokens(span),
+            StreamPropValue::Generic(value) => value_expr(value),
+        }
+    }
+
+    fn interface(&mut self, (interface, _): &Spanned<InterfaceExpr>) -> FmtResult<()> {
+        match interface {
+            InterfaceExpr::Identifier(ident) => {
+                self.write(&ident_expr(ident));
+                Ok(())
+            }
+            InterfaceExpr::Definition((InterfaceDef::Def(params, (ports, _)), span)) => {
+                let mut start = span.start;
+                if let Some(params) = params {
+                    self.interface_parameters(params)?;
+                    start = params.1.end;
+                }
+                let ports = match ports {
+                    PortsDef::Def(ports) => ports,
+                    PortsDef::Error => return Err(invalid(span)),
+                };
+                let items = ports
+                    .iter()
+                    .map(|port| (port, self.item_span(&port.1)))
+                    .collect();
+                let region = (start, self.closing(span, ')')?);
+                self.list(
+                    ("(", ")"),
+                    false,
+                    region,
+                    items,
+                    Self::inline_port,
+                    Self::port,
+                )
+            }
+            InterfaceExpr::Definition((InterfaceDef::Error, span)) => Err(invalid(span)),
+        }
+    }
+
+    fn inline_port(&self, (port, _): &Spanned<PortDef>) -> FmtResult<Option<String>> {
+        if port.doc.is_some() {
+            return Ok(None);
+        }
+        let props = &port.props.0;
+        Ok(self.inline_type(&props.typ)?.map(|typ| {
+            format!(
+                "{}: {} {}{}",
+                port.name.0,
+                props.mode.0,
+                typ,
+                domain(&props.domain)
+            )
+        }))
+    }
+
+    fn port(&mut self, (port, _): &Spanned<PortDef>) -> FmtResult<()> {
+        self.doc(&port.doc);
+        let props = &port.props.0;
+        self.write(&format!("{}: {} ", port.name.0, props.mode.0));
+        self.type_expr(&props.typ)?;
+        self.write(&domain(&props.domain));
+        Ok(())
+    }
+
+    fn impl_body(&mut self, (body, span): &Spanned<ImplBodyExpr>) -> FmtResult<()> {
+        match body {
+            ImplBodyExpr::Link(path) => {
+                self.write(&format!("\"{}\"", path));
+                Ok(())
+            }
+            ImplBodyExpr::Struct(doc, stats) => {
+                if let Some((doc, doc_span)) = doc {
+                    if self.src.get(doc_span.start) == Some(&'#') {
+                        self.write(&format!("#{}# ", doc));
+                    } else {
+                        self.line();
+                        self.doc_at(doc, doc_span.start);
+                    }
+                }
+                let end = self.closing(span, '}')?;
+                self.write("{");
+                if stats.is_empty() && !self.has_comments(span.start, end) {
+                    self.write("}");
+                    return Ok(());
+                }
+                self.open();
+                for (idx, stat) in stats.iter().enumerate() {
+                    let limit = stats.get(idx + 1).map(|(_, s)| s.start).unwrap_or(end);
+                    self.item(self.item_span(&stat.1), limit, true, ";", |f| {
+                        f.struct_stat(stat)
+                    })?;
+                }
+                self.leading(end, false);
+                self.close("}");
+                Ok(())
+            }
+            ImplBodyExpr::Error => Err(invalid(span)),
+        }
+    }
+
+    fn struct_stat(&mut self, (stat, span): &Spanned<StructStat>) -> FmtResult<()> {
+        match stat {
+            StructStat::Documentation((doc, doc_span), stat) => {
+                self.doc_at(doc, doc_span.start);
+                self.struct_stat(stat)
+            }
+            StructStat::Instance((name, _), (ident, _), assignments) => {
+                let text = format!(
+                    "{} = {}{}",
+                    name,
+                    ident_expr(ident),
+                    interface_assignments(assignments)?
+                );
+                self.write(&text);
+                Ok(())
+            }
+            StructStat::Connection((left, _), (right, _)) => {
+                self.write(&format!("{} -- {}", port_sel(left), port_sel(right)));
+                Ok(())
+            }
+            StructStat::Error => Err(invalid(span)),
+        }
+    }
+
+    fn streamlet(&mut self, (expr, span): &Spanned<Expr>) -> FmtResult<()> {
+        match expr {
+            Expr::Ident(ident) => {
+                self.write(&ident_expr(ident));
+                Ok(())
+            }
+            Expr::Documentation((doc, doc_span), expr) => {
+                self.doc_at(doc, doc_span.start);
+                self.streamlet(expr)
+            }
+            Expr::StreamletDef(interface, props) => {
+                self.interface(interface)?;
+                if let Some(props) = props {
+                    self.write(" ");
+                    self.streamlet_props(props)?;
+                }
+                Ok(())
+            }
+            Expr::StreamletProps(_) | Expr::Error => Err(invalid(span)),
+        }
+    }
+
+    fn streamlet_props(&mut self, (props, span): &Spanned<Expr>) -> FmtResult<()> {
+        let props = match props {
+            Expr::StreamletProps(props) => props,
+            _ => return Err(invalid(span)),
+        };
+        let items = props
+            .iter()
+            .map(|prop| {
+                let ((_, keyword_span), StreamletProperty::Implementation((_, impl_span))) = prop;
+                (prop, (keyword_span.start, self.content_end(impl_span)))
+            })
+            .collect();
+        let region = (span.start, self.closing(span, '}')?);
+        self.list(
+            ("{", "}"),
+            true,
+            region,
+            items,
+            Self::inline_streamlet_prop,
+            Self::streamlet_prop,
+        )
+    }
+
+    fn inline_streamlet_prop(
+        &self,
+        (_, StreamletProperty::Implementation((implementation, _))): &StreamletProp,
+    ) -> FmtResult<Option<String>> {
+        Ok(match implementation {
+            StreamletImplExpr::Identity(ident) => Some(format!("impl: {}", ident_expr(ident))),
+            StreamletImplExpr::Def((ImplBodyExpr::Link(path), _)) => {
+                Some(format!("impl: \"{}\"", path))
+            }
+            StreamletImplExpr::Def((ImplBodyExpr::Struct(None, stats), _)) if stats.is_empty() => {
+                Some("impl: {}".to_string())
+            }
+            StreamletImplExpr::Def(_) => None,
+        })
+    }
+
+    fn streamlet_prop(
+        &mut self,
+        (_, StreamletProperty::Implementation((implementation, _))): &StreamletProp,
+    ) -> FmtResult<()> {
+        self.write("impl: ");
+        match implementation {
+            StreamletImplExpr::Identity(ident) => {
+                self.write(&ident_expr(ident));
+                Ok(())
+            }
+            StreamletImplExpr::Def(body) => self.impl_body(body),
+        }
+    }
+}
+
+fn fields_of((fields, span): &Spanned<FieldsDef>) -> FmtResult<&Vec<Field>> {
+    match fields {
+        FieldsDef::Fields(fields) => Ok(fields),
+        FieldsDef::Error => Err(invalid(span)),
+    }
+}
+
+fn variants_of((variants, span): &Spanned<VariantsDef>) -> FmtResult<&Vec<Variant>> {
+    match variants {
+        VariantsDef::Variants(variants) => Ok(variants),
+        VariantsDef::Error => Err(invalid(span)),
+    }
+}
+
+/// The canonical position of a Stream property.
+fn stream_prop_order(prop: &StreamProp) -> usize {
+    match prop {
+        StreamProp::Data(_) => 0,
+        StreamProp::Throughput(_) => 1,
+        StreamProp::Dimensionality(_) => 2,
+        StreamProp::Synchronicity(_) => 3,
+        StreamProp::Complexity(_) => 4,
+        StreamProp::Direction(_) => 5,
+        StreamProp::User(_) => 6,
+        StreamProp::Keep(_) => 7,
+    }
+}
+
+fn path(path: &[Spanned<String>]) -> String {
+    path.iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn ident_expr(ident: &IdentExpr) -> String {
+    match ident {
+        IdentExpr::Name((name, _)) => name.clone(),
+        IdentExpr::PathName(names) => path(names),
+    }
+}
+
+fn domain(domain: &Option<Spanned<String>>) -> String {
+    match domain {
+        Some((domain, _)) => format!(" '{}", domain),
+        None => String::new(),
+    }
+}
+
+fn port_sel(port: &PortSel) -> String {
+    match port {
+        PortSel::Own(port) => port.clone(),
+        PortSel::Instance((instance, _), (port, _)) => format!("{}.{}", instance, port),
+    }
+}
+
+fn value_expr((value, span): &Spanned<GenericParameterValueExpr>) -> FmtResult<String> {
+    Ok(match value {
+        GenericParameterValueExpr::Error => return Err(invalid(span)),
+        GenericParameterValueExpr::Integer(value) => value.to_string(),
+        GenericParameterValueExpr::Ref(name) => name.to_string(),
+        GenericParameterValueExpr::PathRef(path) => path.join("::"),
+        GenericParameterValueExpr::Combination(left, op, right) => {
+            format!("{} {} {}", value_expr(left)?, op, value_expr(right)?)
+        }
+        GenericParameterValueExpr::Parentheses(value) => format!("({})", value_expr(value)?),
+        GenericParameterValueExpr::Negative(value) => {
+            let value = value_expr(value)?;
+            // `--` is the connection operator
+            if value.starts_with('-') {
+                format!("- {}", value)
+            } else {
+                format!("-{}", value)
+            }
+        }
+    })
+}
+
+fn param_assignments(
+    assignments: &[(Option<Name>, Spanned<GenericParameterValueExpr>)],
+) -> FmtResult<String> {
+    let assignments = assignments
+        .iter()
+        .map(|(name, value)| match name {
+            Some(name) => Ok(format!("{} = {}", name, value_expr(value)?)),
+            None => value_expr(value),
+        })
+        .collect::<FmtResult<Vec<_>>>()?;
+    Ok(assignments.join(", "))
+}
+
+fn interface_assignments(
+    (assignments, span): &Spanned<InterfaceParamAssignments>,
+) -> FmtResult<String> {
+    let (domains, params) = match assignments {
+        InterfaceParamAssignments::None => return Ok(String::new()),
+        InterfaceParamAssignments::Error => return Err(invalid(span)),
+        InterfaceParamAssignments::JustDomains(domains) => (domains.as_slice(), &[][..]),
+        InterfaceParamAssignments::JustParams(params) => (&[][..], params.as_slice()),
+        InterfaceParamAssignments::Assignments(domains, params) => {
+            (domains.as_slice(), params.as_slice())
+        }
+    };
+    let mut items = domains
+        .iter()
+        .map(|(instance, (parent, _))| match instance {
+            Some((instance, _)) => format!("'{} = '{}", instance, parent),
+            None => format!("'{}", parent),
+        })
+        .collect::<Vec<_>>();
+    if !params.is_empty() {
+        items.push(param_assignments(params)?);
+    }
+    Ok(format!("<{}>", items.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The tokens of a file, apart from commas, regardless of their order.
+    fn tokens(src: &str) -> Vec<String> {
+        let mut tokens = lexer()
+            .parse(src)
+            .unwrap()
+            .into_iter()
+            .filter(|(token, _)| *token != Token::Ctrl(','))
+            .map(|(token, _)| format!("{:?}", token))
+            .collect::<Vec<_>>();
+        tokens.sort();
+        tokens
+    }
+
+    fn comment_texts(src: &str) -> Vec<String> {
+        let mut texts = comments(&src.chars().collect::<Vec<_>>())
+            .into_iter()
+            .map(|comment| comment.text)
+            .collect::<Vec<_>>();
+        texts.sort();
+        texts
+    }
+
+    /// Format `src`, and check that only its layout changed.
+    fn format(src: &str) -> String {
+        let formatted = format_source(src).unwrap();
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+        assert_eq!(tokens(&formatted), tokens(src));
+        assert_eq!(comment_texts(&formatted), comment_texts(src));
+        formatted
+    }
+
+    #[test]
+    fn layout() {
+        let src = "namespace a::b { import c::d; import c::d::*;
+  const w : natural=8;
+type t<n: natural = 2; > 0 and not = 3,> = Stream(keep: true,
+  data: Group(a: Bits(w),
+  b: Bits(-w + 2 * (n - 1))), dimensionality:1,
+  throughput: 2.0,
+);
+
+
+  type e = Enum(A,B,  C,);
+  type u = t<n = 4>;
+  streamlet s = <'x, 'y>(p: in t 'x, q: out t 'y) { impl: { i = s<'x = 'y, 'y>; p -- i.q; i.p -- q; } };
+  impl i = s { };
+  streamlet l = s {impl:\"./path\",};
+}
+namespace c::d { type x = Array(Bits(8), 4); type long = Group(first: Bits(8), second: Bits(8), third: Bits(8), fourth: Bits(8), fifth: Bits(8)); }";
+        assert_eq!(
+            format(src),
+            "namespace a::b {
+    import c::d;
+    import c::d::*;
+    const w: natural = 8;
+    type t<n: natural = 2; > 0 and not = 3> = Stream(
+        data: Group(a: Bits(w), b: Bits(-w + 2 * (n - 1))),
+        throughput: 2.0,
+        dimensionality: 1,
+        keep: true,
+    );
+
+    type e = Enum(A, B, C);
+    type u = t<n = 4>;
+    streamlet s = <'x, 'y>(p: in t 'x, q: out t 'y) {
+        impl: {
+            i = s<'x = 'y, 'y>;
+            p -- i.q;
+            i.p -- q;
+        },
+    };
+    impl i = s {};
+    streamlet l = s { impl: \"./path\" };
+}
+
+namespace c::d {
+    type x = Array(Bits(8), 4);
+    type long = Group(
+        first: Bits(8),
+        second: Bits(8),
+        third: Bits(8),
+        fourth: Bits(8),
+        fifth: Bits(8),
+    );
+}
+"
+        );
+    }
+
+    #[test]
+    fn comments_are_kept() {
+        let src = "// Header
+
+namespace a { /* first */ type t = Stream( // The stream
+  user: Null, // No user data
+  // The data
+  data: Bits(8), dimensionality: 1 /* one */);
+  type b = Bits(
+      8 // eight
+  );
+  impl i = (a: in t, /* port */ b: out t) {
+      a -- b; // connect
+
+      // empty
+  };
+} // after
+/* end */";
+        assert_eq!(
+            format(src),
+            "// Header
+
+namespace a {
+    /* first */
+    type t = Stream(
+        // The data
+        data: Bits(8),
+        dimensionality: 1, /* one */
+        // The stream
+        user: Null, // No user data
+    );
+    type b = Bits(8); // eight
+    impl i = (
+        a: in t, /* port */
+        b: out t,
+    ) {
+        a -- b; // connect
+        // empty
+    };
+} // after
+/* end */
+"
+        );
+    }
+
+    #[test]
+    fn documentation_is_kept() {
+        let src = "namespace a {
+    ///Line documentation
+    ///
+    ///  continued
+    type t = Group(#a field# a: Bits(1));
+    #Streamlet
+    documentation#
+    streamlet s = (/// A port
+        a: in t) { impl: #Implementation# { /// An instance
+        x = y; } };
+}";
+        assert_eq!(
+            format(src),
+            "namespace a {
+    /// Line documentation
+    ///
+    ///  continued
+    type t = Group(
+        #a field#
+        a: Bits(1),
+    );
+    #Streamlet
+    documentation#
+    streamlet s = (
+        /// A port
+        a: in t,
+    ) {
+        impl: #Implementation# {
+            /// An instance
+            x = y;
+        },
+    };
+}
+"
+        );
+    }
+
+    #[test]
+    fn sample_files() {
+        let paths = glob::glob("../til_vhdl/tests/til_files/**/*.til")
+            .unwrap()
+            .chain(glob::glob("../../demo-cmd/til_samples/**/*.til").unwrap())
+            .chain(glob::glob("*.til").unwrap())
+            .map(|path| path.unwrap())
+            .collect::<Vec<_>>();
+        assert!(!paths.is_empty());
+        for path in paths {
+            let src = std::fs::read_to_string(&path).unwrap();
+            // Some files contain expressions rather than namespaces
+            if let (Some(_), errs, parse_errs) = parse_file(&src) {
+                if errs.is_empty() && parse_errs.is_empty() {
+                    format(&src);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_files() {
+        let diagnostics = format_source("namespace a { type t = ; }").unwrap_err();
+        assert!(diagnostics.iter().all(|diagnostic| diagnostic.is_error()));
+        assert!(!diagnostics.is_empty());
+    }
+}
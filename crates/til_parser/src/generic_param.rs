@@ -10,9 +10,13 @@ use til_query::ir::generics::{
     param_value::{combination::MathOperator, GenericParamValue},
     GenericKind, GenericParameter,
 };
-use tydi_common::{error::Error, name::Name};
+use tydi_common::{
+    error::Error,
+    name::{Name, PathName},
+};
 
 use crate::{
+    ident_expr::path_name,
     lex::{ConditionKeyword, Operator, StreamPropertyKeyword, Token},
     Span, Spanned,
 };
@@ -22,6 +26,8 @@ pub enum GenericParameterValueExpr {
     Error,
     Integer(i32),
     Ref(Name),
+    /// A reference to a constant of another namespace, e.g. `a::b::width`
+    PathRef(PathName),
     Combination(
         Box<Spanned<GenericParameterValueExpr>>,
         MathOperator,
@@ -37,6 +43,7 @@ impl fmt::Display for GenericParameterValueExpr {
             GenericParameterValueExpr::Error => write!(f, "GenericParameterValueExpr::Error"),
             GenericParameterValueExpr::Integer(i) => write!(f, "{}", i),
             GenericParameterValueExpr::Ref(r) => write!(f, "{}", r),
+            GenericParameterValueExpr::PathRef(r) => write!(f, "{}", r.join("::")),
             GenericParameterValueExpr::Combination(l_box, op, r_box) => {
                 write!(f, "{} {} {}", &l_box.0, op, &r_box.0)
             }
@@ -50,7 +57,16 @@ impl fmt::Display for GenericParameterValueExpr {
 pub enum GenericParameterList {
     None,
     Error,
-    List(Vec<Spanned<Result<GenericParameter, Error>>>),
+    List(Vec<Spanned<Result<TypeParameterDef, Error>>>),
+}
+
+/// A parameter of a type declaration.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TypeParameterDef {
+    Parameter(GenericParameter),
+    /// A parameter without a default value, which takes on the default value
+    /// of the parameter of the referenced type it is forwarded to.
+    Forwarded(Name, GenericKind),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -95,6 +111,12 @@ pub fn param_kind() -> impl Parser<Token, GenericKind, Error = Simple<Token>> +
         Token::StreamProperty(StreamPropertyKeyword::Dimensionality) => {
             Ok(GenericKind::from(InterfaceGenericKind::dimensionality()))
         }
+        Token::StreamProperty(StreamPropertyKeyword::Throughput) => {
+            Ok(GenericKind::from(InterfaceGenericKind::throughput()))
+        }
+        Token::StreamProperty(StreamPropertyKeyword::Complexity) => {
+            Ok(GenericKind::from(InterfaceGenericKind::complexity()))
+        }
         _ => Err(Simple::expected_input_found(span, Vec::new(), Some(tok))),
     })
 }
@@ -120,18 +142,19 @@ pub fn param_integer() -> impl Parser<Token, i32, Error = Simple<Token>> + Clone
     negative_integer.or(integer_labelled)
 }
 
-pub fn generic_param_expr(
-) -> impl Parser<Token, Spanned<Result<GenericParameter, Error>>, Error = Simple<Token>> + Clone {
+/// The name, kind and (optional) default value of a parameter.
+type ParamParts = (Name, GenericKind, Option<GenericParamValue>);
+
+fn generic_param_parts(
+    default_value: impl Parser<Token, Option<GenericParamValue>, Error = Simple<Token>> + Clone,
+) -> impl Parser<Token, Spanned<Result<ParamParts, Error>>, Error = Simple<Token>> + Clone {
     // NOTE: It would make more sense to combine the default assignment and condition into the "kind" expression
     // As this would let us choose the right kind of (expected) value and condition.
     // However, at this time we only support integers, so there's not need to get fancy.
     param_name()
         .then_ignore(just(Token::Ctrl(':')))
         .then(param_kind())
-        .then(
-            just(Token::Op(Operator::Eq))
-                .ignore_then(param_integer().map(|i| GenericParamValue::from(i))),
-        )
+        .then(default_value)
         .then(
             just(Token::Ctrl(';'))
                 .ignore_then(generic_param_integer_condition())
@@ -153,25 +176,50 @@ pub fn generic_param_expr(
                                     i.with_condition(c).map(|x| GenericKind::from(x))
                                 }
                             },
-                            GenericKind::Interface(i) => match i {
-                                InterfaceGenericKind::Dimensionality(d) => {
-                                    d.with_condition(c).map(|x| GenericKind::from(x))
-                                }
-                            },
-                        };
-                        match kind_res {
-                            Ok(kind) => {
-                                (GenericParameter::try_new(name, kind, default_value), span)
+                            GenericKind::Interface(i) => {
+                                i.with_condition(c).map(|x| GenericKind::from(x))
                             }
-                            Err(e) => (Err(e), span),
-                        }
+                        };
+                        (kind_res.map(|kind| (name, kind, default_value)), span)
                     }
                 },
-                None => (GenericParameter::try_new(name, kind, default_value), span),
+                None => (Ok((name, kind, default_value)), span),
             },
         )
 }
 
+fn param_default_value() -> impl Parser<Token, GenericParamValue, Error = Simple<Token>> + Clone {
+    just(Token::Op(Operator::Eq)).ignore_then(param_integer().map(|i| GenericParamValue::from(i)))
+}
+
+pub fn generic_param_expr(
+) -> impl Parser<Token, Spanned<Result<GenericParameter, Error>>, Error = Simple<Token>> + Clone {
+    generic_param_parts(param_default_value().map(Some)).map(|(res, span)| {
+        let param = res.and_then(|(name, kind, default_value)| match default_value {
+            Some(default_value) => GenericParameter::try_new(name, kind, default_value),
+            None => Err(Error::ParsingError(format!(
+                "Parameter {} requires a default value",
+                name
+            ))),
+        });
+        (param, span)
+    })
+}
+
+/// A type parameter may omit its default value, as long as it is forwarded to
+/// a parameter of the referenced type.
+pub fn type_param_expr(
+) -> impl Parser<Token, Spanned<Result<TypeParameterDef, Error>>, Error = Simple<Token>> + Clone {
+    generic_param_parts(param_default_value().or_not()).map(|(res, span)| {
+        let param = res.and_then(|(name, kind, default_value)| match default_value {
+            Some(default_value) => GenericParameter::try_new(name, kind, default_value)
+                .map(TypeParameterDef::Parameter),
+            None => Ok(TypeParameterDef::Forwarded(name, kind)),
+        });
+        (param, span)
+    })
+}
+
 pub fn generic_parameters(
 ) -> impl Parser<Token, Vec<Spanned<Result<GenericParameter, Error>>>, Error = Simple<Token>> + Clone
 {
@@ -182,6 +230,16 @@ pub fn generic_parameters(
         .labelled("generic parameters")
 }
 
+pub fn type_parameters(
+) -> impl Parser<Token, Vec<Spanned<Result<TypeParameterDef, Error>>>, Error = Simple<Token>> + Clone
+{
+    type_param_expr()
+        .separated_by(just(Token::Ctrl(',')))
+        .allow_trailing()
+        .at_least(1)
+        .labelled("type parameters")
+}
+
 pub fn generic_parameter_assignment(
 ) -> impl Parser<Token, Spanned<GenericParameterValueExpr>, Error = Simple<Token>> + Clone {
     recursive(|param_assignment| {
@@ -194,8 +252,15 @@ pub fn generic_parameter_assignment(
 
         let ref_n = param_name().map_with_span(|n, span| (GenericParameterValueExpr::Ref(n), span));
 
+        let path_ref = path_name().try_map(|p, span| {
+            PathName::try_new(p.iter().map(|(n, _)| n))
+                .map(|p| (GenericParameterValueExpr::PathRef(p), span.clone()))
+                .map_err(|e| Simple::custom(span, format!("Invalid path name. Error: {}", e)))
+        });
+
         let atom = integer_value
             .or(negative)
+            .or(path_ref)
             .or(ref_n)
             .or(param_assignment
                 .clone()
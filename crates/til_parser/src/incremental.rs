@@ -0,0 +1,552 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use log::debug;
+use til_query::ir::{
+    db::Database,
+    project::{namespace::Namespace, Project},
+    streamlet::Streamlet,
+    traits::GetSelf,
+    Ir,
+};
+use tydi_common::{
+    error::{Error, Result, TryResult, WrapError},
+    name::{PathName, PathNameSelf},
+};
+use tydi_intern::Id;
+
+use crate::{
+    lint::report_lints,
+    project::{import_dependencies_with_report_format, parse_project_file},
+    query::{files_to_project, scan_file, FileNamespaces, SourceFile},
+    report::ReportFormat,
+};
+
+/// A project which keeps its query database alive between changes to its files.
+///
+/// On every [`IncrementalProject::update`], only the files which changed (and
+/// the files importing namespaces from them) are parsed and evaluated again,
+/// together.
+/// The namespaces of all other files are reused as-is.
+pub struct IncrementalProject {
+    db: Database,
+    /// The project without any namespaces, every update starts from this project.
+    base: Project,
+    files: Vec<TrackedFile>,
+    /// The streamlets as of the last successful update.
+    streamlets: BTreeMap<PathName, Id<Arc<Streamlet>>>,
+    /// The project file the project was loaded from, if any.
+    project_file: Option<ProjectFileSource>,
+    /// The directories containing the files of the project, and whether
+    /// their subdirectories can contain files of the project as well.
+    source_dirs: Vec<(PathBuf, bool)>,
+    /// Overrides the output path of the project file.
+    output_path: Option<PathBuf>,
+    /// How the diagnostics of the project's files are printed.
+    report_format: ReportFormat,
+}
+
+/// A project file, and its contents when it was last loaded.
+struct ProjectFileSource {
+    path: PathBuf,
+    /// The variables substituted in the paths of the project file.
+    defines: BTreeMap<String, String>,
+    src: String,
+}
+
+struct TrackedFile {
+    path: PathBuf,
+    link_root: PathBuf,
+    /// The contents of the file when it was last evaluated.
+    src: Option<String>,
+    /// The result of the last evaluation, or None if it failed.
+    evaluated: Option<EvaluatedFile>,
+}
+
+struct EvaluatedFile {
+    namespaces: FileNamespaces,
+    ids: Vec<Id<Namespace>>,
+}
+
+impl IncrementalProject {
+    /// Create a new incremental project for the given project, consisting of
+    /// files and the directories their links are relative to.
+    ///
+    /// Nothing is evaluated until the first call to [`IncrementalProject::update`].
+    pub fn new(base: Project, files: impl IntoIterator<Item = (PathBuf, PathBuf)>) -> Self {
+        let mut db = Database::default();
+        db.set_project(base.clone());
+        let mut result = IncrementalProject {
+            db,
+            base,
+            files: vec![],
+            streamlets: BTreeMap::new(),
+            project_file: None,
+            source_dirs: vec![],
+            output_path: None,
+            report_format: ReportFormat::default(),
+        };
+        result.set_files(files);
+        result.source_dirs = result
+            .files
+            .iter()
+            .filter_map(|file| file.path.parent())
+            .map(|dir| (dir.to_path_buf(), false))
+            .collect();
+        result
+    }
+
+    /// Print the diagnostics of the project's files in the given `format`.
+    pub fn with_report_format(mut self, format: ReportFormat) -> Self {
+        self.report_format = format;
+        self
+    }
+
+    /// Create a new incremental project from a project file.
+    ///
+    /// The project's dependencies are loaded once, changes to their files are
+    /// not picked up.
+    pub fn from_path(proj_file_path: impl TryResult<PathBuf>) -> Result<Self> {
+        Self::from_path_with_defines(proj_file_path, &BTreeMap::new())
+    }
+
+    /// Create a new incremental project from a project file, substituting the
+    /// variables in its paths with `defines` where they are defined.
+    pub fn from_path_with_defines(
+        proj_file_path: impl TryResult<PathBuf>,
+        defines: &BTreeMap<String, String>,
+    ) -> Result<Self> {
+        Self::from_path_with_report_format(proj_file_path, defines, ReportFormat::default())
+    }
+
+    /// Create a new incremental project from a project file, like
+    /// [`IncrementalProject::from_path_with_defines`], printing the
+    /// diagnostics of its files (and those of its dependencies) in the given
+    /// `format`.
+    pub fn from_path_with_report_format(
+        proj_file_path: impl TryResult<PathBuf>,
+        defines: &BTreeMap<String, String>,
+        format: ReportFormat,
+    ) -> Result<Self> {
+        // The project is replaced by that of the project file when it is
+        // loaded.
+        let mut result =
+            Self::new(Project::new("proj", ".", None::<&str>)?, vec![]).with_report_format(format);
+        result.project_file = Some(ProjectFileSource {
+            path: proj_file_path.try_result()?,
+            defines: defines.clone(),
+            src: String::new(),
+        });
+        result.reload()?;
+        Ok(result)
+    }
+
+    /// Read the project file the project was loaded from (if any) again, to
+    /// pick up changes to it, and files added to or removed from the
+    /// directories and glob patterns of its files.
+    ///
+    /// If the project file itself changed, its dependencies are loaded again,
+    /// and all files are evaluated again on the next update. Otherwise, files
+    /// which remain part of the project are only evaluated again when they
+    /// change.
+    pub fn reload(&mut self) -> Result<()> {
+        let source = match &mut self.project_file {
+            Some(source) => source,
+            None => return Ok(()),
+        };
+        let src = std::fs::read_to_string(&source.path)
+            .map_err(|err| Error::FileIOError(format!("Unable to read project file: {}", err)))?;
+        let location = source.path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let mut project_info = parse_project_file(&src)?;
+        project_info.substitute_variables(&location, &source.defines)?;
+        let files = project_info.file_paths(&location)?;
+
+        if src != source.src {
+            let mut base = project_info.to_project(location.clone())?;
+            if let Some(output_path) = &self.output_path {
+                base.set_output_path(Some(output_path.clone()))?;
+            }
+            import_dependencies_with_report_format(
+                &project_info,
+                &location,
+                &self.db,
+                &mut base,
+                &source.defines,
+                self.report_format,
+            )?;
+            self.db.set_project(base.clone());
+            self.base = base;
+            for file in self.files.iter_mut() {
+                file.evaluated = None;
+            }
+            self.streamlets.clear();
+            source.src = src;
+        }
+        self.source_dirs = project_info.source_dirs(&location);
+        self.set_files(files);
+        Ok(())
+    }
+
+    /// Replace the files of the project, files which were already part of the
+    /// project keep their state.
+    fn set_files(&mut self, files: impl IntoIterator<Item = (PathBuf, PathBuf)>) {
+        let mut previous = std::mem::take(&mut self.files);
+        self.files = files
+            .into_iter()
+            .map(|(path, link_root)| {
+                match previous
+                    .iter()
+                    .position(|file| file.path == path && file.link_root == link_root)
+                {
+                    Some(idx) => previous.swap_remove(idx),
+                    None => TrackedFile {
+                        path,
+                        link_root,
+                        src: None,
+                        evaluated: None,
+                    },
+                }
+            })
+            .collect();
+    }
+
+    pub fn db(&self) -> &Database {
+        &self.db
+    }
+
+    /// The files which make up the project.
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|file| file.path.as_path())
+    }
+
+    /// The project file the project was loaded from, if any.
+    pub fn project_file(&self) -> Option<&Path> {
+        self.project_file
+            .as_ref()
+            .map(|source| source.path.as_path())
+    }
+
+    /// The directories containing the files of the project, paired with
+    /// whether files in their subdirectories can be part of the project as
+    /// well.
+    pub fn source_dirs(&self) -> &[(PathBuf, bool)] {
+        &self.source_dirs
+    }
+
+    /// Set the output path of the project, overriding that of the project
+    /// file. Takes effect on the next update.
+    pub fn set_output_path(&mut self, output_path: impl TryResult<PathBuf>) -> Result<()> {
+        let output_path = output_path.try_result()?;
+        self.base.set_output_path(Some(output_path.clone()))?;
+        self.output_path = Some(output_path);
+        Ok(())
+    }
+
+    /// Re-read the project's files, and evaluate the files which changed since
+    /// the last update, as well as any files importing their namespaces.
+    ///
+    /// Returns the names of the streamlets which were added or changed since
+    /// the last successful update.
+    ///
+    /// Files which fail to evaluate are evaluated again on the next update,
+    /// regardless of whether they changed. Their namespaces which failed to
+    /// evaluate are left out of the project.
+    pub fn update(&mut self) -> Result<BTreeSet<PathName>> {
+        let mut project = self.base.clone();
+        let mut result = Ok(());
+
+        let mut sources = vec![];
+        for file in self.files.iter_mut() {
+            match std::fs::read_to_string(&file.path) {
+                Ok(src) => sources.push(Some(src)),
+                Err(err) => {
+                    file.src = None;
+                    file.evaluated = None;
+                    result = result.and(Err(Error::FileIOError(format!(
+                        "Unable to read file {}: {}",
+                        file.path.display(),
+                        err
+                    ))));
+                    sources.push(None);
+                }
+            }
+        }
+
+        // Files which changed, and the namespaces they declared before and after
+        // the change, are dirty. Files importing dirty namespaces are dirty as well.
+        let mut dirty = vec![false; self.files.len()];
+        let mut dirty_namespaces = BTreeSet::new();
+        for (idx, (file, src)) in self.files.iter().zip(sources.iter()).enumerate() {
+            if let Some(src) = src {
+                if file.evaluated.is_none() || file.src.as_ref() != Some(src) {
+                    dirty[idx] = true;
+                    dirty_namespaces.extend(scan_file(src).declared().iter().cloned());
+                    if let Some(evaluated) = &file.evaluated {
+                        dirty_namespaces.extend(evaluated.namespaces.declared().iter().cloned());
+                    }
+                }
+            }
+        }
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (idx, file) in self.files.iter().enumerate() {
+                if let Some(evaluated) = &file.evaluated {
+                    if !dirty[idx]
+                        && sources[idx].is_some()
+                        && !evaluated
+                            .namespaces
+                            .imported()
+                            .is_disjoint(&dirty_namespaces)
+                    {
+                        dirty[idx] = true;
+                        dirty_namespaces.extend(evaluated.namespaces.declared().iter().cloned());
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        for (idx, file) in self.files.iter_mut().enumerate() {
+            if dirty[idx] || sources[idx].is_none() {
+                continue;
+            }
+            if let Some(evaluated) = &file.evaluated {
+                let mut with_file = project.clone();
+                match evaluated
+                    .ids
+                    .iter()
+                    .try_for_each(|id| with_file.add_namespace(&self.db, id.get(&self.db)))
+                {
+                    Ok(()) => project = with_file,
+                    Err(err) => {
+                        file.evaluated = None;
+                        result = result.and(Err(err).wrap_err(Error::ProjectError(format!(
+                            "Error in file \"{}\"",
+                            file.path.display()
+                        ))));
+                    }
+                }
+            }
+        }
+
+        let mut evaluate = vec![];
+        let mut sources_to_evaluate = vec![];
+        for (idx, file) in self.files.iter_mut().enumerate() {
+            if let Some(src) = &sources[idx] {
+                if dirty[idx] {
+                    debug!("Evaluating {}", file.path.display());
+                    file.evaluated = None;
+                    evaluate.push(idx);
+                    sources_to_evaluate.push(
+                        SourceFile::new(src.clone(), file.link_root.clone())?
+                            .with_path(file.path.clone()),
+                    );
+                }
+            }
+        }
+        self.db.set_project(project);
+        let results = files_to_project(&sources_to_evaluate, &mut self.db, self.report_format)?;
+        let project = self.db.project();
+        for (idx, file_result) in evaluate.into_iter().zip(results) {
+            let file = &mut self.files[idx];
+            match file_result {
+                Ok(namespaces) => {
+                    let ids = namespaces
+                        .declared()
+                        .iter()
+                        .map(|name| project.namespaces().try_get(name).copied())
+                        .collect::<Result<Vec<_>>>()?;
+                    file.evaluated = Some(EvaluatedFile { namespaces, ids });
+                }
+                Err(err) => {
+                    result = result.and(Err(err).wrap_err(Error::ProjectError(format!(
+                        "Error in file \"{}\"",
+                        file.path.display()
+                    ))));
+                }
+            }
+        }
+        for (file, src) in self.files.iter_mut().zip(sources) {
+            if src.is_some() {
+                file.src = src;
+            }
+        }
+
+        result?;
+
+        // Lints depend on the whole project, so all files are linted again.
+        let linted = self
+            .files
+            .iter()
+            .filter_map(|file| {
+                file.src.as_ref().map(|src| {
+                    Ok(SourceFile::new(src.clone(), file.link_root.clone())?
+                        .with_path(file.path.clone()))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        report_lints(&linted, &self.db, self.report_format)?;
+
+        let streamlets = streamlet_ids(&self.db);
+        let changed = streamlets
+            .iter()
+            .filter(|(name, id)| self.streamlets.get(name) != Some(id))
+            .map(|(name, _)| name.clone())
+            .collect();
+        self.streamlets = streamlets;
+        Ok(changed)
+    }
+}
+
+/// The (interned) streamlets of every namespace in the project, by their full name.
+///
+/// As interning is structural, a streamlet's Id only changes when its
+/// definition (or a definition it depends on) changes.
+fn streamlet_ids(db: &dyn Ir) -> BTreeMap<PathName, Id<Arc<Streamlet>>> {
+    let mut result = BTreeMap::new();
+    for (_, namespace_id) in db.project().namespaces().iter() {
+        for id in namespace_id.get(db).streamlet_ids().values() {
+            result.insert(id.get(db).path_name().clone(), *id);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(namespace: &str, streamlets: &[&str]) -> String {
+        let mut src = format!(
+            "namespace {} {{\n    type stream = Stream (data: Bits(8), dimensionality: 0, synchronicity: Sync, complexity: 4);\n",
+            namespace
+        );
+        for streamlet in streamlets {
+            src.push_str(&format!(
+                "    streamlet {} = (a: in stream, b: out stream);\n",
+                streamlet
+            ));
+        }
+        src.push('}');
+        src
+    }
+
+    fn names(names: &[&str]) -> BTreeSet<PathName> {
+        names
+            .iter()
+            .map(|name| PathName::try_from(*name).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn only_changed_streamlets() -> Result<()> {
+        let dir = std::env::temp_dir().join("til_parser_incremental_test");
+        std::fs::create_dir_all(&dir)?;
+        let file1 = dir.join("file1.til");
+        let file2 = dir.join("file2.til");
+        std::fs::write(&file1, file("file1", &["comp1"]))?;
+        std::fs::write(&file2, file("file2", &["comp1", "comp2"]))?;
+
+        let mut project = IncrementalProject::new(
+            Project::new("proj", dir.clone(), None::<&str>)?,
+            vec![(file1.clone(), dir.clone()), (file2.clone(), dir.clone())],
+        );
+        assert_eq!(
+            project.update()?,
+            names(&["file1__comp1", "file2__comp1", "file2__comp2"])
+        );
+        assert_eq!(project.update()?, BTreeSet::new());
+
+        std::fs::write(&file2, file("file2", &["comp1", "comp3"]))?;
+        assert_eq!(project.update()?, names(&["file2__comp3"]));
+        assert_eq!(project.db().all_streamlets().len(), 3);
+
+        std::fs::write(
+            &file1,
+            "namespace file1 { streamlet comp1 = (a: in undefined); }",
+        )?;
+        assert!(project.update().is_err());
+        assert_eq!(project.db().all_streamlets().len(), 2);
+
+        std::fs::write(&file1, file("file1", &["comp1"]))?;
+        assert_eq!(project.update()?, BTreeSet::new());
+        assert_eq!(project.db().all_streamlets().len(), 3);
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn imports_from_later_files() -> Result<()> {
+        let dir = std::env::temp_dir().join("til_parser_incremental_imports_test");
+        std::fs::create_dir_all(&dir)?;
+        let file1 = dir.join("file1.til");
+        let file2 = dir.join("file2.til");
+        std::fs::write(
+            &file1,
+            "namespace file1 { import lib::file2; streamlet comp1 = (a: in lib::file2::stream); }",
+        )?;
+        std::fs::write(&file2, file("lib::file2", &["comp1"]))?;
+
+        let mut project = IncrementalProject::new(
+            Project::new("proj", dir.clone(), None::<&str>)?,
+            vec![(file1.clone(), dir.clone()), (file2.clone(), dir.clone())],
+        );
+        assert_eq!(
+            project.update()?,
+            names(&["file1__comp1", "lib__file2__comp1"])
+        );
+
+        // Changing the imported type changes both streamlets
+        std::fs::write(
+            &file2,
+            file("lib::file2", &["comp1"]).replace("Bits(8)", "Bits(4)"),
+        )?;
+        assert_eq!(
+            project.update()?,
+            names(&["file1__comp1", "lib__file2__comp1"])
+        );
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn reload_project_file() -> Result<()> {
+        let dir = std::env::temp_dir().join("til_parser_incremental_reload_test");
+        std::fs::create_dir_all(dir.join("src"))?;
+        let project_file = dir.join("project.toml");
+        std::fs::write(
+            &project_file,
+            "name = \"proj\"\nfiles = [\"src\"]\noutput_path = \"out\"",
+        )?;
+        std::fs::write(dir.join("src/file1.til"), file("file1", &["comp1"]))?;
+
+        let mut project = IncrementalProject::from_path(project_file.clone())?;
+        assert_eq!(project.source_dirs(), &[(dir.join("src"), true)]);
+        assert_eq!(project.update()?, names(&["file1__comp1"]));
+
+        // Files added to a directory of the project are picked up on reload
+        std::fs::write(dir.join("src/file2.til"), file("file2", &["comp1"]))?;
+        project.reload()?;
+        assert_eq!(project.files().count(), 2);
+        assert_eq!(project.update()?, names(&["file2__comp1"]));
+
+        // Changes to the project file itself evaluate all files again
+        std::fs::write(
+            &project_file,
+            "name = \"proj\"\nfiles = [\"src/file2.til\"]\noutput_path = \"out\"",
+        )?;
+        project.reload()?;
+        assert_eq!(project.source_dirs(), &[(dir.join("src"), false)]);
+        assert_eq!(project.update()?, names(&["file2__comp1"]));
+        assert_eq!(project.db().all_streamlets().len(), 1);
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
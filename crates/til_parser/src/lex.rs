@@ -19,6 +19,7 @@ pub enum DeclKeyword {
     LogicalType,
     Namespace,
     Interface,
+    Constant,
 }
 
 impl fmt::Display for DeclKeyword {
@@ -29,6 +30,7 @@ impl fmt::Display for DeclKeyword {
             DeclKeyword::LogicalType => write!(f, "type"),
             DeclKeyword::Namespace => write!(f, "namespace"),
             DeclKeyword::Interface => write!(f, "interface"),
+            DeclKeyword::Constant => write!(f, "const"),
         }
     }
 }
@@ -58,6 +60,8 @@ pub enum TypeKeyword {
     Bits,
     Group,
     Union,
+    Enum,
+    Array,
     Stream,
     Null,
 }
@@ -68,6 +72,8 @@ impl fmt::Display for TypeKeyword {
             TypeKeyword::Bits => write!(f, "Bits"),
             TypeKeyword::Group => write!(f, "Group"),
             TypeKeyword::Union => write!(f, "Union"),
+            TypeKeyword::Enum => write!(f, "Enum"),
+            TypeKeyword::Array => write!(f, "Array"),
             TypeKeyword::Stream => write!(f, "Stream"),
             TypeKeyword::Null => write!(f, "Null"),
         }
@@ -184,7 +190,7 @@ pub enum Token {
     Path(String),
     /// Import keywords: `import`, `as`, `prefixed`
     Import(ImportKeyword),
-    /// Type keywords: `Bits`, `Group`, `Union`, `Stream`, `Null`
+    /// Type keywords: `Bits`, `Group`, `Union`, `Enum`, `Array`, `Stream`, `Null`
     Type(TypeKeyword),
     /// Synchronicity keywords: `Sync`, `Flatten`, `Desync`, `FlatDesync`
     Synchronicity(Synchronicity),
@@ -196,7 +202,8 @@ pub enum Token {
     Op(Operator),
     /// Control characters: `(` `)` `{` `}` `:` `,` `;`
     Ctrl(char),
-    /// Documentation delineated by /* */
+    /// Documentation delineated by `#`, or a sequence of lines starting with
+    /// `///`
     Documentation(String),
     /// Natural or non-negative floating point number
     Num(String),
@@ -276,11 +283,22 @@ pub fn lexer() -> impl Parser<char, Vec<(Token, Span)>, Error = Simple<char>> {
 
     let ctrl = one_of("(){}:,;'<>").map(|c| Token::Ctrl(c));
 
-    let doc = filter(|c| *c != '#')
+    let block_doc = filter(|c| *c != '#')
         .repeated()
         .delimited_by(just('#'), just('#'))
-        .collect::<String>()
-        .map(Token::Documentation);
+        .collect::<String>();
+
+    // Consecutive lines starting with `///` form a single piece of documentation
+    let line_doc = just("///")
+        .ignore_then(just(' ').or_not())
+        .ignore_then(take_until(text::newline().or(end())))
+        .map(|(line, _)| line.into_iter().collect::<String>())
+        .padded()
+        .repeated()
+        .at_least(1)
+        .map(|lines| lines.join("\n"));
+
+    let doc = block_doc.or(line_doc).map(Token::Documentation);
 
     let ident = text::ident().map(|ident: String| match ident.as_str() {
         "import" => Token::Import(ImportKeyword::Import),
@@ -289,6 +307,8 @@ pub fn lexer() -> impl Parser<char, Vec<(Token, Span)>, Error = Simple<char>> {
         "Bits" => Token::Type(TypeKeyword::Bits),
         "Group" => Token::Type(TypeKeyword::Group),
         "Union" => Token::Type(TypeKeyword::Union),
+        "Enum" => Token::Type(TypeKeyword::Enum),
+        "Array" => Token::Type(TypeKeyword::Array),
         "Stream" => Token::Type(TypeKeyword::Stream),
         "Null" => Token::Type(TypeKeyword::Null),
         "Sync" => Token::Synchronicity(Synchronicity::Sync),
@@ -302,6 +322,7 @@ pub fn lexer() -> impl Parser<char, Vec<(Token, Span)>, Error = Simple<char>> {
         "type" => Token::Decl(DeclKeyword::LogicalType),
         "namespace" => Token::Decl(DeclKeyword::Namespace),
         "interface" => Token::Decl(DeclKeyword::Interface),
+        "const" => Token::Decl(DeclKeyword::Constant),
         "true" => Token::Boolean(true),
         "false" => Token::Boolean(false),
         "in" => Token::PortMode(InterfaceDirection::In),
@@ -330,9 +351,46 @@ pub fn lexer() -> impl Parser<char, Vec<(Token, Span)>, Error = Simple<char>> {
         .or(ident)
         .recover_with(skip_then_retry_until([]));
 
-    let single_line = just("//").then(take_until(text::newline())).ignored();
-    let multi_line = just("///").then(take_until(just("///"))).ignored();
-    let comment = multi_line.or(single_line);
+    // Line comments can not start with a third slash, as that makes them documentation
+    let single_line = just("//")
+        .then(filter(|c| *c != '/').rewind().ignored().or(end()))
+        .then(take_until(text::newline().or(end())))
+        .ignored();
+    // Block comments can be nested, e.g. /* /* */ */
+    let block = recursive(|block| {
+        let body = block
+            .or(filter(|c| *c != '*' && *c != '/').ignored())
+            .or(just('*').then(filter(|c| *c != '/').rewind()).ignored())
+            .or(just('/').then(filter(|c| *c != '*').rewind()).ignored());
+        just("/*").then(body.repeated()).then(just("*/")).ignored()
+    });
+    // Comments used to be delineated by `///`, which now starts a line of
+    // documentation. Report comments which still end like that (e.g. `/// a ///`
+    // or `/// a \n b ///`) explicitly, rather than taking them as documentation
+    // or through the errors which result from parsing their contents.
+    let old_comment = just("///")
+        .ignore_then(take_until(just("///")))
+        .then_ignore(
+            one_of(" \t")
+                .repeated()
+                .then(text::newline().or(end()))
+                .rewind(),
+        )
+        .try_map(|(content, _), span| {
+            let content = content.into_iter().collect::<String>();
+            match content.rsplit_once('\n') {
+                Some((_, closing_line)) if !closing_line.trim().is_empty() => Ok(()),
+                None if !content.trim().is_empty() => Ok(()),
+                _ => Err(Simple::custom(span, "Not a comment delineated by ///")),
+            }
+        })
+        .validate(|_, span, emit| {
+            emit(Simple::custom(
+                span,
+                "Comments delineated by /// are no longer supported, as /// starts a line of documentation, use // or /* */ instead",
+            ))
+        });
+    let comment = block.or(old_comment).or(single_line);
 
     token
         .padded()
@@ -344,7 +402,7 @@ pub fn lexer() -> impl Parser<char, Vec<(Token, Span)>, Error = Simple<char>> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chumsky::Parser;
+    use chumsky::{error::SimpleReason, Parser};
     use std::path::Path;
 
     fn test_lex(path: impl AsRef<Path>) {
@@ -367,6 +425,92 @@ mod tests {
         assert_eq!(0, err_len);
     }
 
+    #[test]
+    fn test_comments() {
+        let src = "/* block /* nested # */ still a comment */
+// line comment with a # in it
+/// Line documentation
+///continues # here
+type a = Bits(4 / 2); //
+#block documentation#";
+        let tokens = lexer()
+            .parse(src)
+            .unwrap()
+            .into_iter()
+            .map(|(tok, _)| tok)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Documentation("Line documentation\ncontinues # here".to_string()),
+                Token::Decl(DeclKeyword::LogicalType),
+                Token::Identifier("a".to_string()),
+                Token::Op(Operator::Eq),
+                Token::Type(TypeKeyword::Bits),
+                Token::Ctrl('('),
+                Token::Num("4".to_string()),
+                Token::Op(Operator::Div),
+                Token::Num("2".to_string()),
+                Token::Ctrl(')'),
+                Token::Ctrl(';'),
+                Token::Documentation("block documentation".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_old_multi_line_comments() {
+        let (tokens, errs) = lexer().parse_recovery(
+            "/// multi-line
+comments ///
+type a = Bits(4); /// documentation
+/// continues ///
+type b = a;",
+        );
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].span(), 0..27);
+        assert!(matches!(
+            errs[0].reason(),
+            SimpleReason::Custom(msg) if msg.contains("no longer supported")
+        ));
+        // The comment is skipped, documentation on the same line as code is not
+        // mistaken for the end of a comment
+        let tokens = tokens
+            .unwrap()
+            .into_iter()
+            .map(|(tok, _)| tok)
+            .collect::<Vec<_>>();
+        assert_eq!(tokens[0], Token::Decl(DeclKeyword::LogicalType));
+        assert!(tokens.contains(&Token::Documentation(
+            "documentation\ncontinues ///".to_string()
+        )));
+    }
+
+    #[test]
+    fn test_old_single_line_comments() {
+        let (tokens, errs) = lexer().parse_recovery(
+            "/// comment ///
+/// documentation
+type a = Bits(4); /// comment ///",
+        );
+        assert_eq!(errs.len(), 2);
+        assert_eq!(errs[0].span(), 0..15);
+        assert_eq!(errs[1].span(), 52..67);
+        assert!(errs.iter().all(|err| matches!(
+            err.reason(),
+            SimpleReason::Custom(msg) if msg.contains("no longer supported")
+        )));
+        // The comments are skipped, the documentation in between is not
+        let tokens = tokens
+            .unwrap()
+            .into_iter()
+            .map(|(tok, _)| tok)
+            .collect::<Vec<_>>();
+        assert_eq!(tokens[0], Token::Documentation("documentation".to_string()));
+        assert_eq!(tokens[1], Token::Decl(DeclKeyword::LogicalType));
+        assert_eq!(tokens.last(), Some(&Token::Ctrl(';')));
+    }
+
     #[test]
     fn test_test_til() {
         test_lex("test.til")
@@ -1,11 +1,15 @@
+pub mod diagnostic;
 pub mod doc_expr;
 pub mod eval;
 pub mod expr;
+pub mod format;
 pub mod generic_param;
 pub mod ident_expr;
 pub mod impl_expr;
+pub mod incremental;
 pub mod interface_expr;
 pub mod lex;
+pub mod lint;
 pub mod namespace;
 pub mod project;
 pub mod query;
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+use til_query::ir::{
+    db::Database,
+    lint::{lint_project, LintLevel},
+};
+use tydi_common::{
+    error::{Error, Result},
+    name::PathName,
+};
+
+use crate::{
+    diagnostic::{Diagnostic, Severity},
+    namespace::Statement,
+    query::{parse_file, SourceFile},
+    report::{print_reports_as, ReportFormat},
+    Span,
+};
+
+/// Apply the lints of the project in `db` to the declarations of `files`,
+/// which were evaluated into the project by
+/// [`files_to_project`](crate::query::files_to_project).
+///
+/// Returns the diagnostics of every file: a warning for every declaration
+/// flagged by a lint at the warn level, and an error for every declaration
+/// flagged by a denied lint. Diagnostics point at the name of the declaration.
+pub fn lint_files(files: &[SourceFile], db: &Database) -> Result<Vec<Vec<Diagnostic>>> {
+    let mut declarations: HashMap<(PathName, String), (usize, Span)> = HashMap::new();
+    for (idx, file) in files.iter().enumerate() {
+        if let (Some(ast), _, _) = parse_file(file.src()) {
+            for namespace in ast {
+                let path = match PathName::try_new(namespace.name()) {
+                    Ok(path) => path,
+                    Err(_) => continue,
+                };
+                for stat in namespace.stats() {
+                    if let Statement::Decl(decl) = &stat.0 {
+                        let (name, span) = decl.name();
+                        declarations.insert((path.clone(), name.clone()), (idx, span.clone()));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut diagnostics: Vec<Vec<Diagnostic>> = files.iter().map(|_| vec![]).collect();
+    for message in lint_project(db)? {
+        // Declarations of other files (e.g. evaluated into the project
+        // earlier) are not linted here.
+        let key = (
+            message.namespace().clone(),
+            message.declaration().to_string(),
+        );
+        if let Some((idx, span)) = declarations.get(&key) {
+            let severity = match message.level() {
+                LintLevel::Deny => Severity::Error,
+                _ => Severity::Warning,
+            };
+            let diagnostic = Diagnostic::new(severity, message.message(), span.clone())
+                .with_label(span.clone(), message.message())
+                .with_note(format!(
+                    "Lint level: {} = \"{}\"",
+                    message.lint(),
+                    message.level()
+                ));
+            diagnostics[*idx].push(match files[*idx].path() {
+                Some(path) => diagnostic.with_file(path),
+                None => diagnostic,
+            });
+        }
+    }
+    Ok(diagnostics)
+}
+
+/// Apply the lints of the project in `db` to the declarations of `files`,
+/// like [`lint_files`], and print the diagnostics to stderr in the given
+/// `format`.
+///
+/// Returns an error if any declaration was flagged by a denied lint.
+pub fn report_lints(files: &[SourceFile], db: &Database, format: ReportFormat) -> Result<()> {
+    let mut denied = vec![];
+    for (file, diagnostics) in files.iter().zip(lint_files(files, db)?) {
+        print_reports_as(file.src(), &diagnostics, format)?;
+        if diagnostics.iter().any(Diagnostic::is_error) {
+            denied.push(match file.path() {
+                Some(path) => format!("\"{}\"", path.display()),
+                None => "<unknown>".to_string(),
+            });
+        }
+    }
+    if denied.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::ProjectError(format!(
+            "Denied lints in file {}, see report.",
+            denied.join(", ")
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use til_query::ir::{lint::Lint, project::Project, Ir};
+
+    use crate::query::files_to_project_with_diagnostics;
+
+    use super::*;
+
+    #[test]
+    fn lint_files() -> Result<()> {
+        let mut db = Database::default();
+        let mut project = Project::new("proj", ".", None::<&str>)?;
+        project.set_lint_level(Lint::UnusedParameter, LintLevel::Deny);
+        db.set_project(project);
+        let files = [
+            SourceFile::new(
+                "namespace a { type byte = Bits(8); type octet = Bits(8); type word<w: positive = 8, x: natural = 0> = Bits(w); }",
+                ".",
+            )?
+            .with_path("a.til"),
+            SourceFile::new(
+                "namespace b { import a::byte; import a::word; streamlet s = (x: in Stream(data: byte, dimensionality: 0, synchronicity: Sync, complexity: 4), y: out Stream(data: word<4>, dimensionality: 0, synchronicity: Sync, complexity: 4)); }",
+                ".",
+            )?,
+        ];
+        for (result, _) in files_to_project_with_diagnostics(&files, &mut db) {
+            result?;
+        }
+
+        let diagnostics = super::lint_files(&files, &db)?;
+        // Declarations are used when they are referred to by name, not when
+        // a used type is defined the same way
+        let unused = diagnostics[0]
+            .iter()
+            .filter(|diagnostic| !diagnostic.is_error())
+            .map(|diagnostic| (diagnostic.message(), diagnostic.span().clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(unused, vec![("Type a::octet is never used", 40..46)]);
+        let denied = diagnostics[0]
+            .iter()
+            .filter(|diagnostic| diagnostic.is_error())
+            .collect::<Vec<_>>();
+        assert_eq!(denied.len(), 1);
+        let diagnostic = denied[0];
+        assert_eq!(
+            diagnostic.message(),
+            "Parameter x of type a::word is never used"
+        );
+        assert_eq!(diagnostic.span(), &(62..66));
+        assert_eq!(diagnostic.file(), Some(std::path::Path::new("a.til")));
+        assert_eq!(
+            diagnostic.notes(),
+            &["Lint level: unused-parameter = \"deny\"".to_string()]
+        );
+        assert!(diagnostics[1].is_empty());
+        Ok(())
+    }
+}
@@ -1,10 +1,14 @@
 use chumsky::prelude::*;
 use std::hash::Hash;
+use til_query::ir::generics::GenericKind;
 
 use crate::{
     doc_expr::{doc_expr, DocExpr},
     expr::{doc_parser, expr_parser, Expr},
-    generic_param::{generic_parameters, GenericParameterList},
+    generic_param::{
+        generic_parameter_assignment, param_kind, type_parameters, GenericParameterList,
+        GenericParameterValueExpr,
+    },
     ident_expr::{ident_expr, name, path_name, IdentExpr},
     impl_expr::{impl_def_expr, ImplDefExpr},
     interface_expr::{interface_expr, InterfaceExpr},
@@ -16,19 +20,58 @@ use crate::{
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Decl {
     TypeDecl(
+        DocExpr,
         Spanned<String>,
         Spanned<TypeExpr>,
         Spanned<GenericParameterList>,
     ),
     ImplDecl(DocExpr, Spanned<String>, Spanned<ImplDefExpr>),
-    InterfaceDecl(Spanned<String>, Spanned<InterfaceExpr>),
+    InterfaceDecl(DocExpr, Spanned<String>, Spanned<InterfaceExpr>),
     StreamletDecl(Option<String>, Spanned<String>, Box<Spanned<Expr>>),
+    ConstDecl(
+        DocExpr,
+        Spanned<String>,
+        Spanned<GenericKind>,
+        Spanned<GenericParameterValueExpr>,
+    ),
+}
+
+impl Decl {
+    /// The name of the declaration, and its span.
+    pub fn name(&self) -> &Spanned<String> {
+        match self {
+            Decl::TypeDecl(_, name, _, _)
+            | Decl::ImplDecl(_, name, _)
+            | Decl::InterfaceDecl(_, name, _)
+            | Decl::StreamletDecl(_, name, _)
+            | Decl::ConstDecl(_, name, _, _) => name,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Import {
-    /// Import an entire namespace
+    /// Import an entire namespace, or a single declaration of a namespace
     FullImport(Spanned<Vec<Spanned<String>>>),
+    /// Import an entire namespace under an alias (`import a::b as ab;`)
+    AliasedImport(Spanned<Vec<Spanned<String>>>, Spanned<String>),
+    /// Import the declarations of a namespace by their names, with a prefix
+    /// (`import a::b prefixed ab_;`)
+    PrefixedImport(Spanned<Vec<Spanned<String>>>, Spanned<String>),
+    /// Import the declarations of a namespace by their names (`import a::b::*;`)
+    GlobImport(Spanned<Vec<Spanned<String>>>),
+}
+
+impl Import {
+    /// The path being imported.
+    pub fn path(&self) -> &Spanned<Vec<Spanned<String>>> {
+        match self {
+            Import::FullImport(path)
+            | Import::AliasedImport(path, _)
+            | Import::PrefixedImport(path, _)
+            | Import::GlobImport(path) => path,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -67,13 +110,40 @@ pub fn namespaces_parser() -> impl Parser<Token, Vec<Namespace>, Error = Simple<
 
     let import_stat = just(Token::Import(ImportKeyword::Import))
         .ignore_then(path_name().map_with_span(|p, span| (p, span)))
+        .then(
+            just(Token::Op(Operator::Path))
+                .then(just(Token::Op(Operator::Mul)))
+                .or_not(),
+        )
+        .then(
+            just(Token::Import(ImportKeyword::As))
+                .or(just(Token::Import(ImportKeyword::Prefixed)))
+                .then(name())
+                .or_not(),
+        )
         .then_ignore(just(Token::Ctrl(';')))
-        .map_with_span(|n, span| (Statement::Import(Import::FullImport(n)), span));
-
-    let type_decl = just(Token::Decl(DeclKeyword::LogicalType))
-        .ignore_then(name())
+        .try_map(|((n, glob), modifier), span| {
+            let import = match (glob, modifier) {
+                (Some(_), None) => Import::GlobImport(n),
+                (Some(_), Some(_)) => {
+                    return Err(Simple::custom(
+                        span,
+                        "A glob import can not have an alias or prefix",
+                    ))
+                }
+                (None, Some((Token::Import(ImportKeyword::As), alias))) => {
+                    Import::AliasedImport(n, alias)
+                }
+                (None, Some((_, prefix))) => Import::PrefixedImport(n, prefix),
+                (None, None) => Import::FullImport(n),
+            };
+            Ok((Statement::Import(import), span))
+        });
+
+    let type_decl = doc_expr()
+        .then(just(Token::Decl(DeclKeyword::LogicalType)).ignore_then(name()))
         .then(
-            generic_parameters()
+            type_parameters()
                 .delimited_by(just(Token::Ctrl('<')), just(Token::Ctrl('>')))
                 .map(|x| GenericParameterList::List(x))
                 .or_not()
@@ -90,7 +160,7 @@ pub fn namespaces_parser() -> impl Parser<Token, Vec<Namespace>, Error = Simple<
         )
         .then_ignore(just(Token::Op(Operator::Eq)))
         .then(type_expr())
-        .map(|((n, g), e)| Decl::TypeDecl(n, e, g));
+        .map(|(((doc, n), g), e)| Decl::TypeDecl(doc, n, e, g));
 
     let impl_decl = doc_expr()
         .then(just(Token::Decl(DeclKeyword::Implementation)).ignore_then(name()))
@@ -98,11 +168,11 @@ pub fn namespaces_parser() -> impl Parser<Token, Vec<Namespace>, Error = Simple<
         .then(impl_def_expr())
         .map(|((doc, name), body)| Decl::ImplDecl(doc, name, body));
 
-    let interface_decl = just(Token::Decl(DeclKeyword::Interface))
-        .ignore_then(name())
+    let interface_decl = doc_expr()
+        .then(just(Token::Decl(DeclKeyword::Interface)).ignore_then(name()))
         .then_ignore(just(Token::Op(Operator::Eq)))
         .then(interface_expr())
-        .map(|(n, e)| Decl::InterfaceDecl(n, e));
+        .map(|((doc, n), e)| Decl::InterfaceDecl(doc, n, e));
 
     let streamlet_decl = just(Token::Decl(DeclKeyword::Streamlet))
         .ignore_then(name())
@@ -114,10 +184,19 @@ pub fn namespaces_parser() -> impl Parser<Token, Vec<Namespace>, Error = Simple<
     let streamlet_decl = doc_streamlet_decl
         .or(streamlet_decl.map(|(n, e)| Decl::StreamletDecl(None, n, Box::new(e))));
 
+    let const_decl = doc_expr()
+        .then(just(Token::Decl(DeclKeyword::Constant)).ignore_then(name()))
+        .then_ignore(just(Token::Ctrl(':')))
+        .then(param_kind().map_with_span(|k, span| (k, span)))
+        .then_ignore(just(Token::Op(Operator::Eq)))
+        .then(generic_parameter_assignment())
+        .map(|(((doc, n), k), v)| Decl::ConstDecl(doc, n, k, v));
+
     let decl = type_decl
         .or(impl_decl)
         .or(interface_decl)
         .or(streamlet_decl)
+        .or(const_decl)
         .then_ignore(just(Token::Ctrl(';')))
         .map_with_span(|d, span| (Statement::Decl(d), span));
 
@@ -188,4 +267,29 @@ mod tests {
     fn test_simple_generics_til() {
         test_namespace_parse(source("simple_generics.til"))
     }
+
+    #[test]
+    fn test_imports() {
+        test_namespace_parse(
+            "namespace a::c {
+                import a::b;
+                import a::b::stream_t;
+                import a::b as ab;
+                import a::b prefixed ab_;
+                import a::b::*;
+            }",
+        )
+    }
+
+    #[test]
+    fn test_constants() {
+        test_namespace_parse(
+            "namespace a::c {
+                import a::b;
+                const width: natural = 8;
+                const double_width: positive = 2 * (width + a::b::offset);
+                type t = Bits(double_width);
+            }",
+        )
+    }
 }
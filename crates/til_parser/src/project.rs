@@ -1,22 +1,200 @@
-use std::path::PathBuf;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    path::{Path, PathBuf},
+};
 
-use serde::Deserialize;
-use til_query::ir::{db::Database, project::Project, Ir};
-use tydi_common::error::{Error, Result, TryResult, WrapError};
+use serde::{
+    de::{self, value::MapAccessDeserializer, MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use til_query::ir::{
+    db::Database,
+    lint::{Lint, LintLevel},
+    project::Project,
+    Ir,
+};
+use tydi_common::{
+    error::{Error, Result, TryResult, WrapError},
+    name::{Name, PathName},
+};
 
-use crate::query::file_to_project;
+use crate::{
+    diagnostic::Diagnostic,
+    lint::{lint_files, report_lints},
+    query::{files_to_project, files_to_project_with_diagnostics, SourceFile},
+    report::ReportFormat,
+};
+
+/// The file name of a project file, used when a dependency refers to a directory.
+pub const PROJECT_FILE_NAME: &str = "project.toml";
+
+/// The backends a project file can select.
+pub const BACKENDS: [&str; 1] = ["vhdl"];
+
+/// The variable referring to the directory containing the project file.
+pub const PROJECT_DIR_VARIABLE: &str = "project_dir";
 
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ProjectFile {
     name: String,
-    files: Vec<String>,
+    files: Vec<FileEntry>,
     output_path: String,
     config: Option<ConfigKeys>,
+    #[serde(default)]
+    backend: BackendKeys,
+    #[serde(default)]
+    emit: EmitKeys,
+    /// Other projects, by the name their namespaces are imported through,
+    /// and the path to their directory (or project file).
+    #[serde(default)]
+    dependencies: BTreeMap<String, String>,
+    /// The levels of lints, by the name of the lint.
+    #[serde(default)]
+    lints: BTreeMap<String, String>,
 }
 
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ConfigKeys {
+    #[serde(default)]
     link_relative_to_file: bool,
+    /// What the backend should do when the file of a linked implementation
+    /// does not exist.
+    missing_link: Option<String>,
+}
+
+/// The `[backend]` table, selecting the backend and its options.
+///
+/// The values of the options are validated by the backend itself.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct BackendKeys {
+    /// The backend to use, "vhdl" if omitted.
+    name: Option<String>,
+    /// Whether to indent with "spaces" or "tabs".
+    indent_style: Option<String>,
+    /// The number of spaces or tabs per level of indentation.
+    indent_width: Option<usize>,
+    /// The VHDL standard to target.
+    vhdl_standard: Option<String>,
+    /// The library the generated package is compiled into.
+    library: Option<String>,
+    /// How to name the files of streamlets' architectures.
+    file_naming: Option<String>,
+    /// Whether to declare the constants of all namespaces in the package.
+    export_constants: Option<bool>,
+}
+
+impl BackendKeys {
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(BACKENDS[0])
+    }
+
+    pub fn indent_style(&self) -> Option<&str> {
+        self.indent_style.as_deref()
+    }
+
+    pub fn indent_width(&self) -> Option<usize> {
+        self.indent_width
+    }
+
+    pub fn vhdl_standard(&self) -> Option<&str> {
+        self.vhdl_standard.as_deref()
+    }
+
+    pub fn library(&self) -> Option<&str> {
+        self.library.as_deref()
+    }
+
+    pub fn file_naming(&self) -> Option<&str> {
+        self.file_naming.as_deref()
+    }
+
+    pub fn export_constants(&self) -> Option<bool> {
+        self.export_constants
+    }
+}
+
+/// The `[emit]` table, selecting the namespaces to emit.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct EmitKeys {
+    /// Only emit these namespaces (and the namespaces nested within them),
+    /// emit all namespaces if empty.
+    #[serde(default)]
+    include: Vec<String>,
+    /// Do not emit these namespaces (or the namespaces nested within them).
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+/// An entry of the `files` list.
+///
+/// Either a path (or glob pattern, or directory) relative to the project file,
+/// or a table with a `path` and the `link_root` its links are relative to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+    path: String,
+    link_root: Option<String>,
+}
+
+impl FileEntry {
+    pub fn path(&self) -> &str {
+        self.path.as_ref()
+    }
+
+    pub fn link_root(&self) -> Option<&str> {
+        self.link_root.as_deref()
+    }
+
+    fn is_glob(&self) -> bool {
+        self.path.contains(['*', '?', '['])
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileTable {
+    path: String,
+    link_root: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for FileEntry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct FileEntryVisitor;
+
+        impl<'de> Visitor<'de> for FileEntryVisitor {
+            type Value = FileEntry;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a path, or a table with a path and link_root")
+            }
+
+            fn visit_str<E: de::Error>(self, path: &str) -> std::result::Result<FileEntry, E> {
+                Ok(FileEntry {
+                    path: path.to_string(),
+                    link_root: None,
+                })
+            }
+
+            fn visit_map<M: MapAccess<'de>>(
+                self,
+                map: M,
+            ) -> std::result::Result<FileEntry, M::Error> {
+                // Deserializing the table separately preserves serde's errors
+                // for unknown and missing keys.
+                let table = FileTable::deserialize(MapAccessDeserializer::new(map))?;
+                Ok(FileEntry {
+                    path: table.path,
+                    link_root: table.link_root,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(FileEntryVisitor)
+    }
 }
 
 impl ProjectFile {
@@ -24,7 +202,7 @@ impl ProjectFile {
         self.name.as_ref()
     }
 
-    pub fn files(&self) -> &[String] {
+    pub fn files(&self) -> &[FileEntry] {
         self.files.as_ref()
     }
 
@@ -42,52 +220,837 @@ impl ProjectFile {
             None => false,
         }
     }
+
+    pub fn config_missing_link(&self) -> Option<&str> {
+        self.config()
+            .as_ref()
+            .and_then(|config| config.missing_link.as_deref())
+    }
+
+    pub fn dependencies(&self) -> &BTreeMap<String, String> {
+        &self.dependencies
+    }
+
+    pub fn backend(&self) -> &BackendKeys {
+        &self.backend
+    }
+
+    /// The levels of the lints configured in the `[lints]` table.
+    pub fn lints(&self) -> Result<Vec<(Lint, LintLevel)>> {
+        self.lints
+            .iter()
+            .map(|(lint, level)| Ok((lint.parse()?, level.parse()?)))
+            .collect::<Result<_>>()
+            .map_err(|err| Error::ProjectError(format!("Invalid [lints] table: {}", err)))
+    }
+
+    /// The namespaces to emit, all namespaces if empty.
+    pub fn emit_include(&self) -> Result<Vec<PathName>> {
+        emit_namespaces(&self.emit.include)
+    }
+
+    /// The namespaces not to emit.
+    pub fn emit_exclude(&self) -> Result<Vec<PathName>> {
+        emit_namespaces(&self.emit.exclude)
+    }
+
+    /// Check the values which can be validated without a backend.
+    fn validate(&self) -> Result<()> {
+        let backend = self.backend();
+        if !BACKENDS.contains(&backend.name()) {
+            return Err(Error::ProjectError(format!(
+                "Unsupported backend \"{}\", expected one of: {}",
+                backend.name(),
+                BACKENDS.join(", ")
+            )));
+        }
+        if let Some(library) = backend.library() {
+            Name::try_new(library)
+                .map_err(|err| Error::ProjectError(format!("Invalid [backend] table: {}", err)))?;
+        }
+        self.emit_include()?;
+        self.emit_exclude()?;
+        self.lints()?;
+        Ok(())
+    }
+
+    /// Substitute the variables (`${NAME}`) in the output path, files, link
+    /// roots and dependency paths of this project file, located at `location`.
+    ///
+    /// [`PROJECT_DIR_VARIABLE`] refers to `location`, other variables are
+    /// taken from `defines`, or the environment if they are not defined.
+    pub fn substitute_variables(
+        &mut self,
+        location: &Path,
+        defines: &BTreeMap<String, String>,
+    ) -> Result<()> {
+        let project_dir = std::env::current_dir()?.join(location);
+        let project_dir = project_dir.canonicalize().unwrap_or(project_dir);
+        let lookup = |name: &str| {
+            if name == PROJECT_DIR_VARIABLE {
+                Some(project_dir.display().to_string())
+            } else {
+                defines
+                    .get(name)
+                    .cloned()
+                    .or_else(|| std::env::var(name).ok())
+            }
+        };
+        let mut undefined = BTreeSet::new();
+
+        substitute(&mut self.output_path, lookup, &mut undefined)?;
+        for entry in self.files.iter_mut() {
+            substitute(&mut entry.path, lookup, &mut undefined)?;
+            if let Some(link_root) = &mut entry.link_root {
+                substitute(link_root, lookup, &mut undefined)?;
+            }
+        }
+        for path in self.dependencies.values_mut() {
+            substitute(path, lookup, &mut undefined)?;
+        }
+
+        if undefined.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::ProjectError(format!(
+                "Undefined variables in project {}: {}",
+                self.name(),
+                undefined.into_iter().collect::<Vec<_>>().join(", ")
+            )))
+        }
+    }
+}
+
+/// Replace the variables (`${NAME}`) in `value`, adding the names of variables
+/// for which `lookup` has no value to `undefined`.
+fn substitute(
+    value: &mut String,
+    lookup: impl Fn(&str) -> Option<String>,
+    undefined: &mut BTreeSet<String>,
+) -> Result<()> {
+    let mut result = String::new();
+    let mut rest = value.as_str();
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| Error::ProjectError(format!("Unclosed variable in \"{}\"", value)))?
+            + start;
+        let name = &rest[start + 2..end];
+        match lookup(name) {
+            Some(substitution) => result.push_str(&substitution),
+            None => {
+                undefined.insert(name.to_string());
+            }
+        }
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    *value = result;
+    Ok(())
+}
+
+fn emit_namespaces(namespaces: &[String]) -> Result<Vec<PathName>> {
+    namespaces
+        .iter()
+        .map(|namespace| {
+            PathName::try_from(namespace.as_str()).wrap_err(Error::ProjectError(format!(
+                "\"{}\" is not a valid namespace",
+                namespace
+            )))
+        })
+        .collect()
 }
 
 pub fn from_path(proj_file_path: impl TryResult<PathBuf>) -> Result<Database> {
+    from_path_with_defines(proj_file_path, &BTreeMap::new())
+}
+
+/// Load a project file, substituting the variables in its paths (and those of
+/// its dependencies) with `defines` where they are defined.
+pub fn from_path_with_defines(
+    proj_file_path: impl TryResult<PathBuf>,
+    defines: &BTreeMap<String, String>,
+) -> Result<Database> {
+    from_path_with_report_format(proj_file_path, defines, ReportFormat::default())
+}
+
+/// Load a project file, like [`from_path_with_defines`], printing the
+/// diagnostics of its files (and those of its dependencies) in the given
+/// `format`.
+pub fn from_path_with_report_format(
+    proj_file_path: impl TryResult<PathBuf>,
+    defines: &BTreeMap<String, String>,
+    format: ReportFormat,
+) -> Result<Database> {
     let mut proj_file_path = proj_file_path.try_result()?;
     let src = std::fs::read_to_string(&proj_file_path)
         .map_err(|err| Error::FileIOError(format!("Unable to read project file: {}", err)))?;
     proj_file_path.pop();
-    into_query_storage(src, proj_file_path)
+    load_project(src, proj_file_path, &mut vec![], defines, Some(format))
+}
+
+/// Read and parse a project file, and substitute the variables in its paths.
+pub fn read_project_file(proj_file_path: impl AsRef<Path>) -> Result<ProjectFile> {
+    read_project_file_with_defines(proj_file_path, &BTreeMap::new())
+}
+
+/// Read and parse a project file, and substitute the variables in its paths
+/// with `defines` where they are defined.
+pub fn read_project_file_with_defines(
+    proj_file_path: impl AsRef<Path>,
+    defines: &BTreeMap<String, String>,
+) -> Result<ProjectFile> {
+    let proj_file_path = proj_file_path.as_ref();
+    let src = std::fs::read_to_string(proj_file_path)
+        .map_err(|err| Error::FileIOError(format!("Unable to read project file: {}", err)))?;
+    let mut project_file = parse_project_file(src)?;
+    project_file.substitute_variables(proj_file_path.parent().unwrap_or(Path::new("")), defines)?;
+    Ok(project_file)
+}
+
+/// Parse and validate the contents of a project file.
+pub fn parse_project_file(src: impl AsRef<str>) -> Result<ProjectFile> {
+    let project_file: ProjectFile = toml::from_str(src.as_ref())
+        .map_err(|err| Error::ProjectError(format!("Unable to parse the project file: {}", err)))?;
+    project_file.validate()?;
+    Ok(project_file)
+}
+
+impl ProjectFile {
+    /// Create an (empty) project based on this project file, located at `location`.
+    pub fn to_project(&self, location: impl TryResult<PathBuf>) -> Result<Project> {
+        let mut project = Project::new(self.name(), location, Some(self.output_path()))?;
+        for (lint, level) in self.lints()? {
+            project.set_lint_level(lint, level);
+        }
+        Ok(project)
+    }
+
+    /// The paths of the files in the project, paired with the directory their
+    /// links are relative to.
+    ///
+    /// Glob patterns are expanded in alphabetical order, and must match at
+    /// least one file. Directories are expanded to all `.til` files within
+    /// them (recursively). Files matched by multiple entries are only included
+    /// once, for the first entry matching them.
+    pub fn file_paths(&self, location: &Path) -> Result<Vec<(PathBuf, PathBuf)>> {
+        let mut result: Vec<(PathBuf, PathBuf)> = vec![];
+        for entry in self.files() {
+            let paths = if entry.is_glob() {
+                expand_glob(location, entry.path())?
+            } else if location.join(entry.path()).is_dir() {
+                expand_dir(location, entry.path())?
+            } else {
+                vec![location.join(entry.path())]
+            };
+            for file_location in paths {
+                if result.iter().any(|(path, _)| path == &file_location) {
+                    continue;
+                }
+                let link_root = match (entry.link_root(), self.config_link_relative_to_file()) {
+                    (Some(link_root), _) => location.join(link_root),
+                    (None, true) => {
+                        let mut root = file_location.clone();
+                        root.pop();
+                        root
+                    }
+                    (None, false) => location.to_path_buf(),
+                };
+                result.push((file_location, link_root));
+            }
+        }
+        Ok(result)
+    }
+
+    /// The directories containing the files of the project, paired with
+    /// whether files in their subdirectories can be part of the project as
+    /// well. Files added to these directories may match the project's
+    /// directories and glob patterns.
+    pub fn source_dirs(&self, location: &Path) -> Vec<(PathBuf, bool)> {
+        self.files()
+            .iter()
+            .map(|entry| {
+                let path = location.join(entry.path());
+                if entry.is_glob() {
+                    // The directory up to the first component with a pattern
+                    let mut dir = location.to_path_buf();
+                    let mut components = Path::new(entry.path()).components();
+                    for component in components.by_ref() {
+                        if component
+                            .as_os_str()
+                            .to_string_lossy()
+                            .contains(['*', '?', '['])
+                        {
+                            break;
+                        }
+                        dir.push(component);
+                    }
+                    (dir, components.next().is_some())
+                } else if path.is_dir() {
+                    (path, true)
+                } else {
+                    let mut dir = path;
+                    dir.pop();
+                    (dir, false)
+                }
+            })
+            .collect()
+    }
+}
+
+fn expand_glob(location: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let full_pattern = location.join(pattern);
+    let paths = glob::glob(&full_pattern.to_string_lossy())
+        .map_err(|err| {
+            Error::ProjectError(format!("Invalid file pattern \"{}\": {}", pattern, err))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|err| Error::FileIOError(err.to_string()))?;
+    if paths.is_empty() {
+        Err(Error::ProjectError(format!(
+            "File pattern \"{}\" does not match any files",
+            pattern
+        )))
+    } else {
+        Ok(paths)
+    }
+}
+
+fn expand_dir(location: &Path, dir: &str) -> Result<Vec<PathBuf>> {
+    let pattern = format!(
+        "{}/**/*.til",
+        glob::Pattern::escape(&location.join(dir).to_string_lossy())
+    );
+    let paths = glob::glob(&pattern)
+        .map_err(|err| Error::ProjectError(format!("Invalid directory \"{}\": {}", dir, err)))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|err| Error::FileIOError(err.to_string()))?;
+    if paths.is_empty() {
+        Err(Error::ProjectError(format!(
+            "Directory \"{}\" does not contain any .til files",
+            dir
+        )))
+    } else {
+        Ok(paths)
+    }
 }
 
 pub fn into_query_storage(
     src: impl Into<String>,
     location: impl TryResult<PathBuf>,
 ) -> Result<Database> {
-    let src = src.into();
-    let project_info: ProjectFile = toml::from_str(&src)
-        .map_err(|err| Error::ProjectError(format!("Unable to parse the project file: {}", err)))?;
+    load_project(
+        src.into(),
+        location.try_result()?,
+        &mut vec![],
+        &BTreeMap::new(),
+        Some(ReportFormat::default()),
+    )
+}
+
+/// Load a project, `loading` lists the locations of the projects which
+/// (indirectly) depend on it, to detect cyclical dependencies.
+///
+/// If `report` is set, the diagnostics of the project's files are printed to
+/// stderr in that format.
+fn load_project(
+    src: String,
+    location: PathBuf,
+    loading: &mut Vec<PathBuf>,
+    defines: &BTreeMap<String, String>,
+    report: Option<ReportFormat>,
+) -> Result<Database> {
+    let mut project_info = parse_project_file(src)?;
+    project_info.substitute_variables(&location, defines)?;
     let mut db = Database::default();
-    let location: PathBuf = location.try_result()?;
 
-    db.set_project(Project::new(
-        project_info.name(),
-        location.clone(),
-        Some(project_info.output_path()),
-    )?);
+    let mut project = project_info.to_project(location.clone())?;
+    import_dependencies_checked(
+        &project_info,
+        &location,
+        &db,
+        &mut project,
+        loading,
+        defines,
+        report,
+    )?;
+    db.set_project(project);
 
-    for file in project_info.files() {
-        let mut file_location = location.clone();
-        file_location.push(file);
+    let mut files = vec![];
+    for (file_location, link_root) in project_info.file_paths(&location)? {
         let file_src = std::fs::read_to_string(&file_location).map_err(|err| {
-            Error::FileIOError(format!(
-                "Unable to read file from project: {}",
-                err.to_string()
-            ))
+            Error::FileIOError(format!("Unable to read file from project: {}", err))
         })?;
-        let link_root = match project_info.config_link_relative_to_file() {
-            true => {
-                let mut root = file_location.clone();
-                root.pop();
-                root
-            }
-            false => location.clone(),
-        };
-        file_to_project(file_src, &mut db, link_root)
-            .wrap_err(Error::ProjectError(format!("Error in file \"{}\"", file)))?;
+        // Files are identified relative to the project
+        let file = file_location
+            .strip_prefix(&location)
+            .unwrap_or(&file_location);
+        files.push(SourceFile::new(file_src, link_root)?.with_path(file));
+    }
+
+    let file_error = |file: &SourceFile| {
+        Error::ProjectError(format!(
+            "Error in file \"{}\"",
+            file.path().unwrap().display()
+        ))
+    };
+    if let Some(format) = report {
+        for (file, result) in files.iter().zip(files_to_project(&files, &mut db, format)?) {
+            result.wrap_err(file_error(file))?;
+        }
+        report_lints(&files, &db, format)?;
+    } else {
+        for (file, (result, _)) in files
+            .iter()
+            .zip(files_to_project_with_diagnostics(&files, &mut db))
+        {
+            result.wrap_err(file_error(file))?;
+        }
+        if lint_files(&files, &db)?
+            .iter()
+            .flatten()
+            .any(Diagnostic::is_error)
+        {
+            return Err(Error::ProjectError(format!(
+                "Denied lints in project {}",
+                project_info.name()
+            )));
+        }
     }
 
     Ok(db)
 }
+
+/// Load the dependencies of the project file located at `location`, and
+/// import them into `project`, moving their declarations into `db`.
+///
+/// The variables in the paths of the dependencies are substituted with
+/// `defines` where they are defined.
+pub fn import_dependencies(
+    project_info: &ProjectFile,
+    location: &Path,
+    db: &Database,
+    project: &mut Project,
+    defines: &BTreeMap<String, String>,
+) -> Result<()> {
+    import_dependencies_with_report_format(
+        project_info,
+        location,
+        db,
+        project,
+        defines,
+        ReportFormat::default(),
+    )
+}
+
+/// Load the dependencies of a project file, like [`import_dependencies`],
+/// printing the diagnostics of their files in the given `format`.
+pub fn import_dependencies_with_report_format(
+    project_info: &ProjectFile,
+    location: &Path,
+    db: &Database,
+    project: &mut Project,
+    defines: &BTreeMap<String, String>,
+    format: ReportFormat,
+) -> Result<()> {
+    import_dependencies_checked(
+        project_info,
+        location,
+        db,
+        project,
+        &mut vec![],
+        defines,
+        Some(format),
+    )
+}
+
+/// Load the dependencies of a project file, like [`import_dependencies`],
+/// without printing the reports of their files.
+///
+/// Errors in the dependencies are still returned, for use where diagnostics
+/// are reported by other means (e.g. the language server).
+pub fn import_dependencies_quiet(
+    project_info: &ProjectFile,
+    location: &Path,
+    db: &Database,
+    project: &mut Project,
+    defines: &BTreeMap<String, String>,
+) -> Result<()> {
+    import_dependencies_checked(
+        project_info,
+        location,
+        db,
+        project,
+        &mut vec![],
+        defines,
+        None,
+    )
+}
+
+fn import_dependencies_checked(
+    project_info: &ProjectFile,
+    location: &Path,
+    db: &Database,
+    project: &mut Project,
+    loading: &mut Vec<PathBuf>,
+    defines: &BTreeMap<String, String>,
+    report: Option<ReportFormat>,
+) -> Result<()> {
+    if project_info.dependencies().is_empty() {
+        return Ok(());
+    }
+    let canonical = location
+        .canonicalize()
+        .unwrap_or_else(|_| location.to_path_buf());
+    if loading.contains(&canonical) {
+        return Err(Error::ProjectError(format!(
+            "Project {} has a cyclical dependency on itself",
+            project_info.name()
+        )));
+    }
+    loading.push(canonical);
+
+    for (name, path) in project_info.dependencies() {
+        let dependency_error = || {
+            Error::ProjectError(format!(
+                "Unable to load dependency \"{}\" of project {}",
+                name,
+                project_info.name()
+            ))
+        };
+        let mut dep_file_path = location.join(path);
+        if dep_file_path.is_dir() {
+            dep_file_path.push(PROJECT_FILE_NAME);
+        }
+        let dep_src = std::fs::read_to_string(&dep_file_path)
+            .map_err(|err| {
+                Error::FileIOError(format!(
+                    "Unable to read project file {}: {}",
+                    dep_file_path.display(),
+                    err
+                ))
+            })
+            .wrap_err(dependency_error())?;
+        let mut dep_location = dep_file_path.clone();
+        dep_location.pop();
+        let dep_db = load_project(dep_src, dep_location, loading, defines, report)
+            .wrap_err(dependency_error())?;
+        project
+            .import_project_as(db, &dep_db.project(), &dep_db, name.as_str())
+            .wrap_err(dependency_error())?;
+    }
+
+    loading.pop();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_keys() {
+        let err = parse_project_file(
+            "name = \"proj\"\nfiles = []\noutput_path = \"out\"\noutput = \"out\"",
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("unknown field `output`"));
+
+        let err = parse_project_file(
+            "name = \"proj\"\nfiles = [{ path = \"a.til\", root = \"a\" }]\noutput_path = \"out\"",
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("unknown field `root`"));
+
+        let err = parse_project_file(
+            "name = \"proj\"\nfiles = []\noutput_path = \"out\"\n[backend]\nname = \"verilog\"",
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("Unsupported backend \"verilog\""));
+
+        let err = parse_project_file(
+            "name = \"proj\"\nfiles = []\noutput_path = \"out\"\n[backend]\nlibrary = \"2lib\"",
+        )
+        .err()
+        .unwrap();
+        assert!(
+            err.to_string().contains("Invalid [backend] table"),
+            "{}",
+            err
+        );
+        assert!(
+            err.to_string().contains("name cannot start with a digit"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn file_entries() -> Result<()> {
+        let dir = std::env::temp_dir().join("til_parser_project_test");
+        std::fs::create_dir_all(dir.join("src"))?;
+        for file in ["src/b.til", "src/a.til", "c.til"] {
+            std::fs::write(dir.join(file), "")?;
+        }
+
+        let project_file = parse_project_file(
+            r#"
+name = "proj"
+files = [ "c.til", { path = "src/*.til", link_root = "src" } ]
+output_path = "out"
+
+[backend]
+indent_width = 4
+vhdl_standard = "1993"
+library = "my_lib"
+
+[emit]
+exclude = [ "my::test" ]
+"#,
+        )?;
+        assert_eq!(
+            project_file.file_paths(&dir)?,
+            vec![
+                (dir.join("c.til"), dir.clone()),
+                (dir.join("src/a.til"), dir.join("src")),
+                (dir.join("src/b.til"), dir.join("src")),
+            ]
+        );
+        assert_eq!(
+            project_file.source_dirs(&dir),
+            vec![(dir.clone(), false), (dir.join("src"), false)]
+        );
+        assert_eq!(project_file.backend().name(), "vhdl");
+        assert_eq!(project_file.backend().indent_width(), Some(4));
+        assert_eq!(project_file.backend().vhdl_standard(), Some("1993"));
+        assert_eq!(project_file.backend().library(), Some("my_lib"));
+        assert_eq!(project_file.emit_include()?, vec![]);
+        assert_eq!(
+            project_file.emit_exclude()?,
+            vec![PathName::try_from("my::test")?]
+        );
+
+        let project_file =
+            parse_project_file("name = \"proj\"\nfiles = [\"*.vhd\"]\noutput_path = \"out\"")?;
+        assert!(project_file.file_paths(&dir).is_err());
+
+        let project_file = parse_project_file(
+            "name = \"proj\"\nfiles = [\"src\", \"lib/**/*.til\"]\noutput_path = \"out\"",
+        )?;
+        assert_eq!(
+            project_file.source_dirs(&dir),
+            vec![(dir.join("src"), true), (dir.join("lib"), true)]
+        );
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn cross_file_imports() -> Result<()> {
+        let dir = std::env::temp_dir().join("til_parser_cross_file_test");
+        std::fs::create_dir_all(dir.join("src/nested"))?;
+        std::fs::write(
+            dir.join("src/a.til"),
+            "namespace my::app { import my::types; type word = Group(a: my::types::byte, b: my::types::byte); }",
+        )?;
+        std::fs::write(
+            dir.join("src/nested/b.til"),
+            "namespace my::types { type byte = Bits(8); }",
+        )?;
+        let project_src = "name = \"proj\"\nfiles = [\"src\"]\noutput_path = \"out\"";
+
+        let project_file = parse_project_file(project_src)?;
+        assert_eq!(
+            project_file.file_paths(&dir)?,
+            vec![
+                (dir.join("src/a.til"), dir.clone()),
+                (dir.join("src/nested/b.til"), dir.clone()),
+            ]
+        );
+        let db = into_query_storage(project_src, &dir)?;
+        assert_eq!(db.project().namespaces().len(), 2);
+
+        // The files import from one another, but their namespaces do not
+        std::fs::write(
+            dir.join("src/nested/b.til"),
+            "namespace my::types { type byte = Bits(8); }\nnamespace my::other { import my::app; type word = my::app::word; }",
+        )?;
+        let db = into_query_storage(project_src, &dir)?;
+        assert_eq!(db.project().namespaces().len(), 3);
+
+        std::fs::write(
+            dir.join("src/nested/b.til"),
+            "namespace my::types { import my::app; type byte = Bits(8); }",
+        )?;
+        let err = into_query_storage(project_src, &dir).err().unwrap();
+        assert!(err
+            .to_string()
+            .contains("Import error, namespaces my__app, my__types have a cyclical dependency"));
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn lints() -> Result<()> {
+        let dir = std::env::temp_dir().join("til_parser_lints_test");
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(
+            dir.join("a.til"),
+            "namespace a { type byte = Bits(8); streamlet empty = (); }",
+        )?;
+        let project_src = |lints: &str| {
+            format!(
+                "name = \"proj\"\nfiles = [\"a.til\"]\noutput_path = \"out\"\n[lints]\n{}",
+                lints
+            )
+        };
+
+        let db = into_query_storage(project_src("empty-streamlet = \"allow\""), &dir)?;
+        let project = db.project();
+        assert_eq!(project.lint_level(Lint::EmptyStreamlet), LintLevel::Allow);
+        assert_eq!(project.lint_level(Lint::UnusedDeclaration), LintLevel::Warn);
+
+        let err = into_query_storage(project_src("unused-declaration = \"deny\""), &dir)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Denied lints in file \"a.til\""));
+
+        let err = parse_project_file(project_src("unused = \"deny\""))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("\"unused\" is not a lint"));
+        let err = parse_project_file(project_src("unused-domain = \"forbid\""))
+            .err()
+            .unwrap();
+        assert!(err
+            .to_string()
+            .contains("\"forbid\" is not a valid lint level"));
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn variables() -> Result<()> {
+        let dir = std::env::temp_dir().join("til_parser_variables_test");
+        std::fs::create_dir_all(&dir)?;
+        let mut project_file = parse_project_file(
+            r#"
+name = "proj"
+files = [ "${project_dir}/a.til", { path = "b.til", link_root = "${LIBS}/vhdl" } ]
+output_path = "${OUT}/${OUT}"
+
+[dependencies]
+lib = "${LIBS}/lib"
+"#,
+        )?;
+        let defines = BTreeMap::from([
+            ("LIBS".to_string(), "/opt/libs".to_string()),
+            ("OUT".to_string(), "out".to_string()),
+        ]);
+        project_file.substitute_variables(&dir, &defines)?;
+        let dir = dir.canonicalize()?;
+        assert_eq!(project_file.output_path(), "out/out");
+        assert_eq!(
+            project_file.files()[0].path(),
+            format!("{}/a.til", dir.display())
+        );
+        assert_eq!(project_file.files()[1].link_root(), Some("/opt/libs/vhdl"));
+        assert_eq!(project_file.dependencies()["lib"], "/opt/libs/lib");
+
+        let mut project_file = parse_project_file(
+            "name = \"proj\"\nfiles = [\"${TIL_TEST_B}/${TIL_TEST_A}\"]\noutput_path = \"${TIL_TEST_A}\"",
+        )?;
+        let err = project_file
+            .substitute_variables(&dir, &BTreeMap::new())
+            .err()
+            .unwrap();
+        assert!(err
+            .to_string()
+            .contains("Undefined variables in project proj: TIL_TEST_A, TIL_TEST_B"));
+
+        let mut project_file =
+            parse_project_file("name = \"proj\"\nfiles = []\noutput_path = \"${OUT\"")?;
+        assert!(project_file.substitute_variables(&dir, &defines).is_err());
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn dependencies() -> Result<()> {
+        let dir = std::env::temp_dir().join("til_parser_dependencies_test");
+        let write_project = |name: &str, deps: &str, src: &str| -> Result<()> {
+            std::fs::create_dir_all(dir.join(name))?;
+            std::fs::write(
+                dir.join(name).join(PROJECT_FILE_NAME),
+                format!(
+                    "name = \"{}\"\nfiles = [\"{}.til\"]\noutput_path = \"out\"\n[dependencies]\n{}",
+                    name, name, deps
+                ),
+            )?;
+            std::fs::write(dir.join(name).join(format!("{}.til", name)), src)?;
+            Ok(())
+        };
+        write_project("lib", "", "namespace types { type byte = Bits(8); }")?;
+        write_project(
+            "app",
+            "lib = \"../lib\"",
+            "namespace app { import lib::types; type word = Group(a: lib::types::byte, b: lib::types::byte); }",
+        )?;
+
+        let db = from_path(dir.join("app").join(PROJECT_FILE_NAME))?;
+        assert!(db
+            .project()
+            .imported_namespace(&PathName::try_from("lib::types")?)
+            .is_some());
+        assert_eq!(db.project().namespaces().len(), 1);
+
+        // A dependency which is also a dependency of another dependency
+        write_project(
+            "util",
+            "lib = \"../lib\"",
+            "namespace util { import lib::types; type half = lib::types::byte; }",
+        )?;
+        write_project(
+            "app",
+            "lib = \"../lib\"\nutil = \"../util\"",
+            "namespace app { import lib::types; import util::util; type word = Group(a: lib::types::byte, b: util::util::half); }",
+        )?;
+        let db = from_path(dir.join("app").join(PROJECT_FILE_NAME))?;
+        assert_eq!(db.project().imports().len(), 2);
+
+        // A dependency of a dependency using the same name for another project
+        write_project("other", "", "namespace types { type byte = Bits(4); }")?;
+        write_project(
+            "util",
+            "lib = \"../other\"",
+            "namespace util { import lib::types; type half = lib::types::byte; }",
+        )?;
+        let err = from_path(dir.join("app").join(PROJECT_FILE_NAME))
+            .err()
+            .unwrap();
+        assert!(err
+            .to_string()
+            .contains("as lib already refers to project lib"));
+
+        // A dependency on itself
+        std::fs::write(
+            dir.join("lib").join(PROJECT_FILE_NAME),
+            "name = \"lib\"\nfiles = [\"lib.til\"]\noutput_path = \"out\"\n[dependencies]\napp = \"../app\"",
+        )?;
+        let err = from_path(dir.join("app").join(PROJECT_FILE_NAME))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("cyclical dependency"));
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -1,7 +1,10 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
 
-use chumsky::{Parser, Stream};
-use petgraph::algo::toposort;
+use chumsky::{prelude::Simple, Parser, Stream};
+use petgraph::visit::{DfsPostOrder, Walker};
 use til_query::ir::{
     db::Database,
     project::{namespace::Namespace, Project},
@@ -9,18 +12,27 @@ use til_query::ir::{
     Ir,
 };
 use tydi_common::{
-    error::{Error, TryResult},
-    name::PathNameSelf,
+    error::{Error, Result, TryResult},
+    name::{Name, PathName, PathNameSelf},
 };
 
 use crate::{
-    eval::{eval_decl::eval_declaration, eval_import::build_dependency_graph},
-    lex::lexer,
-    namespace::{namespaces_parser, Statement},
-    report::{report_errors, report_eval_errors},
+    diagnostic::Diagnostic,
+    eval::{
+        eval_decl::eval_declaration,
+        eval_import::{
+            build_dependency_graph, find_import_cycles, import_stat::ImportStatement,
+            nodes::NamespaceNode,
+        },
+        Declarations, EvalError, Imports,
+    },
+    lex::{lexer, Token},
+    namespace::{namespaces_parser, Namespace as ParsedNamespace, Statement},
+    report::{print_reports_as, ReportFormat},
+    Spanned,
 };
 
-pub fn into_query_storage_default(src: impl Into<String>) -> tydi_common::error::Result<Database> {
+pub fn into_query_storage_default(src: impl Into<String>) -> Result<Database> {
     let mut db = Database::default();
     db.set_project(Project::new("proj", ".", None::<&str>)?);
 
@@ -32,7 +44,7 @@ pub fn into_query_storage_default(src: impl Into<String>) -> tydi_common::error:
 pub fn into_query_storage_default_with_output(
     src: impl Into<String>,
     output_path: impl TryResult<PathBuf>,
-) -> tydi_common::error::Result<Database> {
+) -> Result<Database> {
     let mut db = Database::default();
     db.set_project(Project::new("proj", ".", Some(output_path))?);
 
@@ -45,7 +57,7 @@ pub fn into_query_storage(
     src: impl Into<String>,
     project: impl TryResult<Project>,
     link_root: impl TryResult<PathBuf>,
-) -> tydi_common::error::Result<Database> {
+) -> Result<Database> {
     let mut db = Database::default();
     db.set_project(project.try_result()?);
 
@@ -54,14 +66,71 @@ pub fn into_query_storage(
     Ok(db)
 }
 
-pub fn file_to_project(
-    src: impl Into<String>,
-    db: &mut Database,
-    link_root: impl TryResult<PathBuf>,
-) -> tydi_common::error::Result<()> {
-    let src = src.into();
-    let link_root = link_root.try_result()?;
-    let (tokens, errs) = lexer().parse_recovery(src.as_str());
+/// The namespaces declared by a single file, and the namespaces declared
+/// elsewhere which it imports.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileNamespaces {
+    declared: Vec<PathName>,
+    imported: BTreeSet<PathName>,
+}
+
+impl FileNamespaces {
+    /// The namespaces declared in the file, in the order they were evaluated.
+    pub fn declared(&self) -> &[PathName] {
+        self.declared.as_ref()
+    }
+
+    /// The namespaces imported by the file which are not declared by the file itself.
+    pub fn imported(&self) -> &BTreeSet<PathName> {
+        &self.imported
+    }
+}
+
+/// Determine the namespaces declared and imported by a file, without
+/// evaluating it.
+///
+/// Files which cannot be parsed are considered to declare and import nothing,
+/// their errors are reported once they are evaluated.
+pub fn scan_file(src: &str) -> FileNamespaces {
+    let mut result = FileNamespaces::default();
+    if let (Some(ast), errs, parse_errs) = parse_file(src) {
+        if errs.is_empty() && parse_errs.is_empty() {
+            for namespace in ast {
+                if let Ok(name) = PathName::try_new(namespace.name()) {
+                    result.declared.push(name);
+                }
+                for stat in namespace.stats() {
+                    if let Statement::Import(import) = &stat.0 {
+                        let (path, _) = import.path();
+                        if let Ok(path) = PathName::try_new(path.iter().map(|(n, _)| n)) {
+                            // The path may refer to a single declaration of a namespace
+                            result.imported.insert(path.root());
+                            result.imported.insert(path);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    for declared in result.declared.iter() {
+        result.imported.remove(declared);
+    }
+    result
+}
+
+/// The namespaces parsed from a file (if any), and the lexing and parsing errors.
+pub type ParsedFile = (
+    Option<Vec<ParsedNamespace>>,
+    Vec<Simple<char>>,
+    Vec<Simple<Token>>,
+);
+
+/// Lex and parse a file, without evaluating it.
+///
+/// Parsing recovers from errors where possible, so the namespaces may be
+/// returned alongside errors.
+pub fn parse_file(src: &str) -> ParsedFile {
+    let (tokens, errs) = lexer().parse_recovery(src);
     let (ast, parse_errs) = if let Some(tokens) = tokens {
         let len = src.chars().count();
         let (ast, parse_errs) =
@@ -71,112 +140,434 @@ pub fn file_to_project(
     } else {
         (None, Vec::new())
     };
-    if errs.len() > 0 || parse_errs.len() > 0 {
-        report_errors(&src, errs, parse_errs);
-        return Err(Error::ParsingError(
-            "Errors during parsing, see report.".to_string(),
-        ));
+    (ast, errs, parse_errs)
+}
+
+/// The contents of a file, and the directory its links are relative to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFile {
+    src: String,
+    link_root: PathBuf,
+    /// The path of the file, used to identify it in diagnostics.
+    path: Option<PathBuf>,
+}
+
+impl SourceFile {
+    pub fn new(src: impl Into<String>, link_root: impl TryResult<PathBuf>) -> Result<Self> {
+        Ok(SourceFile {
+            src: src.into(),
+            link_root: link_root.try_result()?,
+            path: None,
+        })
     }
-    let mut eval_errors = vec![];
 
-    if let Some(ast) = ast {
-        let di_graph = build_dependency_graph(ast, &mut eval_errors)?;
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
 
-        if eval_errors.len() > 0 {
-            report_eval_errors(&src, eval_errors.clone());
-            return Err(Error::ProjectError(
-                "Errors while attempting to resolve imports, see report.".to_string(),
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn src(&self) -> &str {
+        self.src.as_str()
+    }
+
+    pub fn link_root(&self) -> &PathBuf {
+        &self.link_root
+    }
+}
+
+/// Parse and evaluate a single file, adding its namespaces to the project in `db`.
+///
+/// Namespaces may import the namespaces already in the project.
+pub fn file_to_project(
+    src: impl Into<String>,
+    db: &mut Database,
+    link_root: impl TryResult<PathBuf>,
+) -> Result<FileNamespaces> {
+    files_to_project(
+        &[SourceFile::new(src, link_root)?],
+        db,
+        ReportFormat::default(),
+    )?
+    .pop()
+    .unwrap()
+}
+
+/// Parse and evaluate a single file, like [`file_to_project`], returning the
+/// diagnostics of the file rather than printing them.
+pub fn file_to_project_with_diagnostics(
+    src: impl Into<String>,
+    db: &mut Database,
+    link_root: impl TryResult<PathBuf>,
+) -> Result<(Result<FileNamespaces>, Vec<Diagnostic>)> {
+    Ok(
+        files_to_project_with_diagnostics(&[SourceFile::new(src, link_root)?], db)
+            .pop()
+            .unwrap(),
+    )
+}
+
+/// Parse and evaluate the files of a project, adding their namespaces to the
+/// project in `db`.
+///
+/// Namespaces may import namespaces declared in any of the files, or already
+/// in the project, regardless of the order of the files. All files are parsed
+/// first, after which their namespaces are evaluated after the namespaces they
+/// import.
+///
+/// Returns the result of every file. Namespaces which failed to evaluate (or
+/// which import namespaces which failed to evaluate) are left out of the
+/// project, all other namespaces are added to it.
+///
+/// The diagnostics of every file are printed to stderr in the given `format`,
+/// failing to print them is returned as an error.
+pub fn files_to_project(
+    files: &[SourceFile],
+    db: &mut Database,
+    format: ReportFormat,
+) -> Result<Vec<Result<FileNamespaces>>> {
+    files
+        .iter()
+        .zip(files_to_project_with_diagnostics(files, db))
+        .map(|(file, (result, diagnostics))| {
+            print_reports_as(file.src(), &diagnostics, format)?;
+            Ok(result)
+        })
+        .collect()
+}
+
+/// Parse and evaluate the files of a project, like [`files_to_project`].
+///
+/// Returns the result of every file paired with its diagnostics, rather than
+/// printing them.
+pub fn files_to_project_with_diagnostics(
+    files: &[SourceFile],
+    db: &mut Database,
+) -> Vec<(Result<FileNamespaces>, Vec<Diagnostic>)> {
+    let mut results: Vec<Result<FileNamespaces>> = files
+        .iter()
+        .map(|_| Ok(FileNamespaces::default()))
+        .collect();
+    let mut diagnostics: Vec<Vec<Diagnostic>> = files.iter().map(|_| vec![]).collect();
+    let mut parsed = vec![];
+    for ((file, result), diagnostics) in files
+        .iter()
+        .zip(results.iter_mut())
+        .zip(diagnostics.iter_mut())
+    {
+        let (ast, errs, parse_errs) = parse_file(file.src());
+        if !errs.is_empty() || !parse_errs.is_empty() {
+            diagnostics.extend(errs.into_iter().map(Diagnostic::from));
+            diagnostics.extend(parse_errs.into_iter().map(Diagnostic::from));
+            *result = Err(Error::ParsingError(
+                "Errors during parsing, see report.".to_string(),
             ));
         }
+        parsed.push(ast.unwrap_or_default());
+    }
 
-        let namespace_nodes = match toposort(&di_graph, None) {
-            Ok(sorted_nodes) => Ok(sorted_nodes),
-            Err(cycle) => Err(Error::ProjectError(format!(
-                "Import error, namespace {} has a cyclical dependency.",
-                di_graph[cycle.node_id()].path_name()
-            ))),
-        }?
+    let mut eval_errors = files.iter().map(|_| vec![]).collect::<Vec<_>>();
+    let di_graph = match build_dependency_graph(parsed, &db.project(), &mut eval_errors) {
+        Ok(di_graph) => di_graph,
+        Err(err) => {
+            return diagnostics
+                .into_iter()
+                .map(|diagnostics| (Err(err.clone()), diagnostics))
+                .collect()
+        }
+    };
+    let mut failed = find_import_cycles(&di_graph, &mut eval_errors)
         .into_iter()
-        .rev()
-        .map(|idx| di_graph.node_weight(idx).unwrap());
-
-        for namespace_node in namespace_nodes {
-            let mut type_imports = HashMap::new();
-            let mut interface_imports = HashMap::new();
-            let mut implementation_imports = HashMap::new();
-            let mut streamlet_imports = HashMap::new();
-            for (imported_space, _) in namespace_node.imports() {
-                let imported_space = db.project().namespaces().try_get(imported_space)?.get(db);
-                for (name, decl) in imported_space.type_decls() {
-                    type_imports.insert(imported_space.path_name().with_child(name), decl.clone());
-                }
-                for (name, id) in imported_space.interface_ids() {
-                    interface_imports.insert(imported_space.path_name().with_child(name), *id);
-                }
-                for (name, id) in imported_space.implementation_ids() {
-                    implementation_imports.insert(imported_space.path_name().with_child(name), *id);
-                }
-                for (name, id) in imported_space.streamlet_ids() {
-                    streamlet_imports.insert(imported_space.path_name().with_child(name), *id);
+        .flatten()
+        .collect::<BTreeSet<_>>();
+    for ((errors, result), diagnostics) in eval_errors
+        .iter_mut()
+        .zip(results.iter_mut())
+        .zip(diagnostics.iter_mut())
+    {
+        if !errors.is_empty() {
+            diagnostics.extend(std::mem::take(errors).into_iter().map(Diagnostic::from));
+            *result = Err(Error::ProjectError(if failed.is_empty() {
+                "Errors while attempting to resolve imports, see report.".to_string()
+            } else {
+                format!(
+                    "Import error, namespaces {} have a cyclical dependency, see report.",
+                    failed
+                        .iter()
+                        .map(|name| name.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }));
+        }
+    }
+
+    // A post-order traversal visits the namespaces a namespace imports before
+    // the namespace itself.
+    let mut order = vec![];
+    let mut dfs = DfsPostOrder::empty(&di_graph);
+    for idx in di_graph.node_indices() {
+        if !dfs.discovered.contains(idx.index()) {
+            dfs.move_to(idx);
+            order.extend((&mut dfs).iter(&di_graph));
+        }
+    }
+
+    for namespace_node in order.into_iter().map(|idx| &di_graph[idx]) {
+        let file = namespace_node.file;
+        if results[file].is_err() {
+            failed.insert(namespace_node.path_name().clone());
+            continue;
+        }
+        if let Some(import) = namespace_node
+            .imports()
+            .keys()
+            .find(|import| failed.contains(*import))
+        {
+            results[file] = Err(Error::ProjectError(format!(
+                "Unable to evaluate namespace {}, as the namespace it imports, {}, could not be evaluated.",
+                namespace_node.path_name(),
+                import
+            )));
+            failed.insert(namespace_node.path_name().clone());
+            continue;
+        }
+        match eval_namespace(db, &files[file], namespace_node, &mut eval_errors[file]) {
+            Ok(true) => {
+                if let Ok(file_namespaces) = &mut results[file] {
+                    file_namespaces
+                        .declared
+                        .push(namespace_node.path_name().clone());
+                    file_namespaces
+                        .imported
+                        .extend(namespace_node.imports().keys().cloned());
                 }
             }
-            // TODO: Imports currently left immutable as they are unused.
-            let mut types = HashMap::new();
-            let mut interfaces = HashMap::new();
-            let mut implementations = HashMap::new();
-            let mut streamlets = HashMap::new();
-            for stat in namespace_node.namespace.stats().iter() {
-                if let Statement::Decl(decl) = &stat.0 {
-                    let eval_result = eval_declaration(
-                        db,
-                        &link_root,
-                        decl,
-                        namespace_node.path_name(),
-                        &mut streamlets,
-                        &streamlet_imports,
-                        &mut implementations,
-                        &implementation_imports,
-                        &mut interfaces,
-                        &interface_imports,
-                        &mut types,
-                        &type_imports,
-                    );
-
-                    if let Err(err) = eval_result {
-                        eval_errors.push(err);
-                    }
-                }
+            Ok(false) => {
+                failed.insert(namespace_node.path_name().clone());
+            }
+            Err(err) => {
+                results[file] = Err(err);
+                failed.insert(namespace_node.path_name().clone());
             }
+        }
+    }
 
-            // Don't bother doing more work if evaluation failed at any point, just use the errors to provide a useful report.
-            if eval_errors.len() == 0 {
-                let mut namespace = Namespace::new(namespace_node.path_name().clone())?;
+    for ((errors, result), diagnostics) in eval_errors
+        .into_iter()
+        .zip(results.iter_mut())
+        .zip(diagnostics.iter_mut())
+    {
+        if !errors.is_empty() {
+            diagnostics.extend(errors.into_iter().map(Diagnostic::from));
+            *result = Err(Error::ProjectError(
+                "Errors during evaluation, see report.".to_string(),
+            ));
+        }
+        if let Ok(file_namespaces) = result {
+            for declared in file_namespaces.declared.iter() {
+                file_namespaces.imported.remove(declared);
+            }
+        }
+    }
 
-                for (name, type_id) in types {
-                    namespace.import_type(name, type_id)?;
-                }
-                for (name, interface_id) in interfaces {
-                    namespace.import_interface(name, interface_id)?;
-                }
-                for (name, implementation_id) in implementations {
-                    namespace.import_implementation(name, implementation_id)?;
-                }
-                for (name, streamlet_id) in streamlets {
-                    namespace.import_streamlet(name, streamlet_id)?;
+    results
+        .into_iter()
+        .zip(diagnostics)
+        .zip(files)
+        .map(|((result, diagnostics), file)| match file.path() {
+            Some(path) => (
+                result,
+                diagnostics
+                    .into_iter()
+                    .map(|diagnostic| diagnostic.with_file(path))
+                    .collect(),
+            ),
+            None => (result, diagnostics),
+        })
+        .collect()
+}
+
+/// Import the declarations `decls` of the namespace `import_path` through
+/// `import_stat`.
+fn import_decls<T: Clone>(
+    imports: &mut Imports<T>,
+    import_path: &PathName,
+    import_stat: &Spanned<ImportStatement>,
+    decls: &BTreeMap<Name, T>,
+    eval_errors: &mut Vec<EvalError>,
+) {
+    for (name, decl) in decls {
+        match import_stat.0.reference(name) {
+            Ok(Some(reference)) => imports.insert(
+                reference,
+                import_path.with_child(name.clone()),
+                decl.clone(),
+            ),
+            Ok(None) => (),
+            Err(err) => eval_errors.push(EvalError::new(
+                &import_stat.1,
+                format!(
+                    "Unable to import {}: {}",
+                    import_path.with_child(name.clone()),
+                    err
+                ),
+            )),
+        }
+    }
+}
+
+/// Evaluate the declarations of a namespace, and add the namespace to the
+/// project in `db` if evaluation succeeded.
+///
+/// Returns whether the namespace was added, errors in its declarations are
+/// added to `eval_errors`.
+fn eval_namespace(
+    db: &mut Database,
+    file: &SourceFile,
+    namespace_node: &NamespaceNode,
+    eval_errors: &mut Vec<EvalError>,
+) -> Result<bool> {
+    let mut decls = Declarations::new(namespace_node.path_name().clone());
+    let errors_before = eval_errors.len();
+    for (import_path, import_stats) in namespace_node.imports() {
+        let project = db.project();
+        let imported_space = match project.imported_namespace(import_path) {
+            Some(id) => id,
+            None => *project.namespaces().try_get(import_path)?,
+        }
+        .get(db);
+        for import_stat in import_stats {
+            if let ImportStatement::Single(_, decl) = &import_stat.0 {
+                if !imported_space.type_decls().contains_key(decl)
+                    && !imported_space.interface_ids().contains_key(decl)
+                    && !imported_space.implementation_ids().contains_key(decl)
+                    && !imported_space.streamlet_ids().contains_key(decl)
+                    && !imported_space.constants().contains_key(decl)
+                {
+                    eval_errors.push(EvalError::new(
+                        &import_stat.1,
+                        format!("Namespace {} has no declaration {}", import_path, decl),
+                    ));
                 }
+            }
+            // Declarations are identified by the path they were imported
+            // through, which includes the name of the imported project (if any).
+            import_decls(
+                &mut decls.type_imports,
+                import_path,
+                import_stat,
+                imported_space.type_decls(),
+                eval_errors,
+            );
+            import_decls(
+                &mut decls.interface_imports,
+                import_path,
+                import_stat,
+                imported_space.interface_ids(),
+                eval_errors,
+            );
+            import_decls(
+                &mut decls.implementation_imports,
+                import_path,
+                import_stat,
+                imported_space.implementation_ids(),
+                eval_errors,
+            );
+            import_decls(
+                &mut decls.streamlet_imports,
+                import_path,
+                import_stat,
+                imported_space.streamlet_ids(),
+                eval_errors,
+            );
+            import_decls(
+                &mut decls.constant_imports,
+                import_path,
+                import_stat,
+                imported_space.constants(),
+                eval_errors,
+            );
+        }
+    }
+    if eval_errors.len() > errors_before {
+        return Ok(false);
+    }
+    let mut succeeded = true;
+    for stat in namespace_node.namespace.stats().iter() {
+        if let Statement::Decl(decl) = &stat.0 {
+            let eval_result = eval_declaration(db, file.link_root(), decl, &mut decls);
 
-                let mut project = db.project();
-                project.add_namespace(db, namespace)?;
-                db.set_project(project);
+            if let Err(err) = eval_result {
+                eval_errors.push(err);
+                succeeded = false;
             }
         }
     }
-    if eval_errors.len() > 0 {
-        report_eval_errors(&src, eval_errors.clone());
-        return Err(Error::ProjectError(
-            "Errors during evaluation, see report.".to_string(),
-        ));
+
+    // Don't bother doing more work if evaluation failed at any point, just use the errors to provide a useful report.
+    if succeeded {
+        let mut namespace = Namespace::new(namespace_node.path_name().clone())?;
+
+        for (name, type_id) in decls.types {
+            namespace.import_type(name, type_id)?;
+        }
+        for (name, interface_id) in decls.interfaces {
+            namespace.import_interface(name, interface_id)?;
+        }
+        for (name, implementation_id) in decls.implementations {
+            namespace.import_implementation(name, implementation_id)?;
+        }
+        for (name, streamlet_id) in decls.streamlets {
+            namespace.import_streamlet(name, streamlet_id)?;
+        }
+        for (name, constant) in decls.constants {
+            namespace.import_constant(name, constant)?;
+        }
+        for reference in decls.references.into_inner() {
+            namespace.add_reference(reference)?;
+        }
+
+        let mut project = db.project();
+        project.add_namespace(db, namespace)?;
+        db.set_project(project);
     }
 
-    Ok(())
+    Ok(succeeded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnostics() -> Result<()> {
+        let mut db = Database::default();
+        db.set_project(Project::new("proj", ".", None::<&str>)?);
+        let files = [
+            SourceFile::new("namespace a { type t = Bits(8); }", ".")?,
+            SourceFile::new("namespace b { type t = Bits(0); }", ".")?.with_path("b.til"),
+            SourceFile::new("namespace c { type t = }", ".")?,
+        ];
+        let results = files_to_project_with_diagnostics(&files, &mut db);
+        assert!(results[0].0.is_ok());
+        assert!(results[0].1.is_empty());
+
+        assert!(results[1].0.is_err());
+        assert_eq!(results[1].1.len(), 1);
+        assert!(results[1].1[0].is_error());
+        assert_eq!(results[1].1[0].span(), &(28..29));
+        assert_eq!(results[1].1[0].file(), Some(Path::new("b.til")));
+
+        assert!(results[2].0.is_err());
+        assert_eq!(results[2].1[0].file(), None);
+        assert!(!results[2].1.is_empty());
+        assert!(results[2].1[0]
+            .message()
+            .starts_with("Unexpected token in input"));
+        Ok(())
+    }
 }
@@ -1,89 +1,355 @@
-use ariadne::{Color, Fmt, Label, Report, ReportKind, Source};
+use std::{
+    fmt,
+    io::{self, IsTerminal, Write},
+    str::FromStr,
+};
+
+use ariadne::{Color, Config, Fmt, Label, Report, ReportKind, Source};
 use chumsky::prelude::Simple;
+use serde::Serialize;
 
-use crate::{eval::EvalError, lex::Token};
+use tydi_common::error::{Error, Result};
 
-pub fn report_eval_errors(src: &str, errs: Vec<EvalError>) {
-    for err in errs {
-        let report = Report::build(ReportKind::Error, (), err.span().start)
-            .with_message(err.msg())
-            .with_label(
-                Label::new(err.span())
-                    .with_message(format!("{}", err.msg().fg(Color::Red)))
-                    .with_color(Color::Red),
-            );
-        report.finish().print(Source::from(src)).unwrap();
+use crate::{
+    diagnostic::{Diagnostic, Severity},
+    eval::EvalError,
+    lex::Token,
+    Span,
+};
+
+/// Write a diagnostic as a human-readable report, with a snippet of the
+/// source `src` it refers to.
+///
+/// The report identifies the source by the file of the diagnostic (if any),
+/// followed by the line and column the diagnostic starts at.
+pub fn write_report(
+    src: &str,
+    diagnostic: &Diagnostic,
+    colored: bool,
+    w: impl Write,
+) -> io::Result<()> {
+    let (kind, color) = match diagnostic.severity() {
+        Severity::Error => (ReportKind::Error, Color::Red),
+        Severity::Warning => (ReportKind::Warning, Color::Yellow),
+        Severity::Note => (ReportKind::Advice, Color::Fixed(147)),
+    };
+    let file = file_id(diagnostic);
+    let mut report = Report::build(kind, file.clone(), diagnostic.span().start)
+        .with_message(diagnostic.message())
+        .with_config(Config::default().with_color(colored));
+    for (idx, label) in diagnostic.labels().iter().enumerate() {
+        // Secondary labels are highlighted differently from the primary label
+        let color = if idx == 0 { color } else { Color::Yellow };
+        let span = (file.clone(), label.span().clone());
+        let ariadne_label = if colored {
+            Label::new(span)
+                .with_message(label.message().fg(color))
+                .with_color(color)
+        } else {
+            Label::new(span).with_message(label.message())
+        };
+        report = report.with_label(ariadne_label);
+    }
+    if !diagnostic.notes().is_empty() {
+        report = report.with_note(diagnostic.notes().join("\n"));
+    }
+    report.finish().write((file, Source::from(src)), w)
+}
+
+/// Identifies the file of a diagnostic in reports.
+fn file_id(diagnostic: &Diagnostic) -> String {
+    match diagnostic.file() {
+        Some(file) => file.display().to_string(),
+        None => "<unknown>".to_string(),
+    }
+}
+
+/// A one-line summary of a diagnostic, in the form
+/// `file:line:column: severity: message`.
+pub fn format_short(src: &str, diagnostic: &Diagnostic) -> String {
+    let (line, column) = line_column(src, diagnostic.span().start);
+    format!(
+        "{}:{}:{}: {}",
+        file_id(diagnostic),
+        line,
+        column,
+        diagnostic
+    )
+}
+
+/// How diagnostics are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ReportFormat {
+    /// Reports with a snippet of the source, see [`write_report`].
+    #[default]
+    Human,
+    /// A single line per diagnostic, see [`format_short`].
+    Short,
+    /// A JSON array of the diagnostics of every file with diagnostics, on a
+    /// single line, see [`render_json`].
+    Json,
+}
+
+impl ReportFormat {
+    pub const VARIANTS: [&'static str; 3] = ["human", "short", "json"];
+}
+
+impl FromStr for ReportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "human" => Ok(ReportFormat::Human),
+            "short" => Ok(ReportFormat::Short),
+            "json" => Ok(ReportFormat::Json),
+            _ => Err(Error::InvalidArgument(format!(
+                "\"{}\" is not a valid report format, expected one of: {}",
+                s,
+                Self::VARIANTS.join(", ")
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ReportFormat::Human => "human",
+            ReportFormat::Short => "short",
+            ReportFormat::Json => "json",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Write the diagnostics of the source `src` in the given format.
+///
+/// `colored` only affects human-readable reports.
+pub fn write_reports<'a>(
+    src: &str,
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic>,
+    format: ReportFormat,
+    colored: bool,
+    mut w: impl Write,
+) -> io::Result<()> {
+    match format {
+        ReportFormat::Human => {
+            for diagnostic in diagnostics {
+                write_report(src, diagnostic, colored, &mut w)?;
+            }
+        }
+        ReportFormat::Short => {
+            for diagnostic in diagnostics {
+                writeln!(w, "{}", format_short(src, diagnostic))?;
+            }
+        }
+        ReportFormat::Json => {
+            let diagnostics = diagnostics.into_iter().collect::<Vec<_>>();
+            if !diagnostics.is_empty() {
+                writeln!(w, "{}", render_json(src, diagnostics))?;
+            }
+        }
     }
+    Ok(())
+}
+
+/// Print diagnostics as human-readable reports to stderr.
+pub fn print_reports<'a>(
+    src: &str,
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic>,
+) -> io::Result<()> {
+    print_reports_as(src, diagnostics, ReportFormat::Human)
+}
+
+/// Print diagnostics to stderr in the given format.
+///
+/// Diagnostics are kept out of stdout, which is left to the output of
+/// commands. Reports are only colored if stderr is a terminal.
+pub fn print_reports_as<'a>(
+    src: &str,
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic>,
+    format: ReportFormat,
+) -> io::Result<()> {
+    let stderr = io::stderr();
+    let colored = stderr.is_terminal();
+    write_reports(src, diagnostics, format, colored, stderr.lock())
+}
+
+/// A span, both as character offsets and as (1-based) lines and columns.
+#[derive(Serialize)]
+struct JsonSpan {
+    start: usize,
+    end: usize,
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+impl JsonSpan {
+    fn new(src: &str, span: &Span) -> Self {
+        let (start_line, start_column) = line_column(src, span.start);
+        let (end_line, end_column) = line_column(src, span.end);
+        JsonSpan {
+            start: span.start,
+            end: span.end,
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonLabel<'a> {
+    span: JsonSpan,
+    message: &'a str,
+}
+
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    severity: Severity,
+    message: &'a str,
+    file: Option<String>,
+    span: JsonSpan,
+    labels: Vec<JsonLabel<'a>>,
+    notes: &'a [String],
+}
+
+/// The (1-based) line and column of a character offset into `src`.
+pub fn line_column(src: &str, offset: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut column = 1;
+    for c in src.chars().take(offset) {
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+    (line, column)
+}
+
+/// Render diagnostics as a JSON array, for machine consumption.
+///
+/// Every diagnostic is an object with a `severity` ("error", "warning" or
+/// "note"), `message`, `file` (or null), `span`, `labels` and `notes`. Spans
+/// are objects with the `start` and `end` character offsets, and the lines
+/// and columns they correspond to in `src`.
+pub fn render_json<'a>(src: &str, diagnostics: impl IntoIterator<Item = &'a Diagnostic>) -> String {
+    let diagnostics = diagnostics
+        .into_iter()
+        .map(|diagnostic| JsonDiagnostic {
+            severity: diagnostic.severity(),
+            message: diagnostic.message(),
+            file: diagnostic
+                .file()
+                .map(|file| file.to_string_lossy().to_string()),
+            span: JsonSpan::new(src, diagnostic.span()),
+            labels: diagnostic
+                .labels()
+                .iter()
+                .map(|label| JsonLabel {
+                    span: JsonSpan::new(src, label.span()),
+                    message: label.message(),
+                })
+                .collect(),
+            notes: diagnostic.notes(),
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string(&diagnostics).unwrap()
+}
+
+pub fn report_eval_errors(src: &str, errs: Vec<EvalError>) {
+    let diagnostics = errs.into_iter().map(Diagnostic::from).collect::<Vec<_>>();
+    print_reports(src, &diagnostics).unwrap();
 }
 
 pub fn report_errors(src: &str, errs: Vec<Simple<char>>, parse_errs: Vec<Simple<Token>>) {
-    errs.into_iter()
-        .map(|e| e.map(|c| c.to_string()))
-        .chain(parse_errs.into_iter().map(|e| e.map(|tok| tok.to_string())))
-        .for_each(|e| {
-            let report = Report::build(ReportKind::Error, (), e.span().start);
-
-            let report = match e.reason() {
-                chumsky::error::SimpleReason::Unclosed { span, delimiter } => report
-                    .with_message(format!(
-                        "Unclosed delimiter {}",
-                        delimiter.fg(Color::Yellow)
-                    ))
-                    .with_label(
-                        Label::new(span.clone())
-                            .with_message(format!(
-                                "Unclosed delimiter {}",
-                                delimiter.fg(Color::Yellow)
-                            ))
-                            .with_color(Color::Yellow),
-                    )
-                    .with_label(
-                        Label::new(e.span())
-                            .with_message(format!(
-                                "Must be closed before this {}",
-                                e.found()
-                                    .unwrap_or(&"end of file".to_string())
-                                    .fg(Color::Red)
-                            ))
-                            .with_color(Color::Red),
-                    ),
-                chumsky::error::SimpleReason::Unexpected => report
-                    .with_message(format!(
-                        "{}, expected {}",
-                        if e.found().is_some() {
-                            "Unexpected token in input"
-                        } else {
-                            "Unexpected end of input"
-                        },
-                        if e.expected().len() == 0 {
-                            "something else".to_string()
-                        } else {
-                            e.expected()
-                                .map(|expected| match expected {
-                                    Some(expected) => expected.to_string(),
-                                    None => "end of input".to_string(),
-                                })
-                                .collect::<Vec<_>>()
-                                .join(", ")
-                        }
-                    ))
-                    .with_label(
-                        Label::new(e.span())
-                            .with_message(format!(
-                                "Unexpected token {}",
-                                e.found()
-                                    .unwrap_or(&"end of file".to_string())
-                                    .fg(Color::Red)
-                            ))
-                            .with_color(Color::Red),
-                    ),
-                chumsky::error::SimpleReason::Custom(msg) => report.with_message(msg).with_label(
-                    Label::new(e.span())
-                        .with_message(format!("{}", msg.fg(Color::Red)))
-                        .with_color(Color::Red),
-                ),
-            };
-
-            report.finish().print(Source::from(src)).unwrap();
-        });
+    let diagnostics = errs
+        .into_iter()
+        .map(Diagnostic::from)
+        .chain(parse_errs.into_iter().map(Diagnostic::from))
+        .collect::<Vec<_>>();
+    print_reports(src, &diagnostics).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports() -> io::Result<()> {
+        let src = "namespace a {\n    type t = Bits(0);\n}";
+        let diagnostic = Diagnostic::error("Invalid Bits", 27..34)
+            .with_label(27..34, "Bits must be positive")
+            .with_note("Use Null for empty data");
+
+        let mut report = vec![];
+        write_report(src, &diagnostic, false, &mut report)?;
+        let report = String::from_utf8(report).unwrap();
+        assert!(report.starts_with("Error: Invalid Bits"));
+        assert!(report.contains("[<unknown>:2:14]"));
+        assert!(report.contains("type t = Bits(0);"));
+        assert!(report.contains("Bits must be positive"));
+        assert!(report.contains("Note: Use Null for empty data"));
+
+        assert_eq!(
+            render_json(src, [&diagnostic]),
+            concat!(
+                r#"[{"severity":"error","message":"Invalid Bits","file":null,"#,
+                r#""span":{"start":27,"end":34,"start_line":2,"start_column":14,"end_line":2,"end_column":21},"#,
+                r#""labels":[{"span":{"start":27,"end":34,"start_line":2,"start_column":14,"end_line":2,"end_column":21},"#,
+                r#""message":"Bits must be positive"}],"notes":["Use Null for empty data"]}]"#
+            )
+        );
+
+        let diagnostic = diagnostic.with_file("src/types.til");
+        let mut report = vec![];
+        write_report(src, &diagnostic, false, &mut report)?;
+        let report = String::from_utf8(report).unwrap();
+        assert!(report.contains("[src/types.til:2:14]"));
+        assert_eq!(
+            format_short(src, &diagnostic),
+            "src/types.til:2:14: error: Invalid Bits"
+        );
+        assert!(render_json(src, [&diagnostic]).contains(r#""file":"src/types.til""#));
+        Ok(())
+    }
+
+    #[test]
+    fn report_formats() -> Result<()> {
+        let src = "namespace a {\n    type t = Bits(0);\n}";
+        let diagnostic = Diagnostic::error("Invalid Bits", 27..34).with_file("a.til");
+        let write = |format: ReportFormat, diagnostics: &[Diagnostic]| -> Result<String> {
+            let mut result = vec![];
+            write_reports(src, diagnostics, format, false, &mut result)?;
+            Ok(String::from_utf8(result).unwrap())
+        };
+
+        let human = write(ReportFormat::Human, std::slice::from_ref(&diagnostic))?;
+        assert!(human.contains("Invalid Bits"));
+        assert!(!human.contains('\x1b'), "{}", human);
+        assert_eq!(
+            write(
+                ReportFormat::Short,
+                &[diagnostic.clone(), diagnostic.clone()]
+            )?,
+            "a.til:2:14: error: Invalid Bits\na.til:2:14: error: Invalid Bits\n"
+        );
+        assert_eq!(
+            write(ReportFormat::Json, std::slice::from_ref(&diagnostic))?,
+            format!("{}\n", render_json(src, [&diagnostic]))
+        );
+        assert_eq!(write(ReportFormat::Json, &[])?, "");
+
+        assert_eq!("json".parse::<ReportFormat>()?, ReportFormat::Json);
+        assert!("xml".parse::<ReportFormat>().is_err());
+        for variant in ReportFormat::VARIANTS {
+            assert_eq!(variant.parse::<ReportFormat>()?.to_string(), variant);
+        }
+        Ok(())
+    }
 }
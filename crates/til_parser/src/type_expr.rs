@@ -1,12 +1,13 @@
 use chumsky::prelude::*;
 
 use crate::{
+    doc_expr::{doc_expr, DocExpr},
     expr::{val, Value},
     generic_param::{
         generic_parameter_assignment, generic_parameter_assignments, GenericParameterAssignments,
         GenericParameterValueExpr,
     },
-    ident_expr::{ident_expr, label, IdentExpr},
+    ident_expr::{ident_expr, label, name, IdentExpr},
     lex::{StreamPropertyKeyword, Token, TypeKeyword},
     Spanned,
 };
@@ -22,16 +23,28 @@ pub enum TypeExpr {
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum LogicalTypeDef {
     Null,
-    Bits(Spanned<String>),
+    Bits(Spanned<GenericParameterValueExpr>),
     Group(Spanned<FieldsDef>),
     Union(Spanned<FieldsDef>),
+    /// Shorthand for a Union of which all fields are Null, e.g. `Enum(A, B)`
+    /// is equivalent to `Union(A: Null, B: Null)`.
+    Enum(Spanned<VariantsDef>),
+    /// Shorthand for a Group of a number of fields of the same type, e.g.
+    /// `Array(Bits(8), 2)` is equivalent to `Group(item0: Bits(8), item1: Bits(8))`.
+    Array(Spanned<TypeExpr>, Spanned<GenericParameterValueExpr>),
     Stream(Spanned<StreamProps>),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum FieldsDef {
     Error,
-    Fields(Vec<(Spanned<String>, Spanned<TypeExpr>)>),
+    Fields(Vec<(DocExpr, Spanned<String>, Spanned<TypeExpr>)>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum VariantsDef {
+    Error,
+    Variants(Vec<(DocExpr, Spanned<String>)>),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -40,15 +53,22 @@ pub enum StreamProps {
     Props(Vec<Spanned<StreamProp>>),
 }
 
+/// The value of a Stream property which may depend on generic parameters.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StreamPropValue {
+    Value(Spanned<Value>),
+    Generic(Spanned<GenericParameterValueExpr>),
+}
+
 // TODO: Could probably rule out invalid values sooner?
 // Then again, this is a bit more robus on parsing. (Lets us parse more, then fail on eval.)
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum StreamProp {
     Data(Spanned<TypeExpr>),
-    Throughput(Spanned<Value>),
+    Throughput(StreamPropValue),
     Dimensionality(Spanned<GenericParameterValueExpr>),
     Synchronicity(Spanned<Value>),
-    Complexity(Spanned<Value>),
+    Complexity(StreamPropValue),
     Direction(Spanned<Value>),
     User(Spanned<TypeExpr>),
     Keep(Spanned<Value>),
@@ -56,7 +76,10 @@ pub enum StreamProp {
 
 pub fn type_expr() -> impl Parser<Token, Spanned<TypeExpr>, Error = Simple<Token>> + Clone {
     recursive(|type_def| {
-        let typ_el = label().then(type_def.clone());
+        let typ_el = doc_expr()
+            .then(label())
+            .then(type_def.clone())
+            .map(|((doc, label), typ)| (doc, label, typ));
 
         // A group of types
         let fields_def = typ_el
@@ -71,11 +94,28 @@ pub fn type_expr() -> impl Parser<Token, Spanned<TypeExpr>, Error = Simple<Token
                 |span| (FieldsDef::Error, span),
             ));
 
-        let bits_def = filter_map(|span, tok| match tok {
-            Token::Num(num) => Ok((num, span)),
-            _ => Err(Simple::expected_input_found(span, Vec::new(), Some(tok))),
-        })
-        .delimited_by(just(Token::Ctrl('(')), just(Token::Ctrl(')')));
+        // The variants of an enumeration
+        let variants_def = doc_expr()
+            .then(name())
+            .separated_by(just(Token::Ctrl(',')))
+            .allow_trailing()
+            .map_with_span(|variants, span| (VariantsDef::Variants(variants), span))
+            .delimited_by(just(Token::Ctrl('(')), just(Token::Ctrl(')')))
+            .recover_with(nested_delimiters(
+                Token::Ctrl('('),
+                Token::Ctrl(')'),
+                [],
+                |span| (VariantsDef::Error, span),
+            ));
+
+        let bits_def = generic_parameter_assignment()
+            .delimited_by(just(Token::Ctrl('(')), just(Token::Ctrl(')')));
+
+        // Integer expressions (which may refer to generic parameters) or
+        // other values, e.g. real numbers or versions
+        let prop_value = generic_parameter_assignment()
+            .map(StreamPropValue::Generic)
+            .or(val().map(StreamPropValue::Value));
 
         let data_prop = just(Token::StreamProperty(StreamPropertyKeyword::Data))
             .ignore_then(just(Token::Ctrl(':')))
@@ -84,7 +124,7 @@ pub fn type_expr() -> impl Parser<Token, Spanned<TypeExpr>, Error = Simple<Token
 
         let throughput_prop = just(Token::StreamProperty(StreamPropertyKeyword::Throughput))
             .ignore_then(just(Token::Ctrl(':')))
-            .ignore_then(val())
+            .ignore_then(prop_value.clone())
             .map(StreamProp::Throughput);
 
         let dimensionality_prop =
@@ -100,7 +140,7 @@ pub fn type_expr() -> impl Parser<Token, Spanned<TypeExpr>, Error = Simple<Token
 
         let complexity_prop = just(Token::StreamProperty(StreamPropertyKeyword::Complexity))
             .ignore_then(just(Token::Ctrl(':')))
-            .ignore_then(val())
+            .ignore_then(prop_value)
             .map(StreamProp::Complexity);
 
         let direction_prop = just(Token::StreamProperty(StreamPropertyKeyword::Direction))
@@ -155,6 +195,20 @@ pub fn type_expr() -> impl Parser<Token, Spanned<TypeExpr>, Error = Simple<Token
             .ignore_then(fields_def)
             .map(|g| LogicalTypeDef::Union(g));
 
+        let array_def = just(Token::Type(TypeKeyword::Array))
+            .ignore_then(
+                type_def
+                    .clone()
+                    .then_ignore(just(Token::Ctrl(',')))
+                    .then(generic_parameter_assignment())
+                    .delimited_by(just(Token::Ctrl('(')), just(Token::Ctrl(')'))),
+            )
+            .map(|(element, count)| LogicalTypeDef::Array(element, count));
+
+        let enum_def = just(Token::Type(TypeKeyword::Enum))
+            .ignore_then(variants_def)
+            .map(LogicalTypeDef::Enum);
+
         let stream_def = just(Token::Type(TypeKeyword::Stream))
             .ignore_then(stream_props)
             .map(|g| LogicalTypeDef::Stream(g));
@@ -163,6 +217,8 @@ pub fn type_expr() -> impl Parser<Token, Spanned<TypeExpr>, Error = Simple<Token
             .or(bits_def)
             .or(group_def)
             .or(union_def)
+            .or(enum_def)
+            .or(array_def)
             .or(stream_def)
             .map_with_span(|x, span| (x, span))
             .map(|x| TypeExpr::Definition(Box::new(x)));
@@ -0,0 +1,80 @@
+use core::fmt;
+
+use tydi_intern::Id;
+
+use crate::ir::{
+    traits::{InternSelf, MoveDb},
+    Ir,
+};
+use tydi_common::{
+    error::{Error, Result},
+    name::Name,
+    numbers::NonNegative,
+};
+
+use super::{genericproperty::GenericProperty, LogicalType};
+
+/// An Array whose length depends on generic parameters.
+///
+/// Arrays with a fixed length are Groups, see [`LogicalType::try_new_array`].
+/// Once the parameters the length depends on are assigned, the Array is
+/// replaced by its equivalent Group.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Array {
+    element: Id<LogicalType>,
+    length: GenericProperty<NonNegative>,
+}
+
+impl Array {
+    pub(crate) fn new(element: Id<LogicalType>, length: GenericProperty<NonNegative>) -> Self {
+        Array { element, length }
+    }
+
+    /// Returns the ID of the LogicalType of the elements of the Array.
+    pub fn element_id(&self) -> Id<LogicalType> {
+        self.element
+    }
+
+    /// Returns the length of the Array.
+    pub fn length(&self) -> &GenericProperty<NonNegative> {
+        &self.length
+    }
+
+    /// The error for using this Array where its fields must be known.
+    pub(crate) fn unassigned_error(&self) -> Error {
+        Error::InvalidArgument(format!(
+            "The length of Array{} depends on generic parameters, it can only be used once they are assigned",
+            self
+        ))
+    }
+}
+
+impl From<Array> for LogicalType {
+    /// Wraps this array in a [`LogicalType`].
+    ///
+    /// [`LogicalType`]: ./enum.LogicalType.html
+    fn from(array: Array) -> Self {
+        LogicalType::Array(array)
+    }
+}
+
+impl MoveDb<Id<LogicalType>> for Array {
+    fn move_db(
+        &self,
+        original_db: &dyn Ir,
+        target_db: &dyn Ir,
+        prefix: &Option<Name>,
+    ) -> Result<Id<LogicalType>> {
+        Ok(LogicalType::from(Array {
+            element: self.element.move_db(original_db, target_db, prefix)?,
+            length: self.length.clone(),
+        })
+        .intern(target_db))
+    }
+}
+
+impl fmt::Display for Array {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.element, self.length)
+    }
+}
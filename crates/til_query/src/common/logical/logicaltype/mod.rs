@@ -1,18 +1,22 @@
 use core::fmt;
 use std::convert::TryFrom;
 
-use crate::ir::{
-    traits::{GetSelf, InternSelf, MoveDb},
-    Ir,
+use crate::{
+    common::physical::stream::PhysicalBitCount,
+    ir::{
+        traits::{GetSelf, InternSelf, MoveDb},
+        Ir,
+    },
 };
 
 use tydi_common::{
     error::{Error, Result, TryResult},
     map::InsertionOrderedMap,
     name::{Name, PathName},
-    numbers::{BitCount, NonNegative, Positive},
+    numbers::{NonNegative, Positive},
 };
 
+pub mod array;
 pub mod bits;
 pub mod genericproperty;
 pub mod group;
@@ -21,7 +25,9 @@ pub mod union;
 
 use tydi_intern::Id;
 
-use self::{group::Group, stream::Stream, union::Union};
+use self::{
+    array::Array, genericproperty::GenericProperty, group::Group, stream::Stream, union::Union,
+};
 
 use super::split_streams::{SplitStreams, SplitsStreams};
 
@@ -57,8 +63,11 @@ pub enum LogicalType {
     /// of `2^b`-valued data carried by means of a group of `b` bits, where`b`
     /// is a positive integer.
     ///
+    /// The bit count may depend on generic parameters, in which case it must
+    /// evaluate to a positive integer once they are assigned.
+    ///
     /// [Reference](https://abs-tudelft.github.io/tydi/specification/logical.html#bits)
-    Bits(Positive),
+    Bits(GenericProperty<NonNegative>),
     /// The Group stream type acts as a product type (composition).
     ///
     /// [Reference](https://abs-tudelft.github.io/tydi/specification/logical.html#group)
@@ -71,6 +80,9 @@ pub enum LogicalType {
     ///
     /// [Reference](https://abs-tudelft.github.io/tydi/specification/logical.html#stream)
     Stream(Id<Stream>),
+    /// An Array whose length depends on generic parameters, which is replaced
+    /// by its equivalent Group once they are assigned.
+    Array(Array),
 }
 
 impl LogicalType {
@@ -81,20 +93,46 @@ impl LogicalType {
     ///
     /// ```rust
     /// use tydi_common::error::Error;
-    /// use tydi_common::numbers::Positive;
-    /// use til_query::common::logical::logicaltype::LogicalType;
+    /// use til_query::common::logical::logicaltype::{genericproperty::GenericProperty, LogicalType};
     ///
     /// let bits = LogicalType::try_new_bits(4);
     /// let zero = LogicalType::try_new_bits(0);
     ///
-    /// assert_eq!(bits, Ok(LogicalType::Bits(Positive::new(4).unwrap())));
+    /// assert_eq!(bits, Ok(LogicalType::Bits(GenericProperty::Fixed(4))));
     /// assert_eq!(zero, Err(Error::InvalidArgument("bit count cannot be zero".to_string())));
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn try_new_bits(bit_count: NonNegative) -> Result<Self> {
-        Ok(LogicalType::Bits(Positive::new(bit_count).ok_or_else(
-            || Error::InvalidArgument("bit count cannot be zero".to_string()),
-        )?))
+        Self::try_new_generic_bits(bit_count)
+    }
+
+    /// Returns a new Bits stream type with a bit count which may depend on
+    /// generic parameters. Returns an error when the bit count is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tydi_common::name::Name;
+    /// use til_query::common::logical::logicaltype::{genericproperty::GenericProperty, LogicalType};
+    ///
+    /// let width: GenericProperty<u32> = Name::try_new("width")?.into();
+    /// let bits = LogicalType::try_new_generic_bits(width * GenericProperty::Fixed(2))?;
+    ///
+    /// assert_eq!(bits.to_string(), "Bits((Parameterized(width)) * Fixed(2))");
+    /// assert!(LogicalType::try_new_generic_bits(GenericProperty::Fixed(2) - GenericProperty::Fixed(2)).is_err());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn try_new_generic_bits(
+        bit_count: impl TryResult<GenericProperty<NonNegative>>,
+    ) -> Result<Self> {
+        let bit_count = bit_count.try_result()?.try_reduce();
+        if bit_count.try_eval() == Some(0) {
+            Err(Error::InvalidArgument(
+                "bit count cannot be zero".to_string(),
+            ))
+        } else {
+            Ok(LogicalType::Bits(bit_count))
+        }
     }
 
     /// Returns a new Group stream type from the provided iterator of names and
@@ -149,6 +187,78 @@ impl LogicalType {
         Union::try_new(parent_id, union).map(Into::into)
     }
 
+    /// Returns a new Group stream type with `count` fields of the same
+    /// element type, named after their index (`item0`, `item1`, ...).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use til_query::common::logical::logicaltype::LogicalType;
+    /// use til_query::ir::{db::Database, traits::InternSelf};
+    /// use tydi_common::numbers::Positive;
+    ///
+    /// let db = Database::default();
+    /// let pixel = LogicalType::try_new_bits(24)?.intern(&db);
+    /// let pixels = LogicalType::try_new_array(pixel, Positive::new(2).unwrap())?;
+    ///
+    /// assert_eq!(
+    ///     pixels,
+    ///     LogicalType::try_new_group(None, vec![("item0", pixel), ("item1", pixel)])?
+    /// );
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn try_new_array(element: Id<LogicalType>, count: Positive) -> Result<Self> {
+        Self::try_new_group(
+            None,
+            (0..count.get()).map(|index| (format!("item{}", index), element)),
+        )
+    }
+
+    /// Returns a new Array stream type with a length which may depend on
+    /// generic parameters. If the length is fixed, this is the equivalent
+    /// Group, see [`LogicalType::try_new_array`]. Returns an error when the
+    /// length is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use til_query::common::logical::logicaltype::{genericproperty::GenericProperty, LogicalType};
+    /// use til_query::ir::{db::Database, traits::InternSelf};
+    /// use tydi_common::name::Name;
+    ///
+    /// let db = Database::default();
+    /// let pixel = LogicalType::try_new_bits(24)?.intern(&db);
+    /// let lanes: GenericProperty<u32> = Name::try_new("lanes")?.into();
+    ///
+    /// assert!(matches!(
+    ///     LogicalType::try_new_generic_array(pixel, lanes)?,
+    ///     LogicalType::Array(_)
+    /// ));
+    /// assert_eq!(
+    ///     LogicalType::try_new_generic_array(pixel, GenericProperty::Fixed(1))?,
+    ///     LogicalType::try_new_group(None, vec![("item0", pixel)])?
+    /// );
+    /// assert!(LogicalType::try_new_generic_array(pixel, GenericProperty::Fixed(0)).is_err());
+    ///
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn try_new_generic_array(
+        element: Id<LogicalType>,
+        length: impl TryResult<GenericProperty<NonNegative>>,
+    ) -> Result<Self> {
+        let length = length.try_result()?.try_reduce();
+        match length.try_eval() {
+            Some(length) => match Positive::new(length) {
+                Some(length) => Self::try_new_array(element, length),
+                None => Err(Error::InvalidArgument(
+                    "the length of an Array cannot be zero".to_string(),
+                )),
+            },
+            None => Ok(LogicalType::Array(Array::new(element, length))),
+        }
+    }
+
     /// Returns true if this logical type consists of only element-
     /// manipulating nodes. This recursively checks all inner logical
     /// types.
@@ -178,6 +288,7 @@ impl LogicalType {
                 .iter()
                 .all(|(_, typ)| typ.is_element_only(db)),
             LogicalType::Stream(_) => false,
+            LogicalType::Array(array) => array.element_id().get(db).is_element_only(db),
         }
     }
 
@@ -187,49 +298,68 @@ impl LogicalType {
     /// [Reference](https://abs-tudelft.github.io/tydi/specification/logical.html#field-conversion-function)
     ///
     /// [`Fields`]: ./struct.Fields.html
-    pub(crate) fn fields(&self, db: &dyn Ir) -> InsertionOrderedMap<PathName, BitCount> {
+    ///
+    /// The width of a Union is that of its widest field. Returns an error when
+    /// this can not be determined, because the widths of its fields depend on
+    /// different generic parameters, or on both a generic parameter and a
+    /// fixed width, as these can not be compared until they are assigned.
+    pub fn fields(&self, db: &dyn Ir) -> Result<InsertionOrderedMap<PathName, PhysicalBitCount>> {
         let mut fields = InsertionOrderedMap::new();
         match self {
-            LogicalType::Null | LogicalType::Stream(_) => fields,
+            LogicalType::Null | LogicalType::Stream(_) => (),
             LogicalType::Bits(b) => {
-                fields.try_insert(PathName::new_empty(), *b).unwrap();
-                fields
+                let bit_count = Option::<PhysicalBitCount>::from(b.clone()).ok_or_else(|| {
+                    Error::InvalidArgument("bit count cannot be zero".to_string())
+                })?;
+                fields.try_insert(PathName::new_empty(), bit_count)?;
             }
             LogicalType::Group(group) => {
                 for (name, typ) in group.fields(db).iter() {
-                    typ.fields(db).iter().for_each(|(path_name, bit_count)| {
-                        fields
-                            .try_insert(path_name.with_parents(name.clone()), *bit_count)
-                            .unwrap();
-                    })
+                    for (path_name, bit_count) in typ.fields(db)? {
+                        fields.try_insert(path_name.with_parents(name.clone()), bit_count)?;
+                    }
                 }
-                fields
             }
             LogicalType::Union(union) => {
                 if let Some(tag) = union.tag() {
-                    fields
-                        .try_insert(PathName::try_new(vec!["tag"]).unwrap(), tag)
-                        .unwrap();
+                    fields.try_insert(
+                        PathName::try_new(vec!["tag"])?,
+                        PhysicalBitCount::Fixed(tag),
+                    )?;
+                }
+                let mut fixed = 0;
+                let mut parameterized = vec![];
+                for (_, id) in union.field_ids().iter() {
+                    for (_, count) in id.get(db).fields(db)? {
+                        match count.try_eval() {
+                            Some(f) => fixed = fixed.max(f.get()),
+                            None if !parameterized.contains(&count) => parameterized.push(count),
+                            None => (),
+                        }
+                    }
                 }
-                let b = union.field_ids().iter().fold(0, |acc, (_, id)| {
-                    acc.max(
-                        id.get(db)
-                            .fields(db)
-                            .values()
-                            .fold(0, |acc, count| acc.max(count.get())),
-                    )
-                });
-                if b > 0 {
-                    fields
-                        .try_insert(
-                            PathName::try_new(vec!["union"]).unwrap(),
-                            BitCount::new(b).unwrap(),
-                        )
-                        .unwrap();
+                let b = match (parameterized.len(), PhysicalBitCount::fixed(fixed)) {
+                    (0, b) => b,
+                    (1, None) => parameterized.pop(),
+                    _ => {
+                        return Err(Error::InvalidArgument(format!(
+                            "Unable to determine the width of the Union with fields {}, as the widths of its fields depend on different generic parameters, or on both generic parameters and fixed widths",
+                            union
+                                .field_ids()
+                                .keys()
+                                .map(|name| name.to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )))
+                    }
+                };
+                if let Some(b) = b {
+                    fields.try_insert(PathName::try_new(vec!["union"])?, b)?;
                 }
-                fields
             }
+            LogicalType::Array(array) => return Err(array.unassigned_error()),
         }
+        Ok(fields)
     }
 
     pub fn null_id(db: &dyn Ir) -> Id<Self> {
@@ -265,6 +395,7 @@ impl IsNull for LogicalType {
             }
             LogicalType::Stream(stream) => stream.is_null(db),
             LogicalType::Bits(_) => false,
+            LogicalType::Array(array) => array.element_id().is_null(db),
         }
     }
 }
@@ -281,6 +412,7 @@ impl MoveDb<Id<LogicalType>> for LogicalType {
             LogicalType::Bits(_) => self.clone().intern(target_db),
             LogicalType::Group(group) => group.move_db(original_db, target_db, prefix)?,
             LogicalType::Union(union) => union.move_db(original_db, target_db, prefix)?,
+            LogicalType::Array(array) => array.move_db(original_db, target_db, prefix)?,
             LogicalType::Stream(stream) => {
                 LogicalType::Stream(stream.move_db(original_db, target_db, prefix)?)
                     .intern(target_db)
@@ -293,9 +425,11 @@ impl fmt::Display for LogicalType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             LogicalType::Null => write!(f, "Null"),
+            LogicalType::Bits(GenericProperty::Fixed(b)) => write!(f, "Bits({})", b),
             LogicalType::Bits(b) => write!(f, "Bits({})", b),
             LogicalType::Group(group) => write!(f, "Group{}", group),
             LogicalType::Union(union) => write!(f, "Union{}", union),
+            LogicalType::Array(array) => write!(f, "Array{}", array),
             LogicalType::Stream(stream_id) => write!(f, "Stream(Id: {})", stream_id),
         }
     }
@@ -5,7 +5,7 @@ use tydi_common::{
     error::{Error, Result, TryResult},
     map::InsertionOrderedMap,
     name::{Name, PathName},
-    numbers::{BitCount, NonNegative, Positive, PositiveReal},
+    numbers::{NonNegative, Positive, PositiveReal},
     traits::Reverse,
 };
 use tydi_intern::Id;
@@ -18,7 +18,10 @@ use crate::{
             type_hierarchy::TypeHierarchy,
             type_reference::TypeReference,
         },
-        physical::{complexity::Complexity, stream::PhysicalStream},
+        physical::{
+            complexity::Complexity,
+            stream::{PhysicalBitCount, PhysicalStream},
+        },
         stream_direction::StreamDirection,
     },
     ir::{
@@ -119,6 +122,152 @@ impl Default for Throughput {
     }
 }
 
+impl fmt::Display for Throughput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get())
+    }
+}
+
+/// Values of Stream properties which can be derived from the (integer) value
+/// of a generic parameter.
+pub trait FromGenericValue: Sized {
+    fn from_generic_value(value: NonNegative) -> Result<Self>;
+}
+
+impl FromGenericValue for Throughput {
+    fn from_generic_value(value: NonNegative) -> Result<Self> {
+        Throughput::try_new(value as f64)
+    }
+}
+
+impl FromGenericValue for Complexity {
+    fn from_generic_value(value: NonNegative) -> Result<Self> {
+        if value == 0 {
+            Err(Error::InvalidArgument(
+                "Complexity must be at least 1".to_string(),
+            ))
+        } else {
+            Ok(Complexity::new_major(value))
+        }
+    }
+}
+
+/// A property of a Stream which is either a fixed value, or an integer which
+/// depends on generic parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StreamProperty<T> {
+    Fixed(T),
+    Parameterized(GenericProperty<NonNegative>),
+}
+
+impl<T: FromGenericValue + Clone> StreamProperty<T> {
+    /// Create a property from an integer expression, which is fixed if the
+    /// expression does not depend on any generic parameters.
+    pub fn try_new(prop: impl TryResult<GenericProperty<NonNegative>>) -> Result<Self> {
+        let prop = prop.try_result()?.try_reduce();
+        match prop.try_eval() {
+            Some(value) => Ok(StreamProperty::Fixed(T::from_generic_value(value)?)),
+            None => Ok(StreamProperty::Parameterized(prop)),
+        }
+    }
+
+    pub fn try_assign(
+        &self,
+        param: &Name,
+        val: impl TryResult<GenericProperty<NonNegative>>,
+    ) -> Result<Self> {
+        match self {
+            StreamProperty::Fixed(_) => Ok(self.clone()),
+            StreamProperty::Parameterized(prop) => Self::try_new(prop.try_assign(param, val)?),
+        }
+    }
+}
+
+impl<T: Clone> StreamProperty<T> {
+    /// The fixed value of this property, or None if it depends on generic
+    /// parameters.
+    pub fn fixed(&self) -> Option<T> {
+        match self {
+            StreamProperty::Fixed(value) => Some(value.clone()),
+            StreamProperty::Parameterized(_) => None,
+        }
+    }
+
+    /// The fixed value of this property, returns an error if it depends on
+    /// generic parameters.
+    pub fn try_fixed(&self, property: &str) -> Result<T> {
+        match self {
+            StreamProperty::Fixed(value) => Ok(value.clone()),
+            StreamProperty::Parameterized(prop) => Err(Error::InvalidArgument(format!(
+                "The {} of this Stream depends on generic parameters ({}), it can only be used once they are assigned",
+                property, prop
+            ))),
+        }
+    }
+
+    /// The generic expression of this property, or None if it is fixed.
+    pub fn parameterized(&self) -> Option<&GenericProperty<NonNegative>> {
+        match self {
+            StreamProperty::Fixed(_) => None,
+            StreamProperty::Parameterized(prop) => Some(prop),
+        }
+    }
+}
+
+impl StreamProperty<Throughput> {
+    /// Multiply two throughputs. A throughput which depends on generic
+    /// parameters can only be multiplied by whole throughputs.
+    pub fn try_mul(&self, other: &Self) -> Result<Self> {
+        let as_generic = |throughput: &Self| -> Result<GenericProperty<NonNegative>> {
+            match throughput {
+                StreamProperty::Fixed(t) if t.get().fract() == 0.0 => {
+                    Ok(GenericProperty::Fixed(t.non_negative()))
+                }
+                StreamProperty::Fixed(t) => Err(Error::InvalidArgument(format!(
+                    "Cannot combine a throughput of {} with a throughput which depends on generic parameters, only whole throughputs can be combined",
+                    t
+                ))),
+                StreamProperty::Parameterized(prop) => Ok(prop.clone()),
+            }
+        };
+        match (self, other) {
+            (StreamProperty::Fixed(l), StreamProperty::Fixed(r)) => {
+                Ok(StreamProperty::Fixed(l.clone() * r.clone()))
+            }
+            _ => Self::try_new(as_generic(self)? * as_generic(other)?),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for StreamProperty<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamProperty::Fixed(value) => write!(f, "{}", value),
+            StreamProperty::Parameterized(prop) => write!(f, "{}", prop),
+        }
+    }
+}
+
+impl<T> From<T> for StreamProperty<T> {
+    fn from(val: T) -> Self {
+        StreamProperty::Fixed(val)
+    }
+}
+
+impl TryFrom<f64> for StreamProperty<Throughput> {
+    type Error = Error;
+
+    fn try_from(value: f64) -> Result<Self> {
+        Ok(StreamProperty::Fixed(Throughput::try_from(value)?))
+    }
+}
+
+impl From<NonNegative> for StreamProperty<Complexity> {
+    fn from(val: NonNegative) -> Self {
+        StreamProperty::Fixed(Complexity::from(val))
+    }
+}
+
 /// The stream-manipulating logical stream type.
 ///
 /// Defines a new physical stream.
@@ -137,7 +286,9 @@ pub struct Stream {
     /// should be transferrable on the child stream per element in the parent
     /// stream, or if there is no parent stream, the minimum number of elements
     /// that should be transferrable per clock cycle.
-    throughput: Throughput,
+    ///
+    /// May depend on generic parameters, in which case it is a whole number.
+    throughput: StreamProperty<Throughput>,
     /// Dimensionality of the stream.
     ///
     /// Nonnegative integer specifying the dimensionality of the child
@@ -153,7 +304,10 @@ pub struct Stream {
     ///
     /// The complexity number for the physical stream interface, as defined
     /// in the physical stream specification.
-    complexity: Complexity,
+    ///
+    /// May depend on generic parameters, in which case it is a major
+    /// complexity level.
+    complexity: StreamProperty<Complexity>,
     /// Direction of the stream.
     ///
     /// The direction of the stream. If there is no parent stream, this
@@ -184,23 +338,36 @@ impl Stream {
         param_assignment: GenericParameterAssignment,
     ) -> Result<()> {
         match param_assignment.kind() {
-            GenericKind::Behavioral(_) => Ok(()),
+            GenericKind::Behavioral(_) => (),
             GenericKind::Interface(i) => match i {
-                InterfaceGenericKind::Dimensionality(_) => Ok(self.dimensionality = self
-                    .dimensionality
-                    .try_assign(param_name, param_assignment.value_take())?),
+                InterfaceGenericKind::Dimensionality(_) => {
+                    self.dimensionality = self
+                        .dimensionality
+                        .try_assign(param_name, param_assignment.value_take())?
+                }
+                InterfaceGenericKind::Throughput(_) => {
+                    self.throughput = self
+                        .throughput
+                        .try_assign(param_name, param_assignment.value_take())?
+                }
+                InterfaceGenericKind::Complexity(_) => {
+                    self.complexity = self
+                        .complexity
+                        .try_assign(param_name, param_assignment.value_take())?
+                }
             },
         }
+        Ok(())
     }
 
     #[allow(clippy::too_many_arguments)]
     pub fn try_new(
         db: &dyn Ir,
         data: Id<LogicalType>,
-        throughput: impl TryResult<Throughput>,
+        throughput: impl TryResult<StreamProperty<Throughput>>,
         dimensionality: impl TryResult<GenericProperty<NonNegative>>,
         synchronicity: Synchronicity,
-        complexity: impl TryResult<Complexity>,
+        complexity: impl TryResult<StreamProperty<Complexity>>,
         direction: StreamDirection,
         user: Id<LogicalType>,
         keep: bool,
@@ -225,17 +392,17 @@ impl Stream {
     /// For internal use only. Does not validate the User field
     pub(crate) fn new(
         data: Id<LogicalType>,
-        throughput: Throughput,
+        throughput: StreamProperty<Throughput>,
         dimensionality: impl Into<GenericProperty<NonNegative>>,
         synchronicity: Synchronicity,
-        complexity: impl Into<Complexity>,
+        complexity: impl Into<StreamProperty<Complexity>>,
         direction: StreamDirection,
         user: Id<LogicalType>,
         keep: bool,
     ) -> Self {
         Stream {
             data,
-            throughput: throughput,
+            throughput,
             dimensionality: dimensionality.into().try_reduce(),
             synchronicity,
             complexity: complexity.into(),
@@ -283,12 +450,12 @@ impl Stream {
     }
 
     /// Returns the throughput ratio of this stream.
-    pub fn throughput(&self) -> Throughput {
+    pub fn throughput(&self) -> StreamProperty<Throughput> {
         self.throughput.clone()
     }
 
     // Returns the complexity of this stream.
-    pub fn complexity(&self) -> Complexity {
+    pub fn complexity(&self) -> StreamProperty<Complexity> {
         self.complexity.clone()
     }
 
@@ -298,19 +465,19 @@ impl Stream {
     }
 
     // Converts this Stream type into a Physical Stream.
-    pub fn physical(&self, db: &dyn Ir) -> PhysicalStream {
-        PhysicalStream::new(
-            self.data(db).fields(db),
-            self.throughput().positive(),
+    pub fn physical(&self, db: &dyn Ir) -> Result<PhysicalStream> {
+        Ok(PhysicalStream::new(
+            self.data(db).fields(db)?,
+            self.throughput().try_fixed("throughput")?.positive(),
             self.dimensionality().clone(),
-            self.complexity(),
-            self.user(db).fields(db),
+            self.complexity().try_fixed("complexity")?,
+            self.user(db).fields(db)?,
             self.direction(),
-        )
+        ))
     }
 
     /// Set the throughput ratio of this stream.
-    pub(crate) fn set_throughput(&mut self, throughput: Throughput) {
+    pub(crate) fn set_throughput(&mut self, throughput: StreamProperty<Throughput>) {
         self.throughput = throughput;
     }
 
@@ -319,20 +486,30 @@ impl Stream {
         self.synchronicity = synchronicity;
     }
 
+    /// Set the data type of this stream.
+    pub(crate) fn set_data(&mut self, data: Id<LogicalType>) {
+        self.data = data;
+    }
+
+    /// Set the user type of this stream.
+    pub(crate) fn set_user(&mut self, user: Id<LogicalType>) {
+        self.user = user;
+    }
+
     /// Set the dimensionality of this stream.
     pub(crate) fn set_dimensionality(&mut self, dimensionality: GenericProperty<NonNegative>) {
         self.dimensionality = dimensionality.try_reduce();
     }
 }
 
-impl SynthesizeLogicalStream<BitCount, PhysicalStream> for Id<Stream> {
-    fn synthesize(&self, db: &dyn Ir) -> Result<TypedStream<BitCount, PhysicalStream>> {
+impl SynthesizeLogicalStream<PhysicalBitCount, PhysicalStream> for Id<Stream> {
+    fn synthesize(&self, db: &dyn Ir) -> Result<TypedStream<PhysicalBitCount, PhysicalStream>> {
         let split = &self.split_streams(db)?;
         // NOTE: Signals will currently always be empty, as it refers to user-defined signals.
-        let (signals, rest) = (split.signals().get(db).fields(db), split.streams());
+        let (signals, rest) = (split.signals().get(db).fields(db)?, split.streams());
         let mut streams = InsertionOrderedMap::new();
         for (path_name, stream) in rest.into_iter() {
-            streams.try_insert(path_name.clone(), stream.get(db).physical(db))?;
+            streams.try_insert(path_name.clone(), stream.get(db).physical(db)?)?;
         }
         let logical_stream = LogicalStream::new(signals, streams);
         let hierarchy = TypeHierarchy::from_stream(db, *self)?;
@@ -502,4 +679,22 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn generic_throughput() -> Result<()> {
+        let n = Name::try_new("n")?;
+        let param = StreamProperty::<Throughput>::try_new(GenericProperty::from(&n))?;
+        assert_eq!(param.fixed(), None);
+        assert!(param.try_fixed("throughput").is_err());
+
+        let doubled = param.try_mul(&StreamProperty::try_from(2.0)?)?;
+        assert_eq!(doubled.try_assign(&n, 3)?, StreamProperty::try_from(6.0)?);
+        assert!(param.try_mul(&StreamProperty::try_from(1.5)?).is_err());
+        assert_eq!(
+            StreamProperty::try_from(1.5)?.try_mul(&StreamProperty::try_from(2.0)?)?,
+            StreamProperty::try_from(3.0)?
+        );
+        assert!(StreamProperty::<Complexity>::try_new(0).is_err());
+        Ok(())
+    }
 }
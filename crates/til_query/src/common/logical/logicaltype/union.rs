@@ -84,6 +84,16 @@ impl Union {
         }
     }
 
+    /// Returns true if all fields of this Union are Null, meaning it only
+    /// carries a tag. This is how `Enum` types are represented.
+    pub fn is_enumeration(&self, db: &dyn Ir) -> bool {
+        self.fields.len() > 0
+            && self
+                .field_ids()
+                .values()
+                .all(|id| id.get(db) == LogicalType::Null)
+    }
+
     /// Returns the tag width of this union.
     pub fn tag(&self) -> Option<BitCount> {
         if self.fields.len() > 1 {
@@ -138,7 +148,11 @@ impl fmt::Display for Union {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ir::{db::Database, interner::Interner};
+    use crate::{
+        common::physical::stream::PhysicalBitCount,
+        ir::{db::Database, interner::Interner},
+    };
+    use tydi_common::error::Error;
 
     #[test]
     fn test_new() {
@@ -153,4 +167,52 @@ mod tests {
         );
         assert_eq!(union.tag(), None);
     }
+
+    #[test]
+    fn test_is_enumeration() {
+        let db = Database::default();
+        let bits = db.intern_type(LogicalType::try_new_bits(8).unwrap());
+        let null = LogicalType::null_id(&db);
+        let union = Union::try_new(None, vec![("a", null), ("b", null)]).unwrap();
+        assert!(union.is_enumeration(&db));
+        assert_eq!(union.tag(), Some(BitCount::new(1).unwrap()));
+        let union = Union::try_new(None, vec![("a", null), ("b", bits)]).unwrap();
+        assert!(!union.is_enumeration(&db));
+        assert!(!Union::try_new(None, Vec::<(&str, _)>::new())
+            .unwrap()
+            .is_enumeration(&db));
+    }
+
+    #[test]
+    fn test_generic_width() -> Result<()> {
+        let db = Database::default();
+        let width = |name: &str| -> Result<_> {
+            let bits = LogicalType::try_new_generic_bits(Name::try_new(name)?)?;
+            Ok(db.intern_type(bits))
+        };
+        let fixed = db.intern_type(LogicalType::try_new_bits(8)?);
+
+        let union = LogicalType::from(Union::try_new(
+            None,
+            vec![("a", width("w")?), ("b", width("w")?)],
+        )?);
+        assert_eq!(
+            union.fields(&db)?.get(&PathName::try_new(vec!["union"])?),
+            Some(&PhysicalBitCount::parameterized(Name::try_new("w")?))
+        );
+
+        for fields in [
+            vec![("a", width("w")?), ("b", fixed)],
+            vec![("a", width("w")?), ("b", width("v")?)],
+        ] {
+            let union = LogicalType::from(Union::try_new(None, fields)?);
+            let err = union.fields(&db).err().unwrap();
+            assert!(matches!(err, Error::InvalidArgument(_)), "{:?}", err);
+            assert_eq!(
+                err.to_string(),
+                "Invalid argument: Unable to determine the width of the Union with fields a, b, as the widths of its fields depend on different generic parameters, or on both generic parameters and fixed widths"
+            );
+        }
+        Ok(())
+    }
 }
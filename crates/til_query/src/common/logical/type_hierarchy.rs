@@ -1,11 +1,11 @@
 use tydi_common::{
-    error::Result, map::InsertionOrderedMap, name::PathName, numbers::Positive,
+    error::Result, map::InsertionOrderedMap, name::PathName, numbers::NonNegative,
 };
 use tydi_intern::Id;
 
 use crate::ir::{traits::GetSelf, Ir};
 
-use super::logicaltype::{stream::Stream, LogicalType};
+use super::logicaltype::{genericproperty::GenericProperty, stream::Stream, LogicalType};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// A simplified representation of the hierarchy of different logical types.
@@ -13,7 +13,7 @@ use super::logicaltype::{stream::Stream, LogicalType};
 /// This is used to reconstruct type references during Synthesis.
 pub enum TypeHierarchy {
     Null,
-    Bits(Positive),
+    Bits(GenericProperty<NonNegative>),
     Group(InsertionOrderedMap<PathName, TypeHierarchy>),
     Union(InsertionOrderedMap<PathName, TypeHierarchy>),
     Stream(Box<TypeHierarchy>),
@@ -47,6 +47,7 @@ impl TypeHierarchy {
             LogicalType::Group(group) => Self::Group(fields_into_hierarchy(db, group.field_ids())?),
             LogicalType::Union(union) => Self::Union(fields_into_hierarchy(db, union.field_ids())?),
             LogicalType::Stream(stream_id) => Self::from_stream(db, stream_id)?,
+            LogicalType::Array(array) => return Err(array.unassigned_error()),
         })
     }
 
@@ -5,7 +5,7 @@ use tydi_common::{
     error::{Error, Result},
     map::InsertionOrderedMap,
     name::{Name, PathName},
-    numbers::Positive,
+    numbers::NonNegative,
 };
 use tydi_intern::Id;
 
@@ -13,7 +13,11 @@ use crate::ir::{traits::GetSelf, Ir};
 
 use self::{scope_stream::ScopeStream, stream_reference::StreamReference};
 
-use super::{logicaltype::LogicalType, split_streams::SplitStreams, type_hierarchy::TypeHierarchy};
+use super::{
+    logicaltype::{genericproperty::GenericProperty, LogicalType},
+    split_streams::SplitStreams,
+    type_hierarchy::TypeHierarchy,
+};
 
 pub mod scope_stream;
 pub mod stream_reference;
@@ -25,7 +29,7 @@ pub enum ElementManipulatingReference {
     /// Null does not have any associated physical signals.
     Null,
     /// Bits(N) refers to N bits of a `data` signal
-    Bits(Positive),
+    Bits(GenericProperty<NonNegative>),
     /// Group contains a number of distinct fields, which themselves refer to
     /// either element-manipulating nodes or Streams.
     Group(InsertionOrderedMap<Name, TypeReference>),
@@ -69,6 +73,7 @@ impl ElementManipulatingReference {
             LogicalType::Stream(_) => Err(Error::InvalidArgument(
                 "The user signal should only carry element-manipulating types".to_string(),
             )),
+            LogicalType::Array(array) => Err(array.unassigned_error()),
         }
     }
 }
@@ -77,6 +82,9 @@ impl fmt::Display for ElementManipulatingReference {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ElementManipulatingReference::Null => write!(f, "Null"),
+            ElementManipulatingReference::Bits(GenericProperty::Fixed(b)) => {
+                write!(f, "Bits({})", b)
+            }
             ElementManipulatingReference::Bits(b) => write!(f, "Bits({})", b),
             ElementManipulatingReference::Group(g) => write!(
                 f,
@@ -158,7 +166,7 @@ impl TypeReference {
                 TypeReference::ElementManipulating(ElementManipulatingReference::Null)
             }
             TypeHierarchy::Bits(n) => {
-                TypeReference::ElementManipulating(ElementManipulatingReference::Bits(*n))
+                TypeReference::ElementManipulating(ElementManipulatingReference::Bits(n.clone()))
             }
             TypeHierarchy::Group(fields) => {
                 let mut group_fields = InsertionOrderedMap::new();
@@ -49,7 +49,7 @@ impl StreamReference {
         let stream = stream_id.get(db);
         let physical_stream = path_name.clone();
         let direction = stream.direction();
-        let complexity = stream.complexity();
+        let complexity = stream.complexity().try_fixed("complexity")?;
         let dimensionality = stream.dimensionality().clone();
         let transfer_scope = if path_name.is_empty() {
             TransferScope::Root
@@ -61,7 +61,7 @@ impl StreamReference {
                 Synchronicity::Desync | Synchronicity::FlatDesync => TransferScope::Root,
             }
         };
-        let element_lanes = stream.throughput().positive();
+        let element_lanes = stream.throughput().try_fixed("throughput")?.positive();
         let user = ElementManipulatingReference::from_logical_type_id(db, stream.user_id())?;
 
         Ok(StreamReference {
@@ -7,7 +7,7 @@ use tydi_common::{
     error::{Error, Result, TryResult},
     map::InsertionOrderedMap,
     name::{Name, PathName},
-    numbers::{BitCount, NonNegative, Positive},
+    numbers::{NonNegative, Positive},
     util::log2_ceil,
 };
 
@@ -38,6 +38,30 @@ impl PhysicalBitCount {
         }
     }
 
+    /// The combined bit count of `bit_counts`, or None if there are none.
+    ///
+    /// Fixed bit counts are added up, bit counts which depend on generic
+    /// parameters are added to the result in order.
+    pub fn sum<'a>(bit_counts: impl IntoIterator<Item = &'a PhysicalBitCount>) -> Option<Self> {
+        let mut fixed = 0;
+        let mut result: Option<Self> = None;
+        for bit_count in bit_counts {
+            match bit_count.try_eval() {
+                Some(f) => fixed += f.get(),
+                None => {
+                    result = Some(match result {
+                        Some(r) => r + bit_count.clone(),
+                        None => bit_count.clone(),
+                    })
+                }
+            }
+        }
+        match (result, PhysicalBitCount::fixed(fixed)) {
+            (Some(r), Some(f)) => Some(r + f),
+            (r, f) => r.or(f),
+        }
+    }
+
     pub fn with_multiplier(self, m: NonNegative) -> Self {
         if let Some(mul) = Positive::new(m) {
             if mul > Positive::new(1).unwrap() {
@@ -151,7 +175,7 @@ impl Div for PhysicalBitCount {
 #[derive(Debug, Clone, PartialEq)]
 pub struct PhysicalStream {
     /// Element content.
-    element_fields: InsertionOrderedMap<PathName, BitCount>,
+    element_fields: InsertionOrderedMap<PathName, PhysicalBitCount>,
     /// Number of element lanes.
     element_lanes: Positive,
     /// Dimensionality.
@@ -159,7 +183,7 @@ pub struct PhysicalStream {
     /// Complexity.
     complexity: Complexity,
     /// User-defined transfer content.
-    user: InsertionOrderedMap<PathName, BitCount>,
+    user: InsertionOrderedMap<PathName, PhysicalBitCount>,
     /// The Stream's direction.
     stream_direction: StreamDirection,
 }
@@ -180,7 +204,7 @@ impl PhysicalStream {
         let mut element_fields_result = InsertionOrderedMap::new();
         for (path_name, bit_count) in element_fields.into_iter() {
             let path_name = path_name.try_into()?;
-            match Positive::new(bit_count as NonNegative) {
+            match PhysicalBitCount::fixed(bit_count as NonNegative) {
                 Some(bit_count) => element_fields_result.try_insert(path_name, bit_count)?,
                 None => {
                     return Err(Error::InvalidArgument(
@@ -195,7 +219,7 @@ impl PhysicalStream {
         let mut user_result = InsertionOrderedMap::new();
         for (path_name, bit_count) in user.into_iter() {
             let path_name = path_name.try_into()?;
-            match Positive::new(bit_count as NonNegative) {
+            match PhysicalBitCount::fixed(bit_count as NonNegative) {
                 Some(bit_count) => user_result.try_insert(path_name, bit_count)?,
                 None => {
                     return Err(Error::InvalidArgument(
@@ -216,11 +240,11 @@ impl PhysicalStream {
     /// Constructs a new PhysicalStream using provided arguments. Returns an
     /// error when provided argument are not valid.
     pub fn new(
-        element_fields: impl Into<InsertionOrderedMap<PathName, BitCount>>,
+        element_fields: impl Into<InsertionOrderedMap<PathName, PhysicalBitCount>>,
         element_lanes: Positive,
         dimensionality: impl Into<GenericProperty<NonNegative>>,
         complexity: impl Into<Complexity>,
-        user: impl Into<InsertionOrderedMap<PathName, BitCount>>,
+        user: impl Into<InsertionOrderedMap<PathName, PhysicalBitCount>>,
         stream_direction: StreamDirection,
     ) -> Self {
         PhysicalStream {
@@ -234,7 +258,7 @@ impl PhysicalStream {
     }
 
     /// Returns the element fields in this physical stream.
-    pub fn element_fields(&self) -> &InsertionOrderedMap<PathName, BitCount> {
+    pub fn element_fields(&self) -> &InsertionOrderedMap<PathName, PhysicalBitCount> {
         &self.element_fields
     }
 
@@ -254,19 +278,14 @@ impl PhysicalStream {
     }
 
     /// Returns the user fields in this physical stream.
-    pub fn user(&self) -> &InsertionOrderedMap<PathName, BitCount> {
+    pub fn user(&self) -> &InsertionOrderedMap<PathName, PhysicalBitCount> {
         &self.user
     }
 
     /// Returns the bit count of a single data element in this physical
     /// stream. The bit count is equal to the combined bit count of all fields.
     pub fn data_element_bit_count(&self) -> Option<PhysicalBitCount> {
-        PhysicalBitCount::fixed(
-            self.element_fields
-                .values()
-                .map(|b| b.get())
-                .sum::<NonNegative>(),
-        )
+        PhysicalBitCount::sum(self.element_fields.values())
     }
 
     /// Returns the bit count of the data (element) fields in this physical
@@ -336,7 +355,7 @@ impl PhysicalStream {
 
     /// Returns the bit count of the user fields in this physical stream.
     pub fn user_bit_count(&self) -> Option<PhysicalBitCount> {
-        PhysicalBitCount::fixed(self.user.values().map(|b| b.get()).sum::<NonNegative>())
+        PhysicalBitCount::sum(self.user.values())
     }
 
     /// The Stream's direction.
@@ -2,27 +2,77 @@ use core::fmt;
 
 use tydi_common::error::{Result, TryResult};
 
-use self::dimensionality::DimensionalityGeneric;
+use self::{
+    complexity::ComplexityGeneric, dimensionality::DimensionalityGeneric,
+    throughput::ThroughputGeneric,
+};
 
-use super::{condition::TestValue, param_value::GenericParamValue};
+use super::{
+    condition::{
+        integer_condition::IntegerCondition, AppliesCondition, GenericCondition, TestValue,
+    },
+    param_value::GenericParamValue,
+};
 
+pub mod complexity;
 pub mod dimensionality;
+pub mod throughput;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum InterfaceGenericKind {
     Dimensionality(DimensionalityGeneric),
+    Throughput(ThroughputGeneric),
+    Complexity(ComplexityGeneric),
 }
 
 impl InterfaceGenericKind {
     pub fn dimensionality() -> Self {
         DimensionalityGeneric::new().into()
     }
+
+    pub fn throughput() -> Self {
+        ThroughputGeneric::new().into()
+    }
+
+    pub fn complexity() -> Self {
+        ComplexityGeneric::new().into()
+    }
+
+    /// The condition on the values of this parameter, all interface
+    /// parameters are integers.
+    pub fn condition(&self) -> &GenericCondition<IntegerCondition> {
+        match self {
+            InterfaceGenericKind::Dimensionality(dim) => dim.condition(),
+            InterfaceGenericKind::Throughput(thr) => thr.condition(),
+            InterfaceGenericKind::Complexity(com) => com.condition(),
+        }
+    }
+
+    /// Returns this kind of parameter with the given condition.
+    pub fn with_condition(
+        self,
+        condition: impl TryResult<GenericCondition<IntegerCondition>>,
+    ) -> Result<Self> {
+        Ok(match self {
+            InterfaceGenericKind::Dimensionality(dim) => dim.with_condition(condition)?.into(),
+            InterfaceGenericKind::Throughput(thr) => thr.with_condition(condition)?.into(),
+            InterfaceGenericKind::Complexity(com) => com.with_condition(condition)?.into(),
+        })
+    }
+
+    /// Whether both kinds are the same kind of parameter, regardless of their
+    /// conditions.
+    pub fn same_kind(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
 }
 
 impl fmt::Display for InterfaceGenericKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             InterfaceGenericKind::Dimensionality(_) => write!(f, "Dimensionality",),
+            InterfaceGenericKind::Throughput(_) => write!(f, "Throughput",),
+            InterfaceGenericKind::Complexity(_) => write!(f, "Complexity",),
         }
     }
 }
@@ -33,16 +83,32 @@ impl From<DimensionalityGeneric> for InterfaceGenericKind {
     }
 }
 
+impl From<ThroughputGeneric> for InterfaceGenericKind {
+    fn from(val: ThroughputGeneric) -> Self {
+        Self::Throughput(val)
+    }
+}
+
+impl From<ComplexityGeneric> for InterfaceGenericKind {
+    fn from(val: ComplexityGeneric) -> Self {
+        Self::Complexity(val)
+    }
+}
+
 impl TestValue for InterfaceGenericKind {
     fn valid_value(&self, value: impl TryResult<GenericParamValue>) -> Result<bool> {
         match self {
             InterfaceGenericKind::Dimensionality(dim) => dim.valid_value(value),
+            InterfaceGenericKind::Throughput(thr) => thr.valid_value(value),
+            InterfaceGenericKind::Complexity(com) => com.valid_value(value),
         }
     }
 
     fn describe_condition(&self) -> String {
         match self {
             InterfaceGenericKind::Dimensionality(dim) => dim.describe_condition(),
+            InterfaceGenericKind::Throughput(thr) => thr.describe_condition(),
+            InterfaceGenericKind::Complexity(com) => com.describe_condition(),
         }
     }
 }
@@ -9,7 +9,10 @@ use tydi_common::{
 use self::{
     behavioral::BehavioralGenericKind,
     condition::{AppliesCondition, TestValue},
-    interface::{dimensionality::DimensionalityGeneric, InterfaceGenericKind},
+    interface::{
+        complexity::ComplexityGeneric, dimensionality::DimensionalityGeneric,
+        throughput::ThroughputGeneric, InterfaceGenericKind,
+    },
     param_value::GenericParamValue,
 };
 
@@ -58,27 +61,22 @@ impl GenericKind {
                     }
                 }
             }
-            GenericKind::Interface(i) => match i {
-                InterfaceGenericKind::Dimensionality(d) => {
-                    if let GenericKind::Interface(InterfaceGenericKind::Dimensionality(other_d)) =
-                        other
-                    {
-                        if d.condition().satisfies(other_d.condition()) {
-                            Ok(())
-                        } else {
-                            Err(Error::InvalidArgument(format!(
-                                "Condition \"{}\" is more permissive than condition \"{}\"",
-                                d.describe_condition(),
-                                other_d.describe_condition()
-                            )))
-                        }
+            GenericKind::Interface(i) => match other {
+                GenericKind::Interface(other_i) if i.same_kind(other_i) => {
+                    if i.condition().satisfies(other_i.condition()) {
+                        Ok(())
                     } else {
                         Err(Error::InvalidArgument(format!(
-                            "Expected a parameter of type {}, this is a parameter with type {}",
-                            other, self
+                            "Condition \"{}\" is more permissive than condition \"{}\"",
+                            i.describe_condition(),
+                            other_i.describe_condition()
                         )))
                     }
                 }
+                _ => Err(Error::InvalidArgument(format!(
+                    "Expected a parameter of type {}, this is a parameter with type {}",
+                    other, self
+                ))),
             },
         }
     }
@@ -111,6 +109,18 @@ impl From<DimensionalityGeneric> for GenericKind {
     }
 }
 
+impl From<ThroughputGeneric> for GenericKind {
+    fn from(val: ThroughputGeneric) -> Self {
+        Self::Interface(val.into())
+    }
+}
+
+impl From<ComplexityGeneric> for GenericKind {
+    fn from(val: ComplexityGeneric) -> Self {
+        Self::Interface(val.into())
+    }
+}
+
 impl TestValue for GenericKind {
     fn valid_value(&self, value: impl TryResult<GenericParamValue>) -> Result<bool> {
         match self {
@@ -63,7 +63,9 @@ impl GenericParamValue {
                     BehavioralGenericKind::Integer(_) => true,
                 },
                 GenericKind::Interface(i) => match i {
-                    InterfaceGenericKind::Dimensionality(_) => true,
+                    InterfaceGenericKind::Dimensionality(_)
+                    | InterfaceGenericKind::Throughput(_)
+                    | InterfaceGenericKind::Complexity(_) => true,
                 },
             },
             GenericParamValue::Combination(c) => c.left_val().is_integer(),
@@ -63,13 +63,17 @@ impl GenericParameterAssignment {
         }
     }
 
-    pub fn kind(&self) -> &GenericKind {
+    pub fn parameter(&self) -> &GenericParameter {
         match self {
-            GenericParameterAssignment::Default(p) => p.kind(),
-            GenericParameterAssignment::Assigned(p, _) => p.kind(),
+            GenericParameterAssignment::Default(p) => p,
+            GenericParameterAssignment::Assigned(p, _) => p,
         }
     }
 
+    pub fn kind(&self) -> &GenericKind {
+        self.parameter().kind()
+    }
+
     pub fn value(&self) -> &GenericParamValue {
         match self {
             GenericParameterAssignment::Default(p) => p.default_value(),
@@ -0,0 +1,917 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
+    str::FromStr,
+    sync::Arc,
+};
+
+use tydi_common::{
+    error::{Error, Result},
+    name::{Name, NameSelf, PathName, PathNameSelf},
+};
+use tydi_intern::Id;
+
+use super::{
+    generics::param_value::{
+        combination::{Combination, MathCombination},
+        GenericParamValue,
+    },
+    implementation::{
+        structure::streamlet_instance::GenericParameterAssignment, Implementation,
+        ImplementationKind,
+    },
+    physical_properties::Domain,
+    project::{interface::Interface, namespace::Namespace, Project},
+    traits::GetSelf,
+    Ir,
+};
+
+/// The checks which can be applied to the declarations of a project, which
+/// point out likely mistakes in otherwise valid declarations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Lint {
+    /// A port of a streamlet instance in a structure is not connected.
+    UnconnectedPort,
+    /// A type, interface or implementation is not referred to by name by any
+    /// other declaration.
+    UnusedDeclaration,
+    /// A domain of an interface is not used by any of its ports.
+    UnusedDomain,
+    /// A streamlet has neither ports nor an implementation.
+    EmptyStreamlet,
+    /// A generic parameter is declared, but never referred to.
+    UnusedParameter,
+    /// A generic parameter has the same name as a constant declared in the
+    /// same namespace, which can not be referred to where the parameter is
+    /// declared.
+    ShadowedConstant,
+}
+
+impl Lint {
+    pub const VARIANTS: [&'static str; 6] = [
+        "unconnected-port",
+        "unused-declaration",
+        "unused-domain",
+        "empty-streamlet",
+        "unused-parameter",
+        "shadowed-constant",
+    ];
+}
+
+impl FromStr for Lint {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "unconnected-port" => Ok(Lint::UnconnectedPort),
+            "unused-declaration" => Ok(Lint::UnusedDeclaration),
+            "unused-domain" => Ok(Lint::UnusedDomain),
+            "empty-streamlet" => Ok(Lint::EmptyStreamlet),
+            "unused-parameter" => Ok(Lint::UnusedParameter),
+            "shadowed-constant" => Ok(Lint::ShadowedConstant),
+            _ => Err(Error::InvalidArgument(format!(
+                "\"{}\" is not a lint, expected one of: {}",
+                s,
+                Self::VARIANTS.join(", ")
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Lint::UnconnectedPort => "unconnected-port",
+            Lint::UnusedDeclaration => "unused-declaration",
+            Lint::UnusedDomain => "unused-domain",
+            Lint::EmptyStreamlet => "empty-streamlet",
+            Lint::UnusedParameter => "unused-parameter",
+            Lint::ShadowedConstant => "shadowed-constant",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// What to do with the declarations flagged by a lint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum LintLevel {
+    /// Do not apply the lint.
+    Allow,
+    /// Warn about the declarations flagged by the lint.
+    #[default]
+    Warn,
+    /// Treat the declarations flagged by the lint as errors.
+    Deny,
+}
+
+impl LintLevel {
+    pub const VARIANTS: [&'static str; 3] = ["allow", "warn", "deny"];
+}
+
+impl FromStr for LintLevel {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "allow" => Ok(LintLevel::Allow),
+            "warn" => Ok(LintLevel::Warn),
+            "deny" => Ok(LintLevel::Deny),
+            _ => Err(Error::InvalidArgument(format!(
+                "\"{}\" is not a valid lint level, expected one of: {}",
+                s,
+                Self::VARIANTS.join(", ")
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LintLevel::Allow => "allow",
+            LintLevel::Warn => "warn",
+            LintLevel::Deny => "deny",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A declaration flagged by a lint.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LintMessage {
+    lint: Lint,
+    level: LintLevel,
+    namespace: PathName,
+    declaration: Name,
+    message: String,
+}
+
+impl LintMessage {
+    pub fn lint(&self) -> Lint {
+        self.lint
+    }
+
+    /// The level of the lint, as configured for the project.
+    pub fn level(&self) -> LintLevel {
+        self.level
+    }
+
+    /// The namespace of the flagged declaration.
+    pub fn namespace(&self) -> &PathName {
+        &self.namespace
+    }
+
+    /// The name of the flagged declaration within its namespace.
+    pub fn declaration(&self) -> &Name {
+        &self.declaration
+    }
+
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+}
+
+impl fmt::Display for LintMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+/// Apply the lints to the namespaces of the project in `db`, at the levels
+/// configured for the project. Lints which are allowed are skipped.
+///
+/// A declaration is used when the [references](Namespace::references) of any
+/// namespace of the project include its full name. Declarations only referred
+/// to by unused declarations still count as used.
+///
+/// The namespaces of imported projects are not linted, but their
+/// declarations can be used by the project's namespaces.
+pub fn lint_project(db: &dyn Ir) -> Result<Vec<LintMessage>> {
+    let project = db.project();
+    let namespaces = project
+        .namespaces()
+        .values()
+        .map(|id| id.get(db))
+        .collect::<Vec<_>>();
+    let mut linter = Linter {
+        db,
+        usage: Usage::collect(db, &namespaces)?,
+        project,
+        linted_interfaces: HashSet::new(),
+        messages: vec![],
+    };
+    for namespace in namespaces.iter() {
+        linter.lint_namespace(namespace)?;
+    }
+    Ok(linter.messages)
+}
+
+/// The declarations referred to by the namespaces of a project.
+#[derive(Default)]
+struct Usage {
+    /// The full names of the declarations referred to by name.
+    references: HashSet<PathName>,
+    /// The parameters of interfaces referred to by the streamlet instances of
+    /// structures implementing them.
+    parameters: HashMap<Id<Arc<Interface>>, BTreeSet<Name>>,
+    /// The domains of interfaces assigned to the streamlet instances of
+    /// structures implementing them.
+    domains: HashMap<Id<Arc<Interface>>, BTreeSet<Domain>>,
+    /// Interfaces with linked implementations, whose parameters are passed on
+    /// to the linked implementation.
+    linked: HashSet<Id<Arc<Interface>>>,
+}
+
+impl Usage {
+    fn collect(db: &dyn Ir, namespaces: &[Namespace]) -> Result<Self> {
+        let mut usage = Usage::default();
+        let mut structures = HashSet::new();
+        for namespace in namespaces {
+            usage
+                .references
+                .extend(namespace.references().iter().cloned());
+            for (name, interface_id) in namespace.interface_ids() {
+                // Implementations also declare an interface under their own
+                // name.
+                if let Some(implementation_id) = namespace.implementation_ids().get(name) {
+                    if let ImplementationKind::Link(_) = implementation_id.get(db).kind() {
+                        usage.linked.insert(*interface_id);
+                    }
+                }
+            }
+            for streamlet in namespace.streamlets(db).values() {
+                if let Some(interface_id) = streamlet.interface_id() {
+                    if let Some(ImplementationKind::Link(_)) =
+                        streamlet.implementation(db).as_ref().map(|i| i.kind())
+                    {
+                        usage.linked.insert(interface_id);
+                    }
+                }
+                if let Some(implementation_id) = streamlet.implementation_id() {
+                    if structures.insert(implementation_id) {
+                        usage.add_implementation(db, &implementation_id.get(db))?;
+                    }
+                }
+            }
+            for implementation_id in namespace.implementation_ids().values() {
+                if structures.insert(*implementation_id) {
+                    usage.add_implementation(db, &implementation_id.get(db))?;
+                }
+            }
+        }
+        Ok(usage)
+    }
+
+    fn add_implementation(&mut self, db: &dyn Ir, implementation: &Implementation) -> Result<()> {
+        if let ImplementationKind::Structural(structure) = implementation.kind() {
+            let interface_id = structure.interface_id();
+            let parameters = self.parameters.entry(interface_id).or_default();
+            let domains = self.domains.entry(interface_id).or_default();
+            for instance in structure.streamlet_instances().values() {
+                // Parameters of the structure assigned to the instance remain
+                // in the streams of its ports.
+                for port in instance.ports().values() {
+                    parameters.extend(db.stream_parameter_kinds(port.stream_id())?.keys().cloned());
+                }
+                for assignment in instance.parameter_assignments().values() {
+                    if let GenericParameterAssignment::Assigned(_, value) = assignment {
+                        add_param_refs(value, parameters);
+                    }
+                }
+                if let Some(assigned) = instance.assigned_domains()? {
+                    domains.extend(assigned.iter().cloned());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Add the names of the parameters referred to by `value` to `result`.
+fn add_param_refs(value: &GenericParamValue, result: &mut BTreeSet<Name>) {
+    fn add_math_refs(math: &MathCombination, result: &mut BTreeSet<Name>) {
+        match math {
+            MathCombination::Parentheses(inner) => add_math_refs(inner, result),
+            MathCombination::Negative(value) => add_param_refs(value, result),
+            MathCombination::Combination(left, _, right) => {
+                add_param_refs(left, result);
+                add_param_refs(right, result);
+            }
+        }
+    }
+
+    match value {
+        GenericParamValue::Integer(_) => (),
+        GenericParamValue::Ref(param_ref) => {
+            result.insert(param_ref.name().clone());
+        }
+        GenericParamValue::Combination(Combination::Math(math)) => add_math_refs(math, result),
+    }
+}
+
+struct Linter<'a> {
+    db: &'a dyn Ir,
+    project: Project,
+    usage: Usage,
+    /// Interfaces have no name of their own, and can be declared under
+    /// several names (and imported), they are only linted for the first.
+    linted_interfaces: HashSet<Id<Arc<Interface>>>,
+    messages: Vec<LintMessage>,
+}
+
+impl Linter<'_> {
+    fn push(
+        &mut self,
+        lint: Lint,
+        namespace: &Namespace,
+        declaration: &Name,
+        message: impl Into<String>,
+    ) {
+        let level = self.project.lint_level(lint);
+        if level != LintLevel::Allow {
+            self.messages.push(LintMessage {
+                lint,
+                level,
+                namespace: namespace.path_name().clone(),
+                declaration: declaration.clone(),
+                message: message.into(),
+            })
+        }
+    }
+
+    fn lint_namespace(&mut self, namespace: &Namespace) -> Result<()> {
+        let db = self.db;
+        // Only declarations made in this namespace are linted, not those
+        // imported into it.
+        let is_declared =
+            |name: &Name, path: &PathName| path == &namespace.path_name().with_child(name.clone());
+
+        for (name, type_decl) in namespace.type_decls() {
+            if !is_declared(name, type_decl.path_name()) {
+                continue;
+            }
+            let path = type_decl.path_name().join("::");
+            if !self.usage.references.contains(type_decl.path_name()) {
+                self.push(
+                    Lint::UnusedDeclaration,
+                    namespace,
+                    name,
+                    format!("Type {} is never used", path),
+                );
+            }
+            let parameters = type_decl.parameters();
+            if parameters.len() > 0 {
+                let used = db.logical_type_parameter_kinds(type_decl.declared_type_id())?;
+                for parameter in parameters.keys() {
+                    if !used.contains(parameter) {
+                        self.push(
+                            Lint::UnusedParameter,
+                            namespace,
+                            name,
+                            format!("Parameter {} of type {} is never used", parameter, path),
+                        );
+                    }
+                }
+            }
+            self.lint_shadowing(namespace, name, "type", &path, parameters.keys());
+        }
+
+        // Interfaces declared by themselves are linted before those of
+        // streamlets and implementations, which may share their interface.
+        let (declared_interfaces, other_interfaces): (Vec<_>, Vec<_>) =
+            namespace.interface_ids().iter().partition(|(name, _)| {
+                !namespace.streamlet_ids().contains_key(*name)
+                    && !namespace.implementation_ids().contains_key(*name)
+            });
+        for (name, interface_id) in declared_interfaces {
+            let path = namespace.path_name().with_child(name.clone());
+            if !self.usage.references.contains(&path) {
+                self.push(
+                    Lint::UnusedDeclaration,
+                    namespace,
+                    name,
+                    format!("Interface {} is never used", path.join("::")),
+                );
+            }
+            if self.linted_interfaces.insert(*interface_id) {
+                self.lint_interface(namespace, name, "interface", *interface_id)?;
+            }
+        }
+        for (name, interface_id) in other_interfaces {
+            if !self.linted_interfaces.insert(*interface_id) {
+                continue;
+            }
+            let kind = if namespace.streamlet_ids().contains_key(name) {
+                "streamlet"
+            } else {
+                "implementation"
+            };
+            self.lint_interface(namespace, name, kind, *interface_id)?;
+        }
+
+        for (name, streamlet) in namespace.streamlets(db) {
+            if !is_declared(&name, streamlet.path_name()) {
+                continue;
+            }
+            let path = streamlet.path_name().join("::");
+            let implementation = streamlet.implementation(db);
+            if implementation.is_none() && streamlet.ports(db).len() == 0 {
+                self.push(
+                    Lint::EmptyStreamlet,
+                    namespace,
+                    &name,
+                    format!("Streamlet {} has neither ports nor an implementation", path),
+                );
+            }
+            // Implementations declared as part of the streamlet are named
+            // after the streamlet.
+            if let Some(implementation) = implementation {
+                if implementation.path_name() == streamlet.path_name() {
+                    self.lint_implementation(namespace, &name, "streamlet", &implementation);
+                }
+            }
+        }
+
+        for (name, implementation_id) in namespace.implementation_ids() {
+            let implementation = implementation_id.get(db);
+            if !is_declared(name, implementation.path_name()) {
+                continue;
+            }
+            if !self.usage.references.contains(implementation.path_name()) {
+                self.push(
+                    Lint::UnusedDeclaration,
+                    namespace,
+                    name,
+                    format!(
+                        "Implementation {} is never used",
+                        implementation.path_name().join("::")
+                    ),
+                );
+            }
+            self.lint_implementation(namespace, name, "implementation", &implementation);
+        }
+
+        Ok(())
+    }
+
+    /// Lint the domains and parameters of the interface of the declaration
+    /// `name`, which is a `kind` (interface, streamlet or implementation).
+    fn lint_interface(
+        &mut self,
+        namespace: &Namespace,
+        name: &Name,
+        kind: &str,
+        interface_id: Id<Arc<Interface>>,
+    ) -> Result<()> {
+        let db = self.db;
+        let interface = interface_id.get(db);
+        let path = namespace.path_name().with_child(name.clone()).join("::");
+
+        if let Some(domains) = interface.domains() {
+            let assigned = self.usage.domains.get(&interface_id).cloned();
+            for domain in domains.iter() {
+                let is_used = interface
+                    .ports()
+                    .values()
+                    .any(|port| port.domain() == Some(domain))
+                    || assigned
+                        .as_ref()
+                        .is_some_and(|assigned| assigned.contains(domain));
+                if !is_used {
+                    self.push(
+                        Lint::UnusedDomain,
+                        namespace,
+                        name,
+                        format!(
+                            "Domain '{} of {} {} is not used by any of its ports",
+                            domain, kind, path
+                        ),
+                    );
+                }
+            }
+        }
+
+        if !self.usage.linked.contains(&interface_id) {
+            let mut used = self
+                .usage
+                .parameters
+                .get(&interface_id)
+                .cloned()
+                .unwrap_or_default();
+            for port in interface.ports().values() {
+                used.extend(db.stream_parameter_kinds(port.stream_id())?.keys().cloned());
+            }
+            for parameter in interface.parameters().keys() {
+                if !used.contains(parameter) {
+                    self.push(
+                        Lint::UnusedParameter,
+                        namespace,
+                        name,
+                        format!("Parameter {} of {} {} is never used", parameter, kind, path),
+                    );
+                }
+            }
+        }
+
+        self.lint_shadowing(namespace, name, kind, &path, interface.parameters().keys());
+        Ok(())
+    }
+
+    /// Lint the connections of a structural implementation, declared as part
+    /// of the declaration `name`.
+    fn lint_implementation(
+        &mut self,
+        namespace: &Namespace,
+        name: &Name,
+        kind: &str,
+        implementation: &Implementation,
+    ) {
+        if let ImplementationKind::Structural(structure) = implementation.kind() {
+            let connected = structure
+                .connections()
+                .iter()
+                .flat_map(|connection| [connection.source(), connection.sink()])
+                .collect::<HashSet<_>>();
+            let path = namespace.path_name().with_child(name.clone()).join("::");
+            for reference in structure.streamlet_instance_interface_references() {
+                if !connected.contains(&reference) {
+                    self.push(
+                        Lint::UnconnectedPort,
+                        namespace,
+                        name,
+                        format!("Port {} of {} {} is not connected", reference, kind, path),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Lint the names of the `parameters` of the declaration `name` against
+    /// the constants in its namespace.
+    fn lint_shadowing<'p>(
+        &mut self,
+        namespace: &Namespace,
+        name: &Name,
+        kind: &str,
+        path: &str,
+        parameters: impl Iterator<Item = &'p Name>,
+    ) {
+        let shadowed = parameters
+            .filter_map(|parameter| {
+                namespace
+                    .constants()
+                    .get(parameter)
+                    .map(|constant| (parameter, constant.path_name().join("::")))
+            })
+            .collect::<BTreeMap<_, _>>();
+        for (parameter, constant) in shadowed {
+            self.push(
+                Lint::ShadowedConstant,
+                namespace,
+                name,
+                format!(
+                    "Parameter {} of {} {} shadows constant {}",
+                    parameter, kind, path, constant
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        common::logical::logicaltype::{genericproperty::GenericProperty, LogicalType},
+        ir::{
+            db::Database,
+            generics::{behavioral::integer::IntegerGeneric, GenericParameter},
+            implementation::structure::Structure,
+            physical_properties::InterfaceDirection,
+            streamlet::Streamlet,
+        },
+        test_utils::test_stream_id,
+    };
+    use tydi_common::numbers::NonNegative;
+
+    use super::*;
+
+    const LEVELS: [LintLevel; 3] = [LintLevel::Allow, LintLevel::Warn, LintLevel::Deny];
+
+    /// The declarations flagged by `lint` at `level`, and their messages, in
+    /// a project consisting of `namespace`.
+    fn lint_messages(
+        db: &mut Database,
+        namespace: &Namespace,
+        lint: Lint,
+        level: LintLevel,
+    ) -> Result<Vec<(LintLevel, String, String)>> {
+        let mut project = Project::new("proj", ".", None::<&str>)?;
+        project.set_lint_level(lint, level);
+        project.add_namespace(db, namespace.clone())?;
+        db.set_project(project);
+        Ok(super::lint_project(db)?
+            .into_iter()
+            .filter(|message| message.lint() == lint)
+            .map(|message| {
+                (
+                    message.level(),
+                    message.declaration().to_string(),
+                    message.to_string(),
+                )
+            })
+            .collect())
+    }
+
+    /// Assert that `lint` flags the `expected` declarations (with their
+    /// messages) of `namespace` when it warns or denies, and nothing when it
+    /// is allowed.
+    fn assert_flags(
+        db: &mut Database,
+        namespace: &Namespace,
+        lint: Lint,
+        expected: &[(&str, &str)],
+    ) -> Result<()> {
+        for level in LEVELS {
+            let expected = if level == LintLevel::Allow {
+                vec![]
+            } else {
+                expected
+                    .iter()
+                    .map(|(name, message)| (level, name.to_string(), message.to_string()))
+                    .collect()
+            };
+            assert_eq!(lint_messages(db, namespace, lint, level)?, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn lint_project() -> Result<()> {
+        let mut db = Database::default();
+        let stream = test_stream_id(&db, 4)?;
+        let mut namespace = Namespace::new("space")?;
+        namespace.define_type_no_params(&db, "unused", LogicalType::try_new_bits(3)?)?;
+        let streamlet = Streamlet::new().with_ports(
+            &db,
+            vec![
+                ("a", stream, InterfaceDirection::In),
+                ("b", stream, InterfaceDirection::Out),
+            ],
+        )?;
+        let child = namespace.define_streamlet(&db, "child", streamlet.clone())?;
+        let mut structure = Structure::try_from(&streamlet)?;
+        structure.try_add_streamlet_instance_default(&db, "instance", child)?;
+        structure.try_add_connection(&db, "a", ("instance", "a"))?;
+        let implementation = namespace.define_implementation(
+            &db,
+            "structural",
+            Implementation::structural(structure)?,
+        )?;
+        namespace.define_streamlet(
+            &db,
+            "parent",
+            streamlet.with_implementation(Some(implementation)),
+        )?;
+        namespace.add_reference("space::structural")?;
+        namespace.define_streamlet(&db, "empty", Streamlet::new())?;
+
+        let mut project = Project::new("proj", ".", None::<&str>)?;
+        project.set_lint_level(Lint::EmptyStreamlet, LintLevel::Allow);
+        project.set_lint_level(Lint::UnconnectedPort, LintLevel::Deny);
+        project.add_namespace(&db, namespace)?;
+        db.set_project(project);
+
+        let messages = super::lint_project(&db)?
+            .into_iter()
+            .map(|message| {
+                (
+                    message.lint(),
+                    message.level(),
+                    message.declaration().to_string(),
+                    message.to_string(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    Lint::UnusedDeclaration,
+                    LintLevel::Warn,
+                    "unused".to_string(),
+                    "Type space::unused is never used".to_string()
+                ),
+                (
+                    Lint::UnconnectedPort,
+                    LintLevel::Deny,
+                    "structural".to_string(),
+                    "Port instance.b of implementation space::structural is not connected"
+                        .to_string()
+                ),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unconnected_port() -> Result<()> {
+        let mut db = Database::default();
+        let stream = test_stream_id(&db, 4)?;
+        let mut namespace = Namespace::new("space")?;
+        let streamlet = Streamlet::new().with_ports(
+            &db,
+            vec![
+                ("a", stream, InterfaceDirection::In),
+                ("b", stream, InterfaceDirection::Out),
+            ],
+        )?;
+        let child = namespace.define_streamlet(&db, "child", streamlet.clone())?;
+        let mut partial = Structure::try_from(&streamlet)?;
+        partial.try_add_streamlet_instance_default(&db, "instance", child)?;
+        partial.try_add_connection(&db, "a", ("instance", "a"))?;
+        namespace.define_implementation(&db, "partial", Implementation::structural(partial)?)?;
+        let mut complete = Structure::try_from(&streamlet)?;
+        complete.try_add_streamlet_instance_default(&db, "instance", child)?;
+        complete.try_add_connection(&db, "a", ("instance", "a"))?;
+        complete.try_add_connection(&db, ("instance", "b"), "b")?;
+        let complete = namespace.define_implementation(
+            &db,
+            "complete",
+            Implementation::structural(complete)?,
+        )?;
+        namespace.define_streamlet(&db, "parent", streamlet.with_implementation(Some(complete)))?;
+
+        assert_flags(
+            &mut db,
+            &namespace,
+            Lint::UnconnectedPort,
+            &[(
+                "partial",
+                "Port instance.b of implementation space::partial is not connected",
+            )],
+        )
+    }
+
+    #[test]
+    fn unused_declaration() -> Result<()> {
+        let mut db = Database::default();
+        let stream = test_stream_id(&db, 4)?;
+        let mut namespace = Namespace::new("space")?;
+        // Declarations are told apart by their names, not their definitions
+        namespace.define_type_no_params(&db, "nibble", LogicalType::try_new_bits(4)?)?;
+        namespace.define_type_no_params(&db, "unused", LogicalType::try_new_bits(4)?)?;
+        namespace.add_reference("space::nibble")?;
+        namespace.define_type(
+            &db,
+            "word",
+            LogicalType::try_new_generic_bits(GenericProperty::<NonNegative>::Parameterized(
+                Name::try_new("w")?,
+            ))?,
+            [GenericParameter::try_new(
+                "w",
+                IntegerGeneric::positive(),
+                8,
+            )?],
+        )?;
+        // Streamlets also declare their interface under their own name
+        let interface = Interface::new_ports(&db, vec![("a", stream, InterfaceDirection::In)])?;
+        namespace.import_interface("comp", interface)?;
+        namespace.define_streamlet(
+            &db,
+            "comp",
+            Streamlet::new().with_interface(&db, interface.get(&db))?,
+        )?;
+        namespace.import_interface("ports", interface)?;
+
+        assert_flags(
+            &mut db,
+            &namespace,
+            Lint::UnusedDeclaration,
+            &[
+                ("unused", "Type space::unused is never used"),
+                ("word", "Type space::word is never used"),
+                ("ports", "Interface space::ports is never used"),
+            ],
+        )
+    }
+
+    #[test]
+    fn unused_domain() -> Result<()> {
+        let mut db = Database::default();
+        let stream = test_stream_id(&db, 4)?;
+        let mut namespace = Namespace::new("space")?;
+        let interface = Interface::new_ports_domains(
+            &db,
+            ["used", "unused"],
+            vec![
+                ("a", stream, ("used", InterfaceDirection::In)),
+                ("b", stream, ("used", InterfaceDirection::Out)),
+            ],
+        )?;
+        namespace.import_interface("comp", interface)?;
+        namespace.define_streamlet(
+            &db,
+            "comp",
+            Streamlet::new().with_interface(&db, interface.get(&db))?,
+        )?;
+
+        assert_flags(
+            &mut db,
+            &namespace,
+            Lint::UnusedDomain,
+            &[(
+                "comp",
+                "Domain 'unused of streamlet space::comp is not used by any of its ports",
+            )],
+        )
+    }
+
+    #[test]
+    fn empty_streamlet() -> Result<()> {
+        let mut db = Database::default();
+        let stream = test_stream_id(&db, 4)?;
+        let mut namespace = Namespace::new("space")?;
+        namespace.define_streamlet(&db, "empty", Streamlet::new())?;
+        namespace.define_streamlet(
+            &db,
+            "comp",
+            Streamlet::new().with_ports(&db, vec![("a", stream, InterfaceDirection::In)])?,
+        )?;
+
+        assert_flags(
+            &mut db,
+            &namespace,
+            Lint::EmptyStreamlet,
+            &[(
+                "empty",
+                "Streamlet space::empty has neither ports nor an implementation",
+            )],
+        )
+    }
+
+    #[test]
+    fn unused_parameter() -> Result<()> {
+        let mut db = Database::default();
+        let mut namespace = Namespace::new("space")?;
+        namespace.define_type(
+            &db,
+            "word",
+            LogicalType::try_new_generic_bits(GenericProperty::<NonNegative>::Parameterized(
+                Name::try_new("w")?,
+            ))?,
+            [
+                GenericParameter::try_new("w", IntegerGeneric::positive(), 8)?,
+                GenericParameter::try_new("x", IntegerGeneric::natural(), 0)?,
+            ],
+        )?;
+
+        assert_flags(
+            &mut db,
+            &namespace,
+            Lint::UnusedParameter,
+            &[("word", "Parameter x of type space::word is never used")],
+        )
+    }
+
+    #[test]
+    fn shadowed_constant() -> Result<()> {
+        let mut db = Database::default();
+        let mut namespace = Namespace::new("space")?;
+        namespace.define_constant("width", IntegerGeneric::natural(), 8)?;
+        for (name, parameter) in [("shadowing", "width"), ("word", "w")] {
+            namespace.define_type(
+                &db,
+                name,
+                LogicalType::try_new_generic_bits(GenericProperty::<NonNegative>::Parameterized(
+                    Name::try_new(parameter)?,
+                ))?,
+                [GenericParameter::try_new(
+                    parameter,
+                    IntegerGeneric::positive(),
+                    8,
+                )?],
+            )?;
+        }
+
+        assert_flags(
+            &mut db,
+            &namespace,
+            Lint::ShadowedConstant,
+            &[(
+                "shadowing",
+                "Parameter width of type space::shadowing shadows constant space::width",
+            )],
+        )
+    }
+
+    #[test]
+    fn parse_levels() -> Result<()> {
+        assert_eq!("unused-domain".parse::<Lint>()?, Lint::UnusedDomain);
+        assert_eq!("deny".parse::<LintLevel>()?, LintLevel::Deny);
+        assert!("unused".parse::<Lint>().is_err());
+        for variant in Lint::VARIANTS {
+            assert_eq!(variant.parse::<Lint>()?.to_string(), variant);
+        }
+        Ok(())
+    }
+}
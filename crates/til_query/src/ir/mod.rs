@@ -28,7 +28,7 @@ use crate::{
 };
 
 use self::{
-    generics::{interface::InterfaceGenericKind, GenericKind},
+    generics::{behavioral::integer::IntegerGeneric, interface::InterfaceGenericKind, GenericKind},
     implementation::{structure::streamlet_instance::GenericParameterAssignment, Implementation},
     interface_port::InterfacePort,
     interner::Interner,
@@ -46,6 +46,7 @@ pub mod implementation;
 pub mod interface_port;
 pub mod intern_self;
 pub mod interner;
+pub mod lint;
 pub mod physical_properties;
 pub mod project;
 pub mod streamlet;
@@ -119,32 +120,49 @@ fn stream_parameter_kinds(
 ) -> Result<InsertionOrderedMap<Name, GenericKind>> {
     let stream = db.lookup_intern_stream(key);
     let mut result = db.logical_type_parameter_kinds(stream.data_id())?;
-
-    // For now, dimensionality is the only parameterized property, may need to
-    // refactor this in the future.
-    fn add_dim(
-        result: &mut InsertionOrderedMap<Name, GenericKind>,
-        prop: &GenericProperty<NonNegative>,
-    ) -> Result<()> {
-        match prop {
-            GenericProperty::Combination(l, _, r) => {
-                add_dim(result, l.as_ref())?;
-                add_dim(result, r.as_ref())
-            }
-            GenericProperty::Fixed(_) => Ok(()),
-            GenericProperty::Parameterized(n) => try_add_param_kind(
-                result,
-                n.clone(),
-                InterfaceGenericKind::dimensionality().into(),
-            ),
-        }
+    for (kind_name, kind) in db.logical_type_parameter_kinds(stream.user_id())? {
+        try_add_param_kind(&mut result, kind_name, kind)?;
     }
 
-    add_dim(&mut result, stream.dimensionality())?;
+    try_add_property_param_kinds(
+        &mut result,
+        stream.dimensionality(),
+        &InterfaceGenericKind::dimensionality().into(),
+    )?;
+    if let Some(throughput) = stream.throughput().parameterized() {
+        try_add_property_param_kinds(
+            &mut result,
+            throughput,
+            &InterfaceGenericKind::throughput().into(),
+        )?;
+    }
+    if let Some(complexity) = stream.complexity().parameterized() {
+        try_add_property_param_kinds(
+            &mut result,
+            complexity,
+            &InterfaceGenericKind::complexity().into(),
+        )?;
+    }
 
     Ok(result)
 }
 
+/// Add the parameters a property depends on, which are all of the same kind.
+fn try_add_property_param_kinds(
+    result: &mut InsertionOrderedMap<Name, GenericKind>,
+    prop: &GenericProperty<NonNegative>,
+    kind: &GenericKind,
+) -> Result<()> {
+    match prop {
+        GenericProperty::Combination(l, _, r) => {
+            try_add_property_param_kinds(result, l.as_ref(), kind)?;
+            try_add_property_param_kinds(result, r.as_ref(), kind)
+        }
+        GenericProperty::Fixed(_) => Ok(()),
+        GenericProperty::Parameterized(n) => try_add_param_kind(result, n.clone(), kind.clone()),
+    }
+}
+
 fn logical_type_parameter_kinds(
     db: &dyn Ir,
     key: Id<LogicalType>,
@@ -168,8 +186,9 @@ fn logical_type_parameter_kinds(
     let typ = db.lookup_intern_type(key);
     match typ {
         LogicalType::Null => (),
-        // No generic parameter support for Bits yet.
-        LogicalType::Bits(_) => (),
+        LogicalType::Bits(b) => {
+            try_add_property_param_kinds(&mut result, &b, &IntegerGeneric::positive().into())?
+        }
         LogicalType::Group(g) => {
             try_add_params_for_fields(db, g.field_ids(), &mut result)?;
         }
@@ -177,6 +196,14 @@ fn logical_type_parameter_kinds(
             try_add_params_for_fields(db, u.field_ids(), &mut result)?;
         }
         LogicalType::Stream(s) => result = db.stream_parameter_kinds(s)?,
+        LogicalType::Array(a) => {
+            result = db.logical_type_parameter_kinds(a.element_id())?;
+            try_add_property_param_kinds(
+                &mut result,
+                a.length(),
+                &IntegerGeneric::positive().into(),
+            )?
+        }
     }
     Ok(result)
 }
@@ -239,6 +266,7 @@ fn logical_type_split_streams(db: &dyn Ir, key: Id<LogicalType>) -> Result<Split
             SplitStreams::new(LogicalType::from(Union::new(fields)).intern(db), streams)
         }
         LogicalType::Stream(stream_id) => stream_id.split_streams(db)?,
+        LogicalType::Array(array) => return Err(array.unassigned_error()),
     })
 }
 
@@ -255,7 +283,7 @@ fn stream_split_streams(db: &dyn Ir, key: Id<Stream>) -> Result<SplitStreams> {
                 this_stream.throughput(),
                 this_stream.dimensionality().clone(),
                 this_stream.synchronicity(),
-                this_stream.complexity().clone(),
+                this_stream.complexity(),
                 this_stream.direction(),
                 this_stream.user_id(),
                 this_stream.keep(),
@@ -276,7 +304,7 @@ fn stream_split_streams(db: &dyn Ir, key: Id<Stream>) -> Result<SplitStreams> {
                 stream.dimensionality().clone() + this_stream.dimensionality().clone(),
             );
         }
-        stream.set_throughput(stream.throughput() * this_stream.throughput());
+        stream.set_throughput(stream.throughput().try_mul(&this_stream.throughput())?);
 
         streams.try_insert(name.clone(), stream.intern(db)).wrap_err(Error::InvalidArgument(
                 r#"An error occurred during the SplitStreams function due to overlapping Stream names.
@@ -308,9 +336,12 @@ fn type_for_param_assignments(
             LogicalType::Null => Err(Error::BackEndError(
                 "Found a parameter to assign for Null, when none should exist".to_string(),
             )),
-            LogicalType::Bits(_) => Err(Error::BackEndError(
-                "Found a parameter to assign for Bits, when none should exist".to_string(),
-            )),
+            LogicalType::Bits(mut b) => {
+                for (param_name, param_assignment) in to_assign {
+                    b = b.try_assign(&param_name, param_assignment.value_take())?;
+                }
+                Ok(db.intern_type(LogicalType::try_new_generic_bits(b)?))
+            }
             LogicalType::Group(g) => {
                 let modified_fields = g.field_ids().clone().try_map_convert(|field_id| {
                     db.type_for_param_assignments(field_id, to_assign.clone())
@@ -326,6 +357,15 @@ fn type_for_param_assignments(
             LogicalType::Stream(s) => Ok(db.intern_type(LogicalType::Stream(
                 db.stream_for_param_assignments(s, to_assign)?,
             ))),
+            // Once its length is fixed, the Array becomes a Group
+            LogicalType::Array(a) => {
+                let element = db.type_for_param_assignments(a.element_id(), to_assign.clone())?;
+                let mut length = a.length().clone();
+                for (param_name, param_assignment) in to_assign {
+                    length = length.try_assign(&param_name, param_assignment.value_take())?;
+                }
+                Ok(db.intern_type(LogicalType::try_new_generic_array(element, length)?))
+            }
         }
     } else {
         Ok(key)
@@ -338,14 +378,16 @@ fn stream_for_param_assignments(
     param_assignments: InsertionOrderedMap<Name, GenericParameterAssignment>,
 ) -> Result<Id<Stream>> {
     let stream_params = db.stream_parameter_kinds(key)?;
-    let mut to_assign = vec![];
+    let mut to_assign = InsertionOrderedMap::new();
     for (param_name, param_assignment) in param_assignments {
         if stream_params.contains(&param_name) {
-            to_assign.push((param_name, param_assignment));
+            to_assign.try_insert(param_name, param_assignment)?;
         }
     }
     if to_assign.len() > 0 {
         let mut stream = db.lookup_intern_stream(key);
+        stream.set_data(db.type_for_param_assignments(stream.data_id(), to_assign.clone())?);
+        stream.set_user(db.type_for_param_assignments(stream.user_id(), to_assign.clone())?);
         for (param_name, param_assignment) in to_assign {
             stream.try_assign(&param_name, param_assignment)?;
         }
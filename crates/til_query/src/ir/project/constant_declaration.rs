@@ -0,0 +1,117 @@
+use tydi_common::{
+    error::{Error, Result, TryResult},
+    name::{PathName, PathNameSelf},
+    traits::{Document, Documents, Identify},
+};
+
+use crate::ir::generics::{
+    behavioral::integer::{IntegerGeneric, IntegerGenericKind},
+    condition::TestValue,
+    param_value::GenericParamValue,
+};
+
+/// A named, fixed integer value declared within a namespace.
+///
+/// Constants can be used anywhere a generic parameter value is accepted, where
+/// they are substituted with their value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConstantDeclaration {
+    name: PathName,
+    kind: IntegerGeneric,
+    value: i32,
+    doc: Option<String>,
+}
+
+impl ConstantDeclaration {
+    pub fn try_new(
+        name: impl TryResult<PathName>,
+        kind: IntegerGeneric,
+        value: impl TryResult<GenericParamValue>,
+    ) -> Result<Self> {
+        let name = name.try_result()?;
+        let value = value.try_result()?.reduce().remove_outer_parens();
+        match value {
+            GenericParamValue::Integer(integer) if kind.valid_value(integer)? => {
+                Ok(ConstantDeclaration {
+                    name,
+                    kind,
+                    value: integer,
+                    doc: None,
+                })
+            }
+            GenericParamValue::Integer(integer) => Err(Error::InvalidArgument(format!(
+                "Value of constant {} ({}) is not valid for condition: {}",
+                name,
+                integer,
+                kind.describe_condition()
+            ))),
+            _ => Err(Error::InvalidArgument(format!(
+                "Value of constant {} ({}) is not fixed.",
+                name, value
+            ))),
+        }
+    }
+
+    pub fn kind(&self) -> &IntegerGenericKind {
+        self.kind.kind()
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+}
+
+impl From<&ConstantDeclaration> for GenericParamValue {
+    fn from(constant: &ConstantDeclaration) -> Self {
+        GenericParamValue::Integer(constant.value())
+    }
+}
+
+impl Document for ConstantDeclaration {
+    fn doc(&self) -> Option<&String> {
+        self.doc.as_ref()
+    }
+}
+
+impl Documents for ConstantDeclaration {
+    fn set_doc(&mut self, doc: impl Into<String>) {
+        self.doc = Some(doc.into());
+    }
+}
+
+impl Identify for ConstantDeclaration {
+    fn identifier(&self) -> String {
+        self.path_name().to_string()
+    }
+}
+
+impl PathNameSelf for ConstantDeclaration {
+    fn path_name(&self) -> &PathName {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ir::generics::param_value::combination::{MathCombination, MathOperator};
+
+    use super::*;
+
+    #[test]
+    fn try_new() -> Result<()> {
+        let width = ConstantDeclaration::try_new(
+            "a::width",
+            IntegerGeneric::natural(),
+            MathCombination::Combination(
+                Box::new(GenericParamValue::Integer(2)),
+                MathOperator::Multiply,
+                Box::new(GenericParamValue::Integer(4)),
+            ),
+        )?;
+        assert_eq!(width.value(), 8);
+        assert_eq!(width.identifier(), "a__width");
+        assert!(ConstantDeclaration::try_new("a::width", IntegerGeneric::positive(), 0).is_err());
+        assert!(ConstantDeclaration::try_new("a::width", IntegerGeneric::integer(), -3).is_ok());
+        Ok(())
+    }
+}
@@ -8,7 +8,7 @@ use tydi_common::{
     error::{Error, Result, TryResult},
     map::{InsertionOrderedMap, InsertionOrderedSet},
     name::{Name, NameSelf},
-    traits::Identify,
+    traits::{Document, Documents, Identify},
 };
 use tydi_intern::Id;
 
@@ -27,6 +27,7 @@ pub struct Interface {
     domains: Option<InsertionOrderedSet<Domain>>,
     parameters: InsertionOrderedMap<Name, GenericParameter>,
     ports: InsertionOrderedMap<Name, InterfacePort>,
+    doc: Option<String>,
 }
 
 impl Interface {
@@ -35,6 +36,7 @@ impl Interface {
             domains: None,
             parameters: InsertionOrderedMap::new(),
             ports: InsertionOrderedMap::new(),
+            doc: None,
         }
     }
 
@@ -80,6 +82,7 @@ impl Interface {
             domains: domain_set,
             parameters: InsertionOrderedMap::new(),
             ports: InsertionOrderedMap::new(),
+            doc: None,
         })
     }
 
@@ -100,7 +103,7 @@ impl Interface {
         for (expected_param_name, expected_param_kind) in expected_params.iter() {
             if let Some(param) = self.parameters().get(expected_param_name) {
                 if let Err(err) = param.kind().satisfies(expected_param_kind) {
-                    return Err(Error::InvalidArgument(format!("Parameter {} does not satisfy the parameter expected by a type on port {}: {}", expected_param_name, port.name(), err)))
+                    return Err(Error::InvalidArgument(format!("Parameter {} does not satisfy the parameter expected by a type on port {}: {}", expected_param_name, port.name(), err)));
                 }
             } else {
                 return Err(Error::InterfaceError(format!("A type on port {} expects a parameter with name {}, but none exists on this interface.", port.name(), expected_param_name)));
@@ -232,6 +235,18 @@ impl Interface {
     }
 }
 
+impl Document for Interface {
+    fn doc(&self) -> Option<&String> {
+        self.doc.as_ref()
+    }
+}
+
+impl Documents for Interface {
+    fn set_doc(&mut self, doc: impl Into<String>) {
+        self.doc = Some(doc.into());
+    }
+}
+
 impl MoveDb<Id<Arc<Interface>>> for Arc<Interface> {
     fn move_db(
         &self,
@@ -14,10 +14,12 @@ use tydi_intern::Id;
 use self::namespace::Namespace;
 
 use super::{
+    lint::{Lint, LintLevel},
     traits::{InternSelf, MoveDb},
     Ir,
 };
 
+pub mod constant_declaration;
 pub mod interface;
 pub mod namespace;
 pub mod type_declaration;
@@ -35,6 +37,8 @@ pub struct Project {
     namespaces: InsertionOrderedMap<PathName, Id<Namespace>>,
     /// External dependencies
     imports: BTreeMap<Name, Project>,
+    /// The levels of lints which differ from their default level
+    lints: BTreeMap<Lint, LintLevel>,
 }
 
 impl Project {
@@ -43,27 +47,36 @@ impl Project {
         location: impl TryResult<PathBuf>,
         output_path: Option<impl TryResult<PathBuf>>,
     ) -> Result<Self> {
-        let location = location.try_result()?;
-        let output_path = match output_path {
+        let mut project = Project {
+            name: name.try_result()?,
+            location: location.try_result()?,
+            output_path: None,
+            namespaces: InsertionOrderedMap::new(),
+            imports: BTreeMap::new(),
+            lints: BTreeMap::new(),
+        };
+        project.set_output_path(output_path)?;
+        Ok(project)
+    }
+
+    /// Set (or clear) the output directory of the project.
+    ///
+    /// Relative paths are interpreted relative to the project's location.
+    pub fn set_output_path(&mut self, output_path: Option<impl TryResult<PathBuf>>) -> Result<()> {
+        self.output_path = match output_path {
             Some(some) => {
                 let output_path_result = some.try_result()?;
                 if output_path_result.is_absolute() {
                     Some(output_path_result)
                 } else {
-                    let mut output_path_out = location.clone();
+                    let mut output_path_out = self.location.clone();
                     output_path_out.push(output_path_result);
                     Some(output_path_out)
                 }
             }
             None => None,
         };
-        Ok(Project {
-            name: name.try_result()?,
-            location,
-            output_path,
-            namespaces: InsertionOrderedMap::new(),
-            imports: BTreeMap::new(),
-        })
+        Ok(())
     }
 
     pub fn location(&self) -> &Path {
@@ -82,53 +95,90 @@ impl Project {
         &self.imports
     }
 
+    /// The level a lint is applied at when linting the project.
+    pub fn lint_level(&self, lint: Lint) -> LintLevel {
+        self.lints.get(&lint).copied().unwrap_or_default()
+    }
+
+    pub fn set_lint_level(&mut self, lint: Lint, level: LintLevel) {
+        self.lints.insert(lint, level);
+    }
+
+    /// Look up a namespace of an imported project, by the name of the import
+    /// followed by the name of the namespace (e.g. `common::types`).
+    pub fn imported_namespace(&self, path: &PathName) -> Option<Id<Namespace>> {
+        let (import_name, namespace) = path.as_ref().split_first()?;
+        self.imports()
+            .get(import_name)?
+            .namespaces()
+            .get(&PathName::new(namespace.iter().cloned()))
+            .copied()
+    }
+
+    /// Whether `other` is the same project, i.e. has the same name and location.
+    fn is_same_project(&self, other: &Project) -> bool {
+        let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.name == other.name && canonical(&self.location) == canonical(&other.location)
+    }
+
     fn import_project_recursive(
         &mut self,
         db: &dyn Ir,
         project: &Project,
         proj_db: &dyn Ir,
         alias_name: Name,
-        is_root: bool,
-    ) -> Result<()> {
+    ) -> Result<Project> {
+        // The imports of the imported project are imported as well, but also
+        // kept on the imported project, to track which projects depend on which.
+        let mut imports = BTreeMap::new();
         for (import_name, import_project) in project.imports() {
-            if let Err(err) = self.import_project_recursive(
-                db,
-                import_project,
-                proj_db,
-                import_name.clone(),
-                false,
-            ) {
-                return Err(Error::ProjectError(format!(
-                    "Unable to import project {}, due to a problem importing its dependency {}: {}",
-                    project.name(),
-                    import_name,
-                    err
-                )));
+            match self.import_project_recursive(db, import_project, proj_db, import_name.clone()) {
+                Ok(imported) => {
+                    imports.insert(import_name.clone(), imported);
+                }
+                Err(err) => {
+                    return Err(Error::ProjectError(format!(
+                        "Unable to import project {}, due to a problem importing its dependency {}: {}",
+                        project.name(),
+                        import_name,
+                        err
+                    )));
+                }
             }
         }
 
-        let prefix = if is_root {
-            Some(self.name().clone())
-        } else {
-            None
-        };
-
+        // Declarations keep their names, so they are identified the same way
+        // as within the imported project itself.
         let namespaces = project
             .namespaces()
             .clone()
-            .try_map_convert(|v| v.move_db(proj_db, db, &prefix))?;
-        self.imports.insert(
-            alias_name,
-            Project {
-                name: project.name.clone(),
-                location: project.location.clone(),
-                output_path: project.output_path.clone(),
-                namespaces,
-                imports: BTreeMap::new(),
-            },
-        );
+            .try_map_convert(|v| v.move_db(proj_db, db, &None))?;
+        let imported = Project {
+            name: project.name.clone(),
+            location: project.location.clone(),
+            output_path: project.output_path.clone(),
+            namespaces,
+            imports,
+            lints: project.lints.clone(),
+        };
+        // Dependencies of dependencies share the imports of this project, so
+        // every alias must refer to a single project.
+        if let Some(existing) = self.imports.get(&alias_name) {
+            if !existing.is_same_project(&imported) {
+                return Err(Error::ProjectError(format!(
+                    "Unable to import project {} ({}) as {}, as {} already refers to project {} ({})",
+                    imported.name(),
+                    imported.location().display(),
+                    alias_name,
+                    alias_name,
+                    existing.name(),
+                    existing.location().display()
+                )));
+            }
+        }
+        self.imports.insert(alias_name, imported.clone());
 
-        Ok(())
+        Ok(imported)
     }
 
     /// Import another project using an alias
@@ -140,7 +190,12 @@ impl Project {
         alias_name: impl TryResult<Name>,
     ) -> Result<()> {
         let alias_name = alias_name.try_result()?;
-        if self.imports().contains_key(&alias_name) {
+        // The project may already have been imported as a dependency of another project
+        if self
+            .imports()
+            .get(&alias_name)
+            .is_some_and(|existing| !existing.is_same_project(project))
+        {
             Err(Error::InvalidArgument(format!(
                 "Project already has an import with name {}",
                 &alias_name
@@ -156,7 +211,8 @@ impl Project {
                 &alias_name
             )))
         } else {
-            self.import_project_recursive(db, project, proj_db, alias_name, true)
+            self.import_project_recursive(db, project, proj_db, alias_name)?;
+            Ok(())
         }
     }
 
@@ -1,4 +1,7 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
 
 use tydi_common::{
     error::{Error, Result, TryOptional, TryResult},
@@ -10,7 +13,9 @@ use tydi_intern::Id;
 use crate::{
     common::logical::logicaltype::{stream::Stream, LogicalType},
     ir::{
-        generics::{param_value::GenericParamValue, GenericParameter},
+        generics::{
+            behavioral::integer::IntegerGeneric, param_value::GenericParamValue, GenericParameter,
+        },
         implementation::Implementation,
         streamlet::Streamlet,
         traits::{GetSelf, InternArc, InternSelf, MoveDb, TryIntern},
@@ -18,7 +23,10 @@ use crate::{
     },
 };
 
-use super::{interface::Interface, type_declaration::TypeDeclaration};
+use super::{
+    constant_declaration::ConstantDeclaration, interface::Interface,
+    type_declaration::TypeDeclaration,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Namespace {
@@ -36,6 +44,11 @@ pub struct Namespace {
     /// they are also declared as interfaces.
     /// This means that streamlet and implementation names cannot overlap.
     interfaces: BTreeMap<Name, Id<Arc<Interface>>>,
+    /// The constants declared within the namespace.
+    constants: BTreeMap<Name, ConstantDeclaration>,
+    /// The full names of the declarations which the declarations of the
+    /// namespace refer to by name, e.g., `b` in `type a = b;`.
+    references: BTreeSet<PathName>,
 }
 
 impl Namespace {
@@ -46,6 +59,8 @@ impl Namespace {
             streamlets: BTreeMap::new(),
             implementations: BTreeMap::new(),
             interfaces: BTreeMap::new(),
+            constants: BTreeMap::new(),
+            references: BTreeSet::new(),
         })
     }
 
@@ -93,6 +108,21 @@ impl Namespace {
             .collect()
     }
 
+    pub fn constants(&self) -> &BTreeMap<Name, ConstantDeclaration> {
+        &self.constants
+    }
+
+    pub fn references(&self) -> &BTreeSet<PathName> {
+        &self.references
+    }
+
+    /// Record that a declaration of the namespace refers to the declaration
+    /// with the full name `path`.
+    pub fn add_reference(&mut self, path: impl TryResult<PathName>) -> Result<()> {
+        self.references.insert(path.try_result()?);
+        Ok(())
+    }
+
     pub fn import_type(
         &mut self,
         name: impl TryResult<Name>,
@@ -157,6 +187,22 @@ impl Namespace {
         }
     }
 
+    pub fn import_constant(
+        &mut self,
+        name: impl TryResult<Name>,
+        constant: ConstantDeclaration,
+    ) -> Result<()> {
+        let name = name.try_result()?;
+        match self.constants.insert(name.clone(), constant) {
+            None => Ok(()),
+            Some(_) => Err(Error::InvalidArgument(format!(
+                "A constant with name {} already exists in namespace {}.",
+                name,
+                self.path_name()
+            ))),
+        }
+    }
+
     pub fn define_type_no_params(
         &mut self,
         db: &dyn Ir,
@@ -285,6 +331,29 @@ impl Namespace {
         Ok(self.get_type_id(db, name, parameter_assignments)?.get(db))
     }
 
+    pub fn define_constant(
+        &mut self,
+        name: impl TryResult<Name>,
+        kind: IntegerGeneric,
+        value: impl TryResult<GenericParamValue>,
+    ) -> Result<()> {
+        let name = name.try_result()?;
+        let constant =
+            ConstantDeclaration::try_new(self.path_name().with_child(&name), kind, value)?;
+        self.import_constant(name, constant)
+    }
+
+    pub fn get_constant(&self, name: impl TryResult<Name>) -> Result<&ConstantDeclaration> {
+        let name = name.try_result()?;
+        self.constants()
+            .get(&name)
+            .ok_or(Error::InvalidArgument(format!(
+                "A constant with name {} does not exist in namespace {}",
+                name,
+                self.path_name()
+            )))
+    }
+
     pub fn get_streamlet_id(&self, name: impl TryResult<Name>) -> Result<Id<Arc<Streamlet>>> {
         let name = name.try_result()?;
         self.streamlet_ids()
@@ -426,6 +495,12 @@ impl MoveDb<Id<Namespace>> for Namespace {
             streamlets,
             implementations,
             interfaces,
+            constants: self.constants.clone(),
+            references: self
+                .references
+                .iter()
+                .map(|path| path.with_parents(prefix))
+                .collect(),
         }
         .intern(target_db))
     }
@@ -451,4 +526,18 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn constants() -> Result<()> {
+        let mut namespace = Namespace::new("namespace")?;
+        namespace.define_constant("width", IntegerGeneric::natural(), 8)?;
+        assert_eq!(namespace.get_constant("width")?.value(), 8);
+        assert!(namespace
+            .define_constant("width", IntegerGeneric::natural(), 4)
+            .is_err());
+        assert!(namespace
+            .define_constant("depth", IntegerGeneric::natural(), -1)
+            .is_err());
+        Ok(())
+    }
 }
@@ -2,7 +2,7 @@ use tydi_common::{
     error::{Error, Result, TryOptional, TryResult},
     map::InsertionOrderedMap,
     name::{Name, NameSelf, PathName, PathNameSelf},
-    traits::Identify,
+    traits::{Document, Documents, Identify},
 };
 use tydi_intern::Id;
 
@@ -21,6 +21,12 @@ pub struct TypeDeclaration {
     name: PathName,
     typ: Id<LogicalType>,
     parameter_assignments: Option<InsertionOrderedMap<Name, GenericParameterAssignment>>,
+    doc: Option<String>,
+    /// Documentation of the fields of the declared type, by their path.
+    ///
+    /// Kept on the declaration rather than on the (interned) type itself, so
+    /// documentation does not affect whether types are compatible.
+    field_docs: InsertionOrderedMap<PathName, String>,
 }
 
 impl TypeDeclaration {
@@ -51,12 +57,16 @@ impl TypeDeclaration {
                 name: name.try_result()?,
                 typ,
                 parameter_assignments: Some(parameter_assignments),
+                doc: None,
+                field_docs: InsertionOrderedMap::new(),
             }
         } else {
             Self {
                 name: name.try_result()?,
                 typ,
                 parameter_assignments: None,
+                doc: None,
+                field_docs: InsertionOrderedMap::new(),
             }
         };
         result.verify_parameters(db)?;
@@ -77,6 +87,11 @@ impl TypeDeclaration {
         }
     }
 
+    /// The type as it was declared, before its parameters are assigned.
+    pub fn declared_type_id(&self) -> Id<LogicalType> {
+        self.typ
+    }
+
     pub fn with_assignments(
         self,
         parameter_assignments: impl IntoIterator<
@@ -136,6 +151,8 @@ impl TypeDeclaration {
                 name: typ_name,
                 typ: typ_id,
                 parameter_assignments: Some(new_parameter_assignments),
+                doc: self.doc,
+                field_docs: self.field_docs,
             })
         } else {
             Err(Error::InvalidArgument(format!(
@@ -145,15 +162,62 @@ impl TypeDeclaration {
         }
     }
 
+    /// The parameter a value is assigned to, either by its name or by its
+    /// position in the parameter list (for ordered assignments).
+    pub fn assigned_parameter(
+        &self,
+        position: usize,
+        name: Option<&Name>,
+    ) -> Result<&GenericParameter> {
+        let parameter_assignments = self.parameter_assignments().as_ref().ok_or_else(|| {
+            Error::InvalidArgument(format!("No parameters on type {}", self.path_name()))
+        })?;
+        let assignment = match name {
+            Some(name) => parameter_assignments.get(name).ok_or_else(|| {
+                Error::InvalidArgument(format!(
+                    "No parameter with name {} on type {}",
+                    name,
+                    self.path_name()
+                ))
+            })?,
+            None => parameter_assignments
+                .values()
+                .nth(position)
+                .ok_or_else(|| {
+                    Error::InvalidArgument(
+                        "More parameter assignments than there are parameters.".to_string(),
+                    )
+                })?,
+        };
+        Ok(assignment.parameter())
+    }
+
     pub fn with_name(self, name: impl TryResult<PathName>) -> Result<Self> {
         let name = name.try_result()?;
         Ok(Self {
             name,
             typ: self.typ,
             parameter_assignments: self.parameter_assignments,
+            doc: self.doc,
+            field_docs: self.field_docs,
         })
     }
 
+    /// The documentation of the fields of the declared type, including those
+    /// of nested Groups and Unions, by their path (e.g. `a::b` for field `b`
+    /// of field `a`).
+    pub fn field_docs(&self) -> &InsertionOrderedMap<PathName, String> {
+        &self.field_docs
+    }
+
+    pub fn field_doc(&self, field: &PathName) -> Option<&String> {
+        self.field_docs.get(field)
+    }
+
+    pub fn set_field_doc(&mut self, field: PathName, doc: impl Into<String>) -> Result<()> {
+        self.field_docs.try_insert(field, doc.into())
+    }
+
     fn verify_parameters(&self, db: &dyn Ir) -> Result<()> {
         let expected_params = db.logical_type_parameter_kinds(self.typ)?;
         let expects_params = expected_params.len() > 0;
@@ -203,6 +267,18 @@ impl TypeDeclaration {
     }
 }
 
+impl Document for TypeDeclaration {
+    fn doc(&self) -> Option<&String> {
+        self.doc.as_ref()
+    }
+}
+
+impl Documents for TypeDeclaration {
+    fn set_doc(&mut self, doc: impl Into<String>) {
+        self.doc = Some(doc.into());
+    }
+}
+
 impl Identify for TypeDeclaration {
     fn identifier(&self) -> String {
         self.path_name().to_string()
@@ -226,6 +302,8 @@ impl MoveDb<TypeDeclaration> for TypeDeclaration {
             name: self.path_name().with_parents(prefix),
             typ: self.typ.move_db(original_db, target_db, prefix)?,
             parameter_assignments: self.parameter_assignments().clone(),
+            doc: self.doc.clone(),
+            field_docs: self.field_docs.clone(),
         })
     }
 }
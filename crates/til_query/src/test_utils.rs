@@ -57,7 +57,7 @@ pub fn test_stream_id_custom(
     Stream::try_new(
         db,
         data_type,
-        throughput,
+        throughput.try_result()?,
         dimensionality,
         Synchronicity::Sync,
         complexity,
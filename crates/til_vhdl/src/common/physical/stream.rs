@@ -122,26 +122,15 @@ pub fn physical_stream_to_vhdl(
             .map(|valid| valid.clone().with_typ(ObjectType::Bit)),
     )?;
 
-    let user_bit_count = if let Some(u) = physical_stream.user_bit_count() {
-        if let Some(f) = u.try_eval() {
-            f.get()
-        } else {
-            // TODO: NEED TO IMPLEMENT ARRAYS WITH RANGE BASED ON RELATIONS
-            todo!()
-        }
-    } else {
-        0
+    // Sizes which depend on generic parameters are unknown
+    let user_bit_count = match physical_stream.user_bit_count() {
+        Some(u) => u.try_eval().map(|f| f.get()),
+        None => Some(0),
     };
 
-    let data_element_bit_count = if let Some(d) = physical_stream.data_element_bit_count() {
-        if let Some(f) = d.try_eval() {
-            f.get()
-        } else {
-            // TODO: NEED TO IMPLEMENT ARRAYS WITH RANGE BASED ON RELATIONS
-            todo!()
-        }
-    } else {
-        0
+    let data_element_bit_count = match physical_stream.data_element_bit_count() {
+        Some(d) => d.try_eval().map(|f| f.get()),
+        None => Some(0),
     };
 
     let dimensionality =
@@ -168,10 +157,12 @@ pub struct VhdlPhysicalStream {
     dimensionality: Relation,
     /// Complexity.
     complexity: Complexity,
-    /// The absolute size of a data element
-    data_element_size: NonNegative,
-    /// The absolute size of the user data
-    user_size: NonNegative,
+    /// The absolute size of a data element, None if it depends on generic
+    /// parameters
+    data_element_size: Option<NonNegative>,
+    /// The absolute size of the user data, None if it depends on generic
+    /// parameters
+    user_size: Option<NonNegative>,
     /// Direction of the parent interface.
     interface_direction: InterfaceDirection,
     /// The (logical) Stream's direction.
@@ -187,8 +178,8 @@ impl VhdlPhysicalStream {
         element_lanes: Positive,
         dimensionality: impl Into<Relation>,
         complexity: Complexity,
-        data_element_size: NonNegative,
-        user_size: NonNegative,
+        data_element_size: Option<NonNegative>,
+        user_size: Option<NonNegative>,
         interface_direction: InterfaceDirection,
         stream_direction: StreamDirection,
     ) -> Self {
@@ -248,13 +239,15 @@ impl VhdlPhysicalStream {
         self.stream_direction
     }
 
-    /// The absolute size of the user data
-    pub fn user_size(&self) -> NonNegative {
+    /// The absolute size of the user data, None if it depends on generic
+    /// parameters
+    pub fn user_size(&self) -> Option<NonNegative> {
         self.user_size
     }
 
-    /// The absolute size of a data element
-    pub fn data_element_size(&self) -> NonNegative {
+    /// The absolute size of a data element, None if it depends on generic
+    /// parameters
+    pub fn data_element_size(&self) -> Option<NonNegative> {
         self.data_element_size
     }
 }
@@ -275,7 +268,7 @@ mod tests {
     use tydi_common::{
         map::InsertionOrderedMap,
         name::{Name, PathName},
-        numbers::{BitCount, Positive},
+        numbers::Positive,
     };
     use tydi_vhdl::declaration::Declare;
     use tydi_vhdl::object::object_type::ObjectType;
@@ -288,8 +281,8 @@ mod tests {
         let arch_db = &mut _arch_db;
         let physical_stream = PhysicalStream::new(
             InsertionOrderedMap::try_new(vec![
-                ("a".try_into()?, BitCount::new(3).unwrap()),
-                ("b".try_into()?, BitCount::new(2).unwrap()),
+                ("a".try_into()?, PhysicalBitCount::fixed(3).unwrap()),
+                ("b".try_into()?, PhysicalBitCount::fixed(2).unwrap()),
             ])?,
             Positive::new(2).unwrap(),
             3,
@@ -367,8 +360,8 @@ a_strb : out std_logic_vector(1 downto 0)"#,
             Positive::new(1).unwrap(),
             0,
             Complexity::new_major(1),
-            1,
-            0,
+            Some(1),
+            Some(0),
             InterfaceDirection::Out,
             StreamDirection::Forward,
         );
@@ -448,8 +441,8 @@ a_strb : out std_logic_vector(1 downto 0)"#,
             Positive::new(1).unwrap(),
             0,
             Complexity::new_major(1),
-            1,
-            0,
+            Some(1),
+            Some(0),
             InterfaceDirection::In,
             StreamDirection::Forward,
         );
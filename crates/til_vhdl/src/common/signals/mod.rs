@@ -244,31 +244,26 @@ impl<'a> PhysicalStreamProcessWithDb<'a> {
     }
 
     fn default_data(&self) -> Result<ValueAssignment> {
-        if self.stream_object().data_element_size() == 0 {
-            Err(Error::InvalidArgument(format!(
+        match self.stream_object().data_element_size() {
+            Some(0) => Err(Error::InvalidArgument(format!(
                 "Cannot produce a default data signal assignment for {}, as it has no data signal.",
                 self.process.path_name()
-            )))
-        } else if self.stream_object().data_element_size()
-            * self.stream_object().element_lanes().get()
-            == 1
-        {
-            Ok(StdLogicValue::Logic(false).into())
-        } else {
-            Ok(BitVecValue::Others(StdLogicValue::Logic(false)).into())
+            ))),
+            Some(size) if size * self.stream_object().element_lanes().get() == 1 => {
+                Ok(StdLogicValue::Logic(false).into())
+            }
+            _ => Ok(BitVecValue::Others(StdLogicValue::Logic(false)).into()),
         }
     }
 
     fn default_user(&self) -> Result<ValueAssignment> {
-        if self.stream_object().user_size() == 0 {
-            Err(Error::InvalidArgument(format!(
+        match self.stream_object().user_size() {
+            Some(0) => Err(Error::InvalidArgument(format!(
                 "Cannot produce a default user signal assignment for {}, as it has no user signal.",
                 self.process.path_name()
-            )))
-        } else if self.stream_object().user_size() == 1 {
-            Ok(StdLogicValue::Logic(false).into())
-        } else {
-            Ok(BitVecValue::Others(StdLogicValue::Logic(false)).into())
+            ))),
+            Some(1) => Ok(StdLogicValue::Logic(false).into()),
+            _ => Ok(BitVecValue::Others(StdLogicValue::Logic(false)).into()),
         }
     }
 }
@@ -0,0 +1,352 @@
+use std::{fmt, str::FromStr};
+
+use tydi_common::{
+    error::{Error, Result, TryResult},
+    name::PathName,
+};
+use tydi_vhdl::common::vhdl_name::VhdlName;
+
+/// What the backend does when the file of a linked implementation does not exist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MissingLinkBehaviour {
+    /// Create an empty architecture template in the linked directory.
+    #[default]
+    Create,
+    /// Fail the build.
+    Error,
+    /// Log a warning, and do not emit an architecture for the streamlet.
+    WarnAndSkip,
+    /// Emit the empty architecture template to the output directory only,
+    /// leaving the linked directory untouched.
+    CreateInOutputDir,
+}
+
+impl MissingLinkBehaviour {
+    pub const VARIANTS: [&'static str; 4] =
+        ["create", "error", "warn-and-skip", "create-in-output-dir"];
+}
+
+impl FromStr for MissingLinkBehaviour {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "create" => Ok(MissingLinkBehaviour::Create),
+            "error" => Ok(MissingLinkBehaviour::Error),
+            "warn-and-skip" => Ok(MissingLinkBehaviour::WarnAndSkip),
+            "create-in-output-dir" => Ok(MissingLinkBehaviour::CreateInOutputDir),
+            _ => Err(invalid_variant(
+                "behaviour for missing linked implementations",
+                s,
+                &Self::VARIANTS,
+            )),
+        }
+    }
+}
+
+impl fmt::Display for MissingLinkBehaviour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MissingLinkBehaviour::Create => "create",
+            MissingLinkBehaviour::Error => "error",
+            MissingLinkBehaviour::WarnAndSkip => "warn-and-skip",
+            MissingLinkBehaviour::CreateInOutputDir => "create-in-output-dir",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Whether to indent the generated VHDL with spaces or tabs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum IndentStyle {
+    #[default]
+    Spaces,
+    Tabs,
+}
+
+impl IndentStyle {
+    pub const VARIANTS: [&'static str; 2] = ["spaces", "tabs"];
+}
+
+impl FromStr for IndentStyle {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "spaces" => Ok(IndentStyle::Spaces),
+            "tabs" => Ok(IndentStyle::Tabs),
+            _ => Err(invalid_variant("indent style", s, &Self::VARIANTS)),
+        }
+    }
+}
+
+impl fmt::Display for IndentStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            IndentStyle::Spaces => "spaces",
+            IndentStyle::Tabs => "tabs",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The VHDL standard the generated code targets.
+///
+/// The generated code is currently valid under both standards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum VhdlStandard {
+    Vhdl1993,
+    #[default]
+    Vhdl2008,
+}
+
+impl VhdlStandard {
+    pub const VARIANTS: [&'static str; 2] = ["1993", "2008"];
+}
+
+impl FromStr for VhdlStandard {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "1993" | "93" => Ok(VhdlStandard::Vhdl1993),
+            "2008" | "08" => Ok(VhdlStandard::Vhdl2008),
+            _ => Err(invalid_variant("VHDL standard", s, &Self::VARIANTS)),
+        }
+    }
+}
+
+impl fmt::Display for VhdlStandard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            VhdlStandard::Vhdl1993 => "1993",
+            VhdlStandard::Vhdl2008 => "2008",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How the files containing the architectures of streamlets are named.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FileNaming {
+    /// All files in the output directory, named after the streamlet's
+    /// identifier, e.g. `my_0_space_0_comp.vhd`.
+    #[default]
+    Flat,
+    /// A subdirectory per namespace, with files named after the streamlet,
+    /// e.g. `my/space/comp.vhd`.
+    Nested,
+}
+
+impl FileNaming {
+    pub const VARIANTS: [&'static str; 2] = ["flat", "nested"];
+
+    /// The file name (relative to the output directory) of the architecture
+    /// of the streamlet with the given full name.
+    pub fn file_name(&self, streamlet: &PathName) -> String {
+        match self {
+            FileNaming::Flat => format!("{}.vhd", streamlet.join("_0_")),
+            FileNaming::Nested => format!("{}.vhd", streamlet.join("/")),
+        }
+    }
+}
+
+impl FromStr for FileNaming {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "flat" => Ok(FileNaming::Flat),
+            "nested" => Ok(FileNaming::Nested),
+            _ => Err(invalid_variant("file naming scheme", s, &Self::VARIANTS)),
+        }
+    }
+}
+
+impl fmt::Display for FileNaming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FileNaming::Flat => "flat",
+            FileNaming::Nested => "nested",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+fn invalid_variant(what: &str, value: &str, variants: &[&str]) -> Error {
+    Error::InvalidArgument(format!(
+        "\"{}\" is not a valid {}, expected one of: {}",
+        value,
+        what,
+        variants.join(", ")
+    ))
+}
+
+/// Configuration of the VHDL backend.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VhdlConfig {
+    missing_link: MissingLinkBehaviour,
+    indent: String,
+    standard: VhdlStandard,
+    /// The library the generated package is compiled into.
+    library: VhdlName,
+    file_naming: FileNaming,
+    /// The namespaces whose streamlets to emit architectures for, all
+    /// namespaces if empty.
+    include: Vec<PathName>,
+    /// The namespaces whose streamlets not to emit architectures for.
+    exclude: Vec<PathName>,
+    /// Whether to declare the constants of all namespaces in the package.
+    export_constants: bool,
+}
+
+impl Default for VhdlConfig {
+    fn default() -> Self {
+        VhdlConfig {
+            missing_link: MissingLinkBehaviour::default(),
+            indent: "  ".to_string(),
+            standard: VhdlStandard::default(),
+            library: VhdlName::try_new("work").unwrap(),
+            file_naming: FileNaming::default(),
+            include: vec![],
+            exclude: vec![],
+            export_constants: false,
+        }
+    }
+}
+
+impl VhdlConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn missing_link(&self) -> MissingLinkBehaviour {
+        self.missing_link
+    }
+
+    pub fn with_missing_link(mut self, missing_link: MissingLinkBehaviour) -> Self {
+        self.missing_link = missing_link;
+        self
+    }
+
+    /// The string used for a single level of indentation.
+    pub fn indent(&self) -> &str {
+        self.indent.as_str()
+    }
+
+    /// Indent with `width` spaces or tabs per level.
+    pub fn with_indent(mut self, style: IndentStyle, width: usize) -> Self {
+        self.indent = match style {
+            IndentStyle::Spaces => " ",
+            IndentStyle::Tabs => "\t",
+        }
+        .repeat(width);
+        self
+    }
+
+    pub fn standard(&self) -> VhdlStandard {
+        self.standard
+    }
+
+    pub fn with_standard(mut self, standard: VhdlStandard) -> Self {
+        self.standard = standard;
+        self
+    }
+
+    pub fn library(&self) -> &VhdlName {
+        &self.library
+    }
+
+    pub fn with_library(mut self, library: impl TryResult<VhdlName>) -> Result<Self> {
+        self.library = library.try_result()?;
+        Ok(self)
+    }
+
+    pub fn file_naming(&self) -> FileNaming {
+        self.file_naming
+    }
+
+    pub fn with_file_naming(mut self, file_naming: FileNaming) -> Self {
+        self.file_naming = file_naming;
+        self
+    }
+
+    /// Only emit the architectures of streamlets in these namespaces (or
+    /// namespaces nested within them).
+    pub fn with_include(mut self, namespaces: impl IntoIterator<Item = PathName>) -> Self {
+        self.include = namespaces.into_iter().collect();
+        self
+    }
+
+    /// Do not emit the architectures of streamlets in these namespaces (or
+    /// namespaces nested within them).
+    pub fn with_exclude(mut self, namespaces: impl IntoIterator<Item = PathName>) -> Self {
+        self.exclude = namespaces.into_iter().collect();
+        self
+    }
+
+    pub fn export_constants(&self) -> bool {
+        self.export_constants
+    }
+
+    /// Declare the constants of all namespaces in the generated package.
+    pub fn with_export_constants(mut self, export_constants: bool) -> Self {
+        self.export_constants = export_constants;
+        self
+    }
+
+    /// Whether to emit the architecture of the streamlet with the given full name.
+    ///
+    /// Exclusion takes precedence over inclusion.
+    pub fn emits(&self, streamlet: &PathName) -> bool {
+        let namespace = streamlet.root();
+        (self.include.is_empty() || self.include.iter().any(|ns| namespace.starts_with(ns)))
+            && !self.exclude.iter().any(|ns| namespace.starts_with(ns))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_link_from_str() -> Result<()> {
+        for variant in MissingLinkBehaviour::VARIANTS {
+            assert_eq!(
+                variant.parse::<MissingLinkBehaviour>()?.to_string(),
+                variant
+            );
+        }
+        assert!("ask".parse::<MissingLinkBehaviour>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn indent_and_naming() -> Result<()> {
+        let config = VhdlConfig::new();
+        assert_eq!(config.indent(), "  ");
+        assert_eq!(config.with_indent(IndentStyle::Tabs, 1).indent(), "\t");
+        assert_eq!("tabs".parse::<IndentStyle>()?, IndentStyle::Tabs);
+        assert!("tab".parse::<IndentStyle>().is_err());
+        assert_eq!("93".parse::<VhdlStandard>()?, VhdlStandard::Vhdl1993);
+        assert!("2019".parse::<VhdlStandard>().is_err());
+
+        let name = PathName::try_from("my::space::comp")?;
+        assert_eq!(FileNaming::Flat.file_name(&name), "my_0_space_0_comp.vhd");
+        assert_eq!(FileNaming::Nested.file_name(&name), "my/space/comp.vhd");
+        Ok(())
+    }
+
+    #[test]
+    fn include_exclude() -> Result<()> {
+        let config = VhdlConfig::new()
+            .with_include([PathName::try_from("my")?])
+            .with_exclude([PathName::try_from("my::test")?]);
+        assert!(config.emits(&PathName::try_from("my::space::comp")?));
+        assert!(config.emits(&PathName::try_from("my::comp")?));
+        assert!(!config.emits(&PathName::try_from("my::test::comp")?));
+        assert!(!config.emits(&PathName::try_from("other::comp")?));
+        assert!(VhdlConfig::new().emits(&PathName::try_from("other::comp")?));
+        Ok(())
+    }
+}
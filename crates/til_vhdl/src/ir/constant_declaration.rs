@@ -0,0 +1,49 @@
+use til_query::ir::{project::constant_declaration::ConstantDeclaration, Ir};
+use tydi_common::{
+    error::{Result, TryOptional},
+    name::PathNameSelf,
+    traits::{Document, Documents},
+};
+use tydi_intern::Id;
+use tydi_vhdl::{
+    architecture::arch_storage::{
+        interner::{GetSelf, InternSelf},
+        Arch,
+    },
+    assignment::ValueAssignment,
+    common::vhdl_name::VhdlName,
+    declaration::ObjectDeclaration,
+    object::object_type::ObjectType,
+};
+
+use crate::IntoVhdl;
+
+use super::generics::integer_type;
+
+impl IntoVhdl<Id<ObjectDeclaration>> for ConstantDeclaration {
+    /// A constant object named after the full name of the constant, e.g.
+    /// `my_0_space_0_width` for `my::space::width`, documented with the
+    /// documentation of the constant.
+    fn canonical(
+        &self,
+        _ir_db: &dyn Ir,
+        arch_db: &mut dyn Arch,
+        prefix: impl TryOptional<VhdlName>,
+    ) -> Result<Id<ObjectDeclaration>> {
+        let name = self.path_name().join("_0_");
+        let identifier = match prefix.try_optional()? {
+            Some(prefix) => format!("{}_{}", prefix, name),
+            None => name,
+        };
+        let constant = ObjectDeclaration::constant(
+            arch_db,
+            identifier,
+            ObjectType::Integer(integer_type(self.kind())),
+            ValueAssignment::Integer(self.value()),
+        )?;
+        Ok(match self.doc() {
+            Some(doc) => constant.get(arch_db).with_doc(doc).intern(arch_db),
+            None => constant,
+        })
+    }
+}
@@ -14,6 +14,14 @@ use self::param_value::param_value_to_vhdl;
 
 pub mod param_value;
 
+pub fn integer_type(kind: &IntegerGenericKind) -> IntegerType {
+    match kind {
+        IntegerGenericKind::Integer => IntegerType::Integer,
+        IntegerGenericKind::Natural => IntegerType::Natural,
+        IntegerGenericKind::Positive => IntegerType::Positive,
+    }
+}
+
 pub fn param_to_param(
     arch_db: &dyn Arch,
     val: &til_query::ir::generics::GenericParameter,
@@ -22,14 +30,12 @@ pub fn param_to_param(
     let default = param_value_to_vhdl(arch_db, val.default_value(), parent_params)?;
     let typ = match val.kind() {
         GenericKind::Behavioral(b) => match b {
-            BehavioralGenericKind::Integer(i) => match i.kind() {
-                IntegerGenericKind::Integer => IntegerType::Integer,
-                IntegerGenericKind::Natural => IntegerType::Natural,
-                IntegerGenericKind::Positive => IntegerType::Positive,
-            },
+            BehavioralGenericKind::Integer(i) => integer_type(i.kind()),
         },
         GenericKind::Interface(i) => match i {
-            InterfaceGenericKind::Dimensionality(_) => IntegerType::Positive,
+            InterfaceGenericKind::Dimensionality(_)
+            | InterfaceGenericKind::Throughput(_)
+            | InterfaceGenericKind::Complexity(_) => IntegerType::Positive,
         },
     };
     if let Some(doc) = val.doc() {
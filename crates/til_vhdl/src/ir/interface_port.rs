@@ -21,7 +21,9 @@ use tydi_vhdl::{
     port::{Mode, Port},
 };
 
-use crate::common::physical::stream::{physical_stream_to_vhdl, VhdlPhysicalStream};
+use crate::common::physical::stream::{
+    physical_bitcount_to_bitvector, physical_stream_to_vhdl, VhdlPhysicalStream,
+};
 
 pub(crate) type InterfacePort = til_query::ir::interface_port::InterfacePort;
 
@@ -87,7 +89,10 @@ pub fn interface_port_to_vhdl(
                     InterfaceDirection::Out => Mode::Out,
                     InterfaceDirection::In => Mode::In,
                 },
-                width.clone(),
+                match width.try_eval() {
+                    Some(fixed) => fixed.into(),
+                    None => physical_bitcount_to_bitvector(arch_db, width, parent_params)?,
+                },
             )?,
         )?;
     }
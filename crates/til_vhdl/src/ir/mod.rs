@@ -1,4 +1,7 @@
+pub mod constant_declaration;
 pub mod generics;
 pub mod interface_port;
 pub mod physical_properties;
+pub mod specialization;
 pub mod streamlet;
+pub mod type_declaration;
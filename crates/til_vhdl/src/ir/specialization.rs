@@ -0,0 +1,272 @@
+//! Parameters which determine the throughput or complexity of a Streamlet's
+//! ports, or the length of an Array in their types, can not be lowered to VHDL
+//! generics, as they determine which signals its ports have. Instead, every
+//! distinct assignment of these parameters results in its own entity.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use til_query::{
+    common::logical::logicaltype::{genericproperty::GenericProperty, LogicalType},
+    ir::{
+        generics::{
+            interface::InterfaceGenericKind, param_value::GenericParamValue, GenericKind,
+            GenericParameter,
+        },
+        implementation::{
+            structure::streamlet_instance::{GenericParameterAssignment, StreamletInstance},
+            ImplementationKind,
+        },
+        traits::GetSelf,
+        Ir,
+    },
+};
+use tydi_common::{
+    error::{Error, Result},
+    map::InsertionOrderedMap,
+    name::{Name, NameSelf, PathName, PathNameSelf},
+    numbers::{i32_to_u32, u32_to_i32, NonNegative},
+};
+use tydi_intern::Id;
+
+use super::streamlet::Streamlet;
+
+/// Whether a parameter determines the throughput or complexity of a Stream.
+pub fn is_stream_property(param: &GenericParameter) -> bool {
+    matches!(
+        param.kind(),
+        GenericKind::Interface(
+            InterfaceGenericKind::Throughput(_) | InterfaceGenericKind::Complexity(_)
+        )
+    )
+}
+
+/// The names of the parameters which determine the length of an Array in
+/// the type of one of the ports of `streamlet`.
+fn array_length_params(ir_db: &dyn Ir, streamlet: &Streamlet) -> BTreeSet<Name> {
+    fn add_property(prop: &GenericProperty<NonNegative>, result: &mut BTreeSet<Name>) {
+        match prop {
+            GenericProperty::Combination(l, _, r) => {
+                add_property(l, result);
+                add_property(r, result);
+            }
+            GenericProperty::Fixed(_) => (),
+            GenericProperty::Parameterized(name) => {
+                result.insert(name.clone());
+            }
+        }
+    }
+    fn add_type(ir_db: &dyn Ir, typ: Id<LogicalType>, result: &mut BTreeSet<Name>) {
+        match typ.get(ir_db) {
+            LogicalType::Null | LogicalType::Bits(_) => (),
+            LogicalType::Group(group) => {
+                for field in group.field_ids().values() {
+                    add_type(ir_db, *field, result);
+                }
+            }
+            LogicalType::Union(union) => {
+                for field in union.field_ids().values() {
+                    add_type(ir_db, *field, result);
+                }
+            }
+            LogicalType::Stream(stream) => {
+                let stream = stream.get(ir_db);
+                add_type(ir_db, stream.data_id(), result);
+                add_type(ir_db, stream.user_id(), result);
+            }
+            LogicalType::Array(array) => {
+                add_property(array.length(), result);
+                add_type(ir_db, array.element_id(), result);
+            }
+        }
+    }
+
+    let mut result = BTreeSet::new();
+    for port in streamlet.ports(ir_db).values() {
+        let stream = port.stream(ir_db);
+        add_type(ir_db, stream.data_id(), &mut result);
+        add_type(ir_db, stream.user_id(), &mut result);
+    }
+    result
+}
+
+/// The values assigned to the throughput and complexity parameters of a
+/// Streamlet, and to the parameters which determine the length of an Array
+/// in the types of its ports.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Specialization {
+    assignments: InsertionOrderedMap<Name, GenericParameterAssignment>,
+    /// Whether all parameters are assigned their default values, in which
+    /// case the entity keeps the name of the Streamlet.
+    is_default: bool,
+}
+
+impl Specialization {
+    /// A specialization which does not assign any parameters.
+    pub fn none() -> Self {
+        Specialization {
+            assignments: InsertionOrderedMap::new(),
+            is_default: true,
+        }
+    }
+
+    /// The specialization which assigns the default values of the parameters
+    /// of `streamlet`.
+    pub fn default_for(ir_db: &dyn Ir, streamlet: &Streamlet) -> Result<Self> {
+        let array_params = array_length_params(ir_db, streamlet);
+        let mut assignments = InsertionOrderedMap::new();
+        for (name, param) in streamlet.parameters(ir_db) {
+            if is_stream_property(&param) || array_params.contains(&name) {
+                let value = fixed_value(&param, param.default_value(), &Specialization::none())?;
+                assignments.try_insert(
+                    name,
+                    GenericParameterAssignment::Default(param).try_assign(value)?,
+                )?;
+            }
+        }
+        Ok(Specialization {
+            assignments,
+            is_default: true,
+        })
+    }
+
+    /// The specialization of the definition of `instance`, where the values
+    /// assigned to it may refer to the parameters of its parent, which is
+    /// specialized as `parent`.
+    pub fn for_instance(
+        ir_db: &dyn Ir,
+        instance: &StreamletInstance,
+        parent: &Specialization,
+    ) -> Result<Self> {
+        let array_params = array_length_params(ir_db, &instance.definition());
+        let mut assignments = InsertionOrderedMap::new();
+        let mut is_default = true;
+        for (name, assignment) in instance.parameter_assignments() {
+            let param = assignment.parameter();
+            if is_stream_property(param) || array_params.contains(name) {
+                let value = fixed_value(param, assignment.value(), parent)?;
+                let default = fixed_value(param, param.default_value(), &Specialization::none())?;
+                is_default &= value == default;
+                assignments.try_insert(
+                    name.clone(),
+                    GenericParameterAssignment::Default(param.clone()).try_assign(value)?,
+                )?;
+            }
+        }
+        Ok(Specialization {
+            assignments,
+            is_default,
+        })
+    }
+
+    /// The assignments of this specialization, by parameter name.
+    pub fn assignments(&self) -> &InsertionOrderedMap<Name, GenericParameterAssignment> {
+        &self.assignments
+    }
+
+    /// Whether this specialization assigns `param`.
+    pub fn assigns(&self, param: &Name) -> bool {
+        self.assignments.contains(param)
+    }
+
+    /// Substitute the values assigned by this specialization in `value`. If
+    /// `value` refers to any of the parameters assigned by it, the result
+    /// must be fixed.
+    pub fn substitute(&self, value: &GenericParamValue) -> Result<GenericParamValue> {
+        let property = match GenericProperty::<NonNegative>::try_from(value.clone()) {
+            Ok(property) => property,
+            Err(_) => return Ok(value.clone()),
+        };
+        let mut assigned = property.clone();
+        for (name, assignment) in self.assignments() {
+            if let GenericParamValue::Integer(parent_value) = assignment.value() {
+                assigned = assigned
+                    .try_assign(name, GenericProperty::Fixed(i32_to_u32(*parent_value)?))?;
+            }
+        }
+        if assigned == property {
+            Ok(value.clone())
+        } else {
+            match assigned.try_eval() {
+                Some(result) => Ok(GenericParamValue::Integer(u32_to_i32(result)?)),
+                None => Err(Error::BackEndError(format!(
+                    "Unable to assign {}, as it combines parameters which are lowered to VHDL generics with parameters which determine the signals of a port",
+                    value
+                ))),
+            }
+        }
+    }
+
+    /// The name of the entity of `base` with this specialization. This is the
+    /// name of `base` itself if all parameters are assigned their defaults,
+    /// otherwise the name is extended with the assigned values.
+    pub fn path_name(&self, base: &PathName) -> Result<PathName> {
+        if self.is_default {
+            return Ok(base.clone());
+        }
+        let mut result = base.clone();
+        for (name, assignment) in &self.assignments {
+            if let GenericParamValue::Integer(value) = assignment.value() {
+                result = result.with_child(Name::try_new(format!("{}_{}", name, value))?);
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Evaluate the `value` assigned to `param`, where `value` may refer to the
+/// parameters assigned by `parent`.
+fn fixed_value(
+    param: &GenericParameter,
+    value: &GenericParamValue,
+    parent: &Specialization,
+) -> Result<GenericParamValue> {
+    match parent.substitute(value)?.reduce().remove_outer_parens() {
+        GenericParamValue::Integer(result) => Ok(GenericParamValue::Integer(result)),
+        _ => Err(Error::BackEndError(format!(
+            "The value of parameter {} ({}) depends on parameters which are not known when generating VHDL, as it determines the signals of a port",
+            param.name(),
+            value,
+        ))),
+    }
+}
+
+/// The specializations of `streamlets` and all streamlets they instantiate,
+/// by name of the Streamlet. The default specialization of each Streamlet
+/// comes first.
+pub fn collect_specializations(
+    ir_db: &dyn Ir,
+    streamlets: impl IntoIterator<Item = Arc<Streamlet>>,
+) -> Result<BTreeMap<PathName, Vec<Specialization>>> {
+    let mut result: BTreeMap<PathName, Vec<Specialization>> = BTreeMap::new();
+    let mut pending = vec![];
+    for streamlet in streamlets {
+        let specialization = Specialization::default_for(ir_db, &streamlet)?;
+        result
+            .entry(streamlet.path_name().clone())
+            .or_default()
+            .push(specialization.clone());
+        pending.push((streamlet, specialization));
+    }
+
+    while let Some((streamlet, specialization)) = pending.pop() {
+        if let Some(implementation) = streamlet.implementation(ir_db) {
+            if let ImplementationKind::Structural(structure) = implementation.kind() {
+                for instance in structure.streamlet_instances().values() {
+                    let definition = instance.definition();
+                    let instance_specialization =
+                        Specialization::for_instance(ir_db, instance, &specialization)?;
+                    let known = result.entry(definition.path_name().clone()).or_default();
+                    if !known.contains(&instance_specialization) {
+                        known.push(instance_specialization.clone());
+                        pending.push((definition, instance_specialization));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(result)
+}
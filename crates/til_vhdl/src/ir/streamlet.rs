@@ -1,4 +1,4 @@
-use std::{fs, sync::Arc};
+use std::{fs, path::PathBuf, sync::Arc};
 
 use til_query::{
     common::{
@@ -36,7 +36,7 @@ use tydi_vhdl::{
     assignment::{Assign, FieldSelection, ObjectSelection, SelectObject, ValueAssignment},
     common::vhdl_name::{VhdlName, VhdlNameSelf},
     component::Component,
-    declaration::{Declare, DeclareWithIndent, ObjectDeclaration},
+    declaration::{DeclareWithIndent, ObjectDeclaration},
     port::{GenericParameter, Port},
     statement::{
         mapping::Mapping,
@@ -50,6 +50,7 @@ use super::{
     generics::{param_to_param, param_value::param_value_to_vhdl},
     interface_port::{interface_port_to_vhdl, VhdlInterface},
     physical_properties::{VhdlDomain, VhdlDomainListOrDefault},
+    specialization::Specialization,
 };
 
 pub(crate) type Streamlet = til_query::ir::streamlet::Streamlet;
@@ -98,10 +99,12 @@ pub struct PhysicalStreamObject {
     dimensionality: Relation,
     /// Complexity.
     complexity: Complexity,
-    /// The absolute size of a data element
-    data_element_size: NonNegative,
-    /// The absolute size of the user data
-    user_size: NonNegative,
+    /// The absolute size of a data element, None if it depends on generic
+    /// parameters
+    data_element_size: Option<NonNegative>,
+    /// The absolute size of the user data, None if it depends on generic
+    /// parameters
+    user_size: Option<NonNegative>,
     /// Direction of the parent interface.
     interface_direction: InterfaceDirection,
     /// Overall direction of the physical stream
@@ -136,13 +139,15 @@ impl PhysicalStreamObject {
         self.interface_direction
     }
 
-    /// The absolute size of the user data
-    pub fn user_size(&self) -> NonNegative {
+    /// The absolute size of the user data, None if it depends on generic
+    /// parameters
+    pub fn user_size(&self) -> Option<NonNegative> {
         self.user_size
     }
 
-    /// The absolute size of a data element
-    pub fn data_element_size(&self) -> NonNegative {
+    /// The absolute size of a data element, None if it depends on generic
+    /// parameters
+    pub fn data_element_size(&self) -> Option<NonNegative> {
         self.data_element_size
     }
 
@@ -233,7 +238,17 @@ impl PhysicalStreamObject {
                     lanes
                 )))
             } else {
-                let lower = lane * self.data_element_size();
+                let lower = match self.data_element_size() {
+                    Some(size) => lane * size,
+                    None if lane == 0 => 0,
+                    None => {
+                        return Err(Error::InvalidArgument(format!(
+                            "Cannot select lane {} of {}, as the size of its data elements depends on generic parameters.",
+                            lane,
+                            self.path_name()
+                        )))
+                    }
+                };
                 let upper = lower + element_size - 1;
                 let selection = if lower == upper {
                     FieldSelection::index(u32_to_i32(lower)?)
@@ -273,19 +288,25 @@ pub struct VhdlStreamlet {
     interface: InsertionOrderedMap<Name, VhdlInterface>,
     doc: Option<String>,
     component: Option<Arc<Component>>,
+    specialization: Specialization,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum StreamletArchitecture {
     Imported(String),
     Generated(Architecture),
+    /// The file of a linked implementation does not exist yet. The
+    /// architecture is an empty template which can be created at this path.
+    Template(PathBuf, Architecture),
 }
 
 impl DeclareWithIndent for StreamletArchitecture {
     fn declare_with_indent(&self, db: &dyn Arch, indent_style: &str) -> Result<String> {
         match self {
             StreamletArchitecture::Imported(i) => Ok(i.clone()),
-            StreamletArchitecture::Generated(g) => g.declare_with_indent(db, indent_style),
+            StreamletArchitecture::Generated(g) | StreamletArchitecture::Template(_, g) => {
+                g.declare_with_indent(db, indent_style)
+            }
         }
     }
 }
@@ -298,12 +319,45 @@ pub fn create_instance(
     parent_domains: &VhdlDomainListOrDefault<Id<ObjectDeclaration>>,
     parent_params: &InsertionOrderedMap<Name, Id<ObjectDeclaration>>,
     prefix: impl TryOptional<VhdlName>,
+) -> Result<InsertionOrderedMap<InterfaceReference, PortObject>> {
+    create_specialized_instance(
+        ir_db,
+        arch_db,
+        instance,
+        architecture,
+        parent_domains,
+        parent_params,
+        &Specialization::none(),
+        prefix,
+    )
+}
+
+/// Create an instance in the architecture of a parent which is specialized
+/// as `parent_specialization`.
+///
+/// The instance refers to the entity of its definition which is specialized
+/// for the throughput and complexity parameters assigned to it.
+#[allow(clippy::too_many_arguments)]
+fn create_specialized_instance(
+    ir_db: &dyn Ir,
+    arch_db: &mut dyn Arch,
+    instance: &StreamletInstance,
+    architecture: &mut Architecture,
+    parent_domains: &VhdlDomainListOrDefault<Id<ObjectDeclaration>>,
+    parent_params: &InsertionOrderedMap<Name, Id<ObjectDeclaration>>,
+    parent_specialization: &Specialization,
+    prefix: impl TryOptional<VhdlName>,
 ) -> Result<InsertionOrderedMap<InterfaceReference, PortObject>> {
     let prefix = prefix.try_optional()?;
 
-    let mut vhdl_streamlet = instance
-        .definition()
-        .canonical(ir_db, arch_db, prefix.clone())?;
+    let specialization = Specialization::for_instance(ir_db, instance, parent_specialization)?;
+    let mut vhdl_streamlet = specialized_canonical(
+        &instance.definition(),
+        ir_db,
+        arch_db,
+        prefix.clone(),
+        &specialization,
+    )?;
     let component = vhdl_streamlet.to_component();
 
     let instance_name = instance.name();
@@ -322,10 +376,15 @@ pub fn create_instance(
     for (param_name, param_assignment) in instance.parameter_assignments() {
         match param_assignment {
             GenericParameterAssignment::Default(_) => (),
+            GenericParameterAssignment::Assigned(_, _) if specialization.assigns(param_name) => (),
             GenericParameterAssignment::Assigned(_, val) => port_mapping.map_param(
                 arch_db,
                 param_name.clone(),
-                param_value_to_vhdl(arch_db, val, parent_params)?,
+                param_value_to_vhdl(
+                    arch_db,
+                    &parent_specialization.substitute(val)?,
+                    parent_params,
+                )?,
             )?,
         };
     }
@@ -334,9 +393,11 @@ pub fn create_instance(
 
     let mut interface = InsertionOrderedMap::new();
     for (name, port) in instance.ports() {
+        let mut port = port.clone();
+        port.try_assign_stream(ir_db, parent_specialization.assignments())?;
         interface.try_insert(
             name.clone(),
-            interface_port_to_vhdl(ir_db, arch_db, port, prefix.clone(), parent_params)?,
+            interface_port_to_vhdl(ir_db, arch_db, &port, prefix.clone(), parent_params)?,
         )?;
     }
 
@@ -464,6 +525,11 @@ impl VhdlStreamlet {
         &self.parameters
     }
 
+    /// The throughput and complexity parameters assigned to this entity.
+    pub fn specialization(&self) -> &Specialization {
+        &self.specialization
+    }
+
     pub fn to_component(&mut self) -> Arc<Component> {
         if let Some(component) = &self.component {
             component.clone()
@@ -567,14 +633,9 @@ impl VhdlStreamlet {
                 Architecture::from_database(arch_db, "Behaviour")
             }?;
 
-            // TODO: Make whether to create a file if one doesn't exist configurable (Yes/No/Ask)
-            let result_string = architecture.declare(arch_db)?;
-            fs::write(file_pth.as_path(), &result_string)
-                .map_err(|err| Error::FileIOError(err.to_string()))?;
-
             // TODO for much later: Try to incorporate "fancy wrapper" work into this
 
-            Ok(StreamletArchitecture::Generated(architecture))
+            Ok(StreamletArchitecture::Template(file_pth, architecture))
         }
     }
 
@@ -644,13 +705,14 @@ impl VhdlStreamlet {
             .try_map_convert(|x| ObjectDeclaration::from_parameter(arch_db, &x))?;
 
         for (_, streamlet) in structure.streamlet_instances() {
-            ports.try_append(create_instance(
+            ports.try_append(create_specialized_instance(
                 ir_db,
                 arch_db,
                 streamlet,
                 &mut architecture,
                 &entity_domains,
                 &parent_parameters,
+                self.specialization(),
                 self.prefix().clone(),
             )?)?;
         }
@@ -801,42 +863,65 @@ impl Document for VhdlStreamlet {
 }
 
 impl IntoVhdl<VhdlStreamlet> for Streamlet {
+    /// The canonical definition of this Streamlet, with its throughput and
+    /// complexity parameters assigned their default values.
     fn canonical(
         &self,
         ir_db: &dyn Ir,
         arch_db: &mut dyn Arch,
         prefix: impl TryOptional<VhdlName>,
     ) -> Result<VhdlStreamlet> {
-        let prefix = prefix.try_optional()?;
-
-        let no_parent_params = InsertionOrderedMap::new();
-        let parameters = self
-            .parameters(ir_db)
-            .try_map_convert(|p| param_to_param(arch_db, &p, &no_parent_params))?;
+        let specialization = Specialization::default_for(ir_db, self)?;
+        specialized_canonical(self, ir_db, arch_db, prefix, &specialization)
+    }
+}
 
-        let parent_params = parameters
-            .clone()
-            .try_map_convert(|x| ObjectDeclaration::from_parameter(arch_db, &x))?;
+/// The canonical definition of `streamlet`, with its throughput and complexity
+/// parameters assigned as in `specialization`.
+///
+/// The remaining parameters are lowered to VHDL generics.
+pub fn specialized_canonical(
+    streamlet: &Streamlet,
+    ir_db: &dyn Ir,
+    arch_db: &mut dyn Arch,
+    prefix: impl TryOptional<VhdlName>,
+    specialization: &Specialization,
+) -> Result<VhdlStreamlet> {
+    let prefix = prefix.try_optional()?;
 
-        let mut interface = InsertionOrderedMap::new();
-        for (name, port) in self.interface(ir_db).ports() {
-            interface.try_insert(
-                name.clone(),
-                interface_port_to_vhdl(ir_db, arch_db, port, prefix.clone(), &parent_params)?,
-            )?;
+    let no_parent_params = InsertionOrderedMap::new();
+    let mut parameters = InsertionOrderedMap::new();
+    for (name, param) in streamlet.parameters(ir_db) {
+        if !specialization.assigns(&name) {
+            parameters.try_insert(name, param_to_param(arch_db, &param, &no_parent_params)?)?;
         }
+    }
 
-        let domains = self.domains(ir_db).into();
+    let parent_params = parameters
+        .clone()
+        .try_map_convert(|x| ObjectDeclaration::from_parameter(arch_db, &x))?;
 
-        Ok(VhdlStreamlet {
-            prefix,
-            name: self.path_name().clone(),
-            implementation: self.implementation_id(),
-            parameters,
-            domains,
-            interface,
-            doc: self.doc().cloned(),
-            component: None,
-        })
+    let mut interface = InsertionOrderedMap::new();
+    for (name, port) in streamlet.interface(ir_db).ports() {
+        let mut port = port.clone();
+        port.try_assign_stream(ir_db, specialization.assignments())?;
+        interface.try_insert(
+            name.clone(),
+            interface_port_to_vhdl(ir_db, arch_db, &port, prefix.clone(), &parent_params)?,
+        )?;
     }
+
+    let domains = streamlet.domains(ir_db).into();
+
+    Ok(VhdlStreamlet {
+        prefix,
+        name: specialization.path_name(streamlet.path_name())?,
+        implementation: streamlet.implementation_id(),
+        parameters,
+        domains,
+        interface,
+        doc: streamlet.doc().cloned(),
+        component: None,
+        specialization: specialization.clone(),
+    })
 }
@@ -0,0 +1,118 @@
+use til_query::{
+    common::logical::logicaltype::LogicalType,
+    ir::{project::type_declaration::TypeDeclaration, traits::GetSelf, Ir},
+};
+use tydi_common::{
+    error::{Result, TryOptional},
+    name::PathNameSelf,
+    numbers::u32_to_i32,
+    traits::{Document, Documents},
+};
+use tydi_intern::Id;
+use tydi_vhdl::{
+    architecture::arch_storage::{
+        interner::{GetSelf as GetVhdl, InternSelf},
+        Arch,
+    },
+    assignment::{bitvec::BitVecValue, ValueAssignment},
+    common::vhdl_name::VhdlName,
+    declaration::ObjectDeclaration,
+    object::object_type::ObjectType,
+};
+
+use crate::IntoVhdl;
+
+impl IntoVhdl<Vec<Id<ObjectDeclaration>>> for TypeDeclaration {
+    /// The constants for the tag values of an enumeration (a Union of which
+    /// all fields are Null), named after the type and its variant, e.g.
+    /// `my_0_space_0_opcode_0_add` for variant `add` of `my::space::opcode`.
+    ///
+    /// The constants are documented with the documentation of their variant,
+    /// the first constant is also documented with that of the type.
+    ///
+    /// Other types, and enumerations with a single variant (which have no
+    /// tag), do not declare any constants.
+    fn canonical(
+        &self,
+        ir_db: &dyn Ir,
+        arch_db: &mut dyn Arch,
+        prefix: impl TryOptional<VhdlName>,
+    ) -> Result<Vec<Id<ObjectDeclaration>>> {
+        let union = match self.type_id(ir_db)?.get(ir_db) {
+            LogicalType::Union(union) if union.is_enumeration(ir_db) => union,
+            _ => return Ok(vec![]),
+        };
+        let tag = match union.tag() {
+            Some(tag) => tag.get(),
+            None => return Ok(vec![]),
+        };
+        let type_name = self.path_name().join("_0_");
+        let type_name = match prefix.try_optional()? {
+            Some(prefix) => format!("{}_{}", prefix, type_name),
+            None => type_name,
+        };
+        union
+            .field_ids()
+            .keys()
+            .enumerate()
+            .map(|(value, variant)| {
+                let constant = ObjectDeclaration::constant(
+                    arch_db,
+                    format!("{}_0_{}", type_name, variant),
+                    ObjectType::bit_vector(u32_to_i32(tag - 1)?, 0)?,
+                    ValueAssignment::from(BitVecValue::from_str(&format!(
+                        "{:0width$b}",
+                        value,
+                        width = tag as usize
+                    ))?),
+                )?;
+                let type_doc = if value == 0 { self.doc() } else { None };
+                let variant_doc = self.field_doc(variant);
+                let doc = type_doc
+                    .into_iter()
+                    .chain(variant_doc)
+                    .cloned()
+                    .collect::<Vec<String>>();
+                Ok(if doc.is_empty() {
+                    constant
+                } else {
+                    constant
+                        .get(arch_db)
+                        .with_doc(doc.join("\n"))
+                        .intern(arch_db)
+                })
+            })
+            .collect()
+    }
+}
+
+/// A comment documenting a type declaration and its fields, for types which
+/// do not declare any constants (see [`IntoVhdl`]), as their documentation
+/// would otherwise be lost. Returns None if neither the type nor any of its
+/// fields are documented.
+///
+/// E.g., for a documented Group `my::space::pixel` with a documented field
+/// `r`:
+///
+/// ```text
+/// Type my::space::pixel
+/// A single pixel
+/// Field r: The red channel
+/// ```
+pub fn type_documentation(type_decl: &TypeDeclaration) -> Option<String> {
+    if type_decl.doc().is_none() && type_decl.field_docs().len() == 0 {
+        return None;
+    }
+    let mut result = vec![format!("Type {}", type_decl.path_name().join("::"))];
+    if let Some(doc) = type_decl.doc() {
+        result.push(doc.clone());
+    }
+    for (field, doc) in type_decl.field_docs() {
+        result.push(format!(
+            "Field {}: {}",
+            field.join("::"),
+            doc.split_terminator('\n').collect::<Vec<_>>().join("\n  ")
+        ));
+    }
+    Some(result.join("\n"))
+}
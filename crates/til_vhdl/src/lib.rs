@@ -1,24 +1,40 @@
 extern crate tydi_vhdl;
 
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    iter,
+    path::PathBuf,
+    sync::Arc,
+};
 
-use log::debug;
-use til_query::ir::Ir;
+use log::warn;
+use til_query::ir::{project::Project, streamlet::Streamlet, traits::GetSelf, Ir};
 use tydi_common::{
     error::{Error, Result, TryOptional},
+    name::{Name, NameSelf, PathName, PathNameSelf},
     traits::Identify,
 };
 use tydi_vhdl::{
     architecture::arch_storage::Arch,
     common::vhdl_name::{VhdlName, VhdlNameSelf},
-    declaration::Declare,
+    declaration::DeclareWithIndent,
     package::Package,
 };
 
-use crate::ir::streamlet::StreamletArchitecture;
+use crate::{
+    config::{MissingLinkBehaviour, VhdlConfig},
+    ir::{
+        specialization::{collect_specializations, Specialization},
+        streamlet::{specialized_canonical, StreamletArchitecture},
+        type_declaration::type_documentation,
+    },
+    output::{GeneratedVhdl, OutputReport},
+};
 
 pub mod common;
+pub mod config;
 pub mod ir;
+pub mod output;
 
 // TODO: To improve performance, it might make sense to put these
 // implementations on a database trait, instead.
@@ -34,12 +50,10 @@ pub trait IntoVhdl<T> {
     }
 }
 
-/// Generates canonical definitions of all Streamlets defined in the database `db`.
+/// The directory the backend writes the VHDL files of the project in `db` to.
 ///
-/// The `output_folder` is defined relative to the base Project's folder.
-///
-/// The `indent_style` determines whether to use tabs or spaces, and how many.
-pub fn canonical(db: &dyn Ir) -> Result<()> {
+/// This is a subdirectory of the project's output path, named after the project.
+pub fn output_directory(db: &dyn Ir) -> Result<PathBuf> {
     let mut dir = db
         .project_ref()
         .output_path()
@@ -48,47 +62,279 @@ pub fn canonical(db: &dyn Ir) -> Result<()> {
             "VHDL project requires an output path, project output path is None".to_string(),
         ))?;
     dir.push(db.project_ref().identifier());
-    std::fs::create_dir_all(dir.as_path())?;
+    Ok(dir)
+}
 
-    let streamlets = db.all_streamlets();
+/// The directory the backend writes the VHDL files of the project imported
+/// as `name` to.
+///
+/// Dependencies are written next to the project itself, in a directory named
+/// after the name they are imported as, which is also the name of their library.
+pub fn dependency_directory(db: &dyn Ir, name: &Name) -> Result<PathBuf> {
+    let mut dir = output_directory(db)?;
+    dir.pop();
+    dir.push(name.to_string());
+    Ok(dir)
+}
+
+/// The directories of the project in `db` and those of its dependencies.
+pub fn output_directories(db: &dyn Ir) -> Result<Vec<PathBuf>> {
+    let mut result = vec![output_directory(db)?];
+    for name in db.project_ref().imports().keys() {
+        result.push(dependency_directory(db, name)?);
+    }
+    Ok(result)
+}
 
-    let mut package = Package::new_named(db.project_ref().identifier())?;
+/// Generates canonical definitions of all Streamlets defined in the database `db`.
+///
+/// The `output_folder` is defined relative to the base Project's folder.
+///
+/// Uses the default backend configuration, see [`canonical_with_config`].
+///
+/// Files which already have the generated contents are not written. The returned
+/// report lists which files were added, changed or unchanged, as well as any
+/// stale files which were generated previously but are no longer part of the output.
+pub fn canonical(db: &dyn Ir) -> Result<OutputReport> {
+    canonical_with_config(db, &VhdlConfig::default())
+}
+
+/// Generates canonical definitions of all Streamlets defined in the database `db`,
+/// using the given backend configuration.
+pub fn canonical_with_config(db: &dyn Ir, config: &VhdlConfig) -> Result<OutputReport> {
+    generate(db, config)?.write()
+}
+
+/// Generates canonical definitions of all Streamlets defined in the database `db`,
+/// but only writes the architectures of the Streamlets in `streamlets`.
+///
+/// The package is always written, as it declares the components of all Streamlets.
+/// The architectures of all other Streamlets are reported as unchanged.
+pub fn canonical_streamlets(
+    db: &dyn Ir,
+    config: &VhdlConfig,
+    streamlets: &BTreeSet<PathName>,
+) -> Result<OutputReport> {
+    generate_streamlets(db, config, streamlets)?.write()
+}
+
+/// Generates canonical definitions of all Streamlets defined in the database `db`,
+/// without writing anything to the file system.
+pub fn generate(db: &dyn Ir, config: &VhdlConfig) -> Result<GeneratedVhdl> {
+    generate_selected(db, config, |_| true)
+}
+
+/// Generates canonical definitions of all Streamlets defined in the database `db`,
+/// but only generates the architectures of the Streamlets in `streamlets`,
+/// without writing anything to the file system.
+pub fn generate_streamlets(
+    db: &dyn Ir,
+    config: &VhdlConfig,
+    streamlets: &BTreeSet<PathName>,
+) -> Result<GeneratedVhdl> {
+    generate_selected(db, config, |name| streamlets.contains(name))
+}
+
+fn generate_selected(
+    db: &dyn Ir,
+    config: &VhdlConfig,
+    select: impl Fn(&PathName) -> bool,
+) -> Result<GeneratedVhdl> {
+    let project = db.project_ref();
+
+    // Streamlets of different projects end up in different libraries, but
+    // their components would still conflict when used by the same architecture.
+    let mut defined_by = BTreeMap::new();
+    for (project_name, streamlets) in iter::once((project.name(), project_streamlets(db, &project)))
+        .chain(
+            project
+                .imports()
+                .iter()
+                .map(|(name, dependency)| (name, project_streamlets(db, dependency))),
+        )
+    {
+        for streamlet in streamlets {
+            if let Some(other) = defined_by.insert(streamlet.identifier(), project_name) {
+                return Err(Error::BackEndError(format!(
+                    "Streamlet {} is defined by both project {} and project {}",
+                    streamlet.path_name().join("::"),
+                    other,
+                    project_name
+                )));
+            }
+        }
+    }
+
+    // Streamlets may be instantiated with different throughputs and
+    // complexities, which all require their own entity.
+    let specializations = collect_specializations(
+        db,
+        iter::once(project.as_ref())
+            .chain(project.imports().values())
+            .flat_map(|project| project_streamlets(db, project)),
+    )?;
+
+    let mut output = generate_project(
+        db,
+        config,
+        &project,
+        output_directory(db)?,
+        &select,
+        &specializations,
+        true,
+    )?;
+    for (name, dependency) in project.imports() {
+        let dependency_config = config
+            .clone()
+            .with_library(name.to_string())?
+            .with_include([])
+            .with_exclude([]);
+        output.add_dependency(generate_project(
+            db,
+            &dependency_config,
+            dependency,
+            dependency_directory(db, name)?,
+            &select,
+            &specializations,
+            false,
+        )?);
+    }
+    Ok(output)
+}
+
+/// The streamlets in the namespaces of `project`, which may be an imported project.
+fn project_streamlets(db: &dyn Ir, project: &Project) -> Vec<Arc<Streamlet>> {
+    project
+        .namespaces()
+        .iter()
+        .flat_map(|(_, id)| id.get(db).streamlets(db).into_values())
+        .collect()
+}
+
+/// Generate the package and architectures of a single project, `is_root`
+/// determines whether the namespaces to emit are filtered.
+///
+/// Every Streamlet results in an entity for each of its `specializations`.
+fn generate_project(
+    db: &dyn Ir,
+    config: &VhdlConfig,
+    project: &Project,
+    directory: PathBuf,
+    select: impl Fn(&PathName) -> bool,
+    specializations: &BTreeMap<PathName, Vec<Specialization>>,
+    is_root: bool,
+) -> Result<GeneratedVhdl> {
+    let mut output = GeneratedVhdl::new(directory);
+
+    let mut package = Package::new_named(project.identifier())?;
+    package.set_library(config.library().clone())?;
+    for (name, dependency) in project.imports() {
+        package.add_import(name.to_string(), dependency.identifier())?;
+    }
     let mut streamlet_component_names = vec![];
 
     let mut arch_db = tydi_vhdl::architecture::arch_storage::db::Database::default();
-    for streamlet in streamlets.iter() {
-        let mut streamlet = streamlet.canonical(db, &mut arch_db, "")?;
-        let component = streamlet.to_component();
-        streamlet_component_names.push((streamlet, component.vhdl_name().clone()));
-        package.add_component(component);
+    if config.export_constants() {
+        for (_, namespace_id) in project.namespaces() {
+            for constant in namespace_id.get(db).constants().values() {
+                let constant = constant.canonical(db, &mut arch_db, "")?;
+                package.add_constant(&arch_db, constant)?;
+            }
+        }
+    }
+    for (_, namespace_id) in project.namespaces() {
+        for type_decl in namespace_id.get(db).type_decls().values() {
+            let tags = type_decl.canonical(db, &mut arch_db, "")?;
+            if tags.is_empty() {
+                if let Some(comment) = type_documentation(type_decl) {
+                    package.add_comment(comment);
+                }
+            }
+            for tag in tags {
+                package.add_constant(&arch_db, tag)?;
+            }
+        }
+    }
+    for streamlet in project_streamlets(db, project).iter() {
+        for specialization in specializations
+            .get(streamlet.path_name())
+            .into_iter()
+            .flatten()
+        {
+            let mut vhdl_streamlet =
+                specialized_canonical(streamlet, db, &mut arch_db, "", specialization)?;
+            let component = vhdl_streamlet.to_component();
+            streamlet_component_names.push((
+                streamlet.path_name().clone(),
+                vhdl_streamlet,
+                component.vhdl_name().clone(),
+            ));
+            package.add_component(component);
+        }
     }
 
     let package = Arc::new(package);
-    let mut pkg = dir.clone();
-    pkg.push(format!("{}_pkg", package.vhdl_name()));
-    pkg.set_extension("vhd");
+    let pkg = format!("{}_pkg.vhd", package.vhdl_name());
 
     arch_db.set_default_package(package);
 
-    std::fs::write(pkg.as_path(), arch_db.default_package().declare(&arch_db)?)?;
-    debug!("Wrote {}.", pkg.as_path().to_str().unwrap_or(""));
+    output.add_file(
+        pkg,
+        arch_db
+            .default_package()
+            .declare_with_indent(&arch_db, config.indent())?,
+    );
 
-    for (streamlet, component_name) in streamlet_component_names.into_iter() {
+    for (streamlet_name, streamlet, component_name) in streamlet_component_names.into_iter() {
+        // The package still declares the components of streamlets which are
+        // not emitted, so emitted streamlets can instantiate them.
+        if is_root && !config.emits(&streamlet_name) {
+            continue;
+        }
+        let arch = config.file_naming().file_name(streamlet.path_name());
+        if !select(&streamlet_name) {
+            output.add_streamlet(streamlet.path_name().clone(), arch);
+            continue;
+        }
         arch_db.set_subject_component_name(Arc::new(component_name));
         let streamlet_arch = streamlet.to_architecture(db, &mut arch_db)?;
         let arch_string = match streamlet_arch {
             StreamletArchitecture::Imported(i) => i,
-            StreamletArchitecture::Generated(g) => g.declare(&arch_db)?,
+            StreamletArchitecture::Generated(g) => {
+                g.declare_with_indent(&arch_db, config.indent())?
+            }
+            StreamletArchitecture::Template(path, g) => match config.missing_link() {
+                MissingLinkBehaviour::Create => {
+                    let template = g.declare_with_indent(&arch_db, config.indent())?;
+                    output.add_template(path, template.clone());
+                    template
+                }
+                MissingLinkBehaviour::CreateInOutputDir => {
+                    g.declare_with_indent(&arch_db, config.indent())?
+                }
+                MissingLinkBehaviour::Error => {
+                    return Err(Error::BackEndError(format!(
+                        "The linked implementation of streamlet {} does not exist, expected a file at {}",
+                        streamlet.path_name().join("::"),
+                        path.display()
+                    )))
+                }
+                MissingLinkBehaviour::WarnAndSkip => {
+                    warn!(
+                        "Skipping streamlet {}, as its linked implementation does not exist, expected a file at {}",
+                        streamlet.path_name().join("::"),
+                        path.display()
+                    );
+                    continue;
+                }
+            },
         };
 
-        let mut arch = dir.clone();
-        arch.push(streamlet.identifier());
-        arch.set_extension("vhd");
-        std::fs::write(arch.as_path(), arch_string)?;
-        debug!("Wrote {}.", arch.as_path().to_str().unwrap_or(""));
+        output.add_streamlet(streamlet.path_name().clone(), arch.clone());
+        output.add_file(arch, arch_string);
     }
 
-    Ok(())
+    Ok(output)
 }
 
 // TODO: Once there's a super/project/root node, create a public function which uses all the IntoVhdls to output VHDL
@@ -0,0 +1,365 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use log::debug;
+use tydi_common::{
+    error::{Error, Result},
+    name::PathName,
+};
+
+/// Name of the file in the output directory which lists the files the backend
+/// generated there, used to detect stale files.
+pub const MANIFEST_FILE_NAME: &str = ".til_manifest";
+
+/// The VHDL generated for a project, kept in memory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GeneratedVhdl {
+    directory: PathBuf,
+    /// The contents of the files in the output directory, by file name.
+    files: BTreeMap<String, String>,
+    /// The file names of the architectures of all streamlets, including those
+    /// whose architectures were not generated.
+    streamlets: BTreeMap<PathName, String>,
+    /// Templates for linked implementations whose files do not exist yet, by path.
+    templates: BTreeMap<PathBuf, String>,
+    /// The VHDL generated for the project's dependencies, each in their own directory.
+    dependencies: Vec<GeneratedVhdl>,
+}
+
+impl GeneratedVhdl {
+    pub(crate) fn new(directory: PathBuf) -> Self {
+        GeneratedVhdl {
+            directory,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn add_file(&mut self, file_name: String, contents: String) {
+        self.files.insert(file_name, contents);
+    }
+
+    pub(crate) fn add_streamlet(&mut self, name: PathName, file_name: String) {
+        self.streamlets.insert(name, file_name);
+    }
+
+    pub(crate) fn add_template(&mut self, path: PathBuf, contents: String) {
+        self.templates.insert(path, contents);
+    }
+
+    pub(crate) fn add_dependency(&mut self, dependency: GeneratedVhdl) {
+        self.dependencies.push(dependency);
+    }
+
+    /// The directory the files would be written to.
+    pub fn directory(&self) -> &Path {
+        self.directory.as_path()
+    }
+
+    /// The contents of the generated files, by file name (relative to the
+    /// output directory).
+    pub fn files(&self) -> &BTreeMap<String, String> {
+        &self.files
+    }
+
+    /// The contents of the generated files, by the path they would be written to.
+    pub fn paths(&self) -> impl Iterator<Item = (PathBuf, &str)> {
+        self.files
+            .iter()
+            .map(|(name, contents)| (self.directory.join(name), contents.as_str()))
+    }
+
+    /// The templates which would be created for linked implementations whose
+    /// files do not exist yet, by path.
+    pub fn templates(&self) -> &BTreeMap<PathBuf, String> {
+        &self.templates
+    }
+
+    /// The VHDL generated for the project's dependencies.
+    pub fn dependencies(&self) -> &[GeneratedVhdl] {
+        self.dependencies.as_ref()
+    }
+
+    /// The generated entity and architecture of a streamlet, if it was generated.
+    pub fn streamlet(&self, name: &PathName) -> Option<&str> {
+        self.streamlets
+            .get(name)
+            .and_then(|file_name| self.files.get(file_name))
+            .map(String::as_str)
+    }
+
+    /// Write the generated files to the output directory (and those of the
+    /// dependencies to theirs), and create the templates for linked implementations.
+    ///
+    /// Files which already have the generated contents are not written.
+    /// Streamlets whose architectures were not generated are reported as unchanged.
+    pub fn write(&self) -> Result<OutputReport> {
+        let dependencies = self
+            .dependencies
+            .iter()
+            .map(GeneratedVhdl::write)
+            .collect::<Result<Vec<_>>>()?;
+
+        for (path, template) in self.templates.iter() {
+            std::fs::write(path, template).map_err(|err| {
+                Error::FileIOError(format!(
+                    "Unable to create template {}: {}",
+                    path.display(),
+                    err
+                ))
+            })?;
+            debug!("Created template {}.", path.display());
+        }
+
+        let mut output = OutputWriter::new(self.directory.clone())?;
+        for (file_name, contents) in self.files.iter() {
+            output.write(file_name, contents)?;
+        }
+        for file_name in self.streamlets.values() {
+            if !self.files.contains_key(file_name) {
+                output.keep(file_name);
+            }
+        }
+        let mut report = output.finish()?;
+        report.dependencies = dependencies;
+        Ok(report)
+    }
+}
+
+/// The files the backend wrote (or left alone) in an output directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputReport {
+    directory: PathBuf,
+    /// Files which did not exist yet.
+    added: Vec<PathBuf>,
+    /// Files which existed, but had different contents.
+    changed: Vec<PathBuf>,
+    /// Files which already had the expected contents, and were not written.
+    unchanged: Vec<PathBuf>,
+    /// Files which were generated previously, but are no longer part of the output.
+    stale: Vec<PathBuf>,
+    /// The reports of the output directories of the project's dependencies.
+    dependencies: Vec<OutputReport>,
+}
+
+impl OutputReport {
+    pub fn directory(&self) -> &Path {
+        self.directory.as_path()
+    }
+
+    pub fn added(&self) -> &[PathBuf] {
+        self.added.as_ref()
+    }
+
+    pub fn changed(&self) -> &[PathBuf] {
+        self.changed.as_ref()
+    }
+
+    pub fn unchanged(&self) -> &[PathBuf] {
+        self.unchanged.as_ref()
+    }
+
+    pub fn stale(&self) -> &[PathBuf] {
+        self.stale.as_ref()
+    }
+
+    pub fn dependencies(&self) -> &[OutputReport] {
+        self.dependencies.as_ref()
+    }
+
+    pub fn dependencies_mut(&mut self) -> &mut [OutputReport] {
+        self.dependencies.as_mut()
+    }
+
+    /// All files which are part of the current output.
+    pub fn current(&self) -> impl Iterator<Item = &PathBuf> {
+        self.added
+            .iter()
+            .chain(self.changed.iter())
+            .chain(self.unchanged.iter())
+    }
+
+    /// Remove the stale files from the output directory.
+    ///
+    /// Stale files remain listed in the manifest until they are removed.
+    /// The output directories of dependencies are left alone.
+    pub fn remove_stale(&mut self) -> Result<()> {
+        for file in self.stale.iter() {
+            std::fs::remove_file(file).map_err(|err| {
+                Error::FileIOError(format!("Unable to remove {}: {}", file.display(), err))
+            })?;
+            debug!("Removed {}.", file.display());
+        }
+        self.stale.clear();
+        write_manifest(&self.directory, self.current())
+    }
+}
+
+/// Writes files to an output directory, skipping files whose contents did not
+/// change, and keeping track of which files were generated.
+pub(crate) struct OutputWriter {
+    report: OutputReport,
+    previous: BTreeSet<PathBuf>,
+}
+
+impl OutputWriter {
+    pub fn new(directory: PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(directory.as_path())?;
+        let previous = match std::fs::read_to_string(directory.join(MANIFEST_FILE_NAME)) {
+            Ok(manifest) => manifest
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(|line| directory.join(line))
+                .collect(),
+            Err(_) => BTreeSet::new(),
+        };
+        Ok(OutputWriter {
+            report: OutputReport {
+                directory,
+                ..Default::default()
+            },
+            previous,
+        })
+    }
+
+    /// Write `contents` to `file_name` in the output directory, unless the
+    /// file already has these contents.
+    pub fn write(&mut self, file_name: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<()> {
+        let path = self.report.directory.join(file_name);
+        match std::fs::read(&path) {
+            Ok(existing) if existing == contents.as_ref() => {
+                debug!("{} is unchanged.", path.display());
+                self.report.unchanged.push(path);
+                return Ok(());
+            }
+            Ok(_) => self.report.changed.push(path.clone()),
+            Err(_) => self.report.added.push(path.clone()),
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, contents)?;
+        debug!("Wrote {}.", path.display());
+        Ok(())
+    }
+
+    /// Mark `file_name` as part of the output, without writing it.
+    pub fn keep(&mut self, file_name: impl AsRef<Path>) {
+        let path = self.report.directory.join(file_name);
+        self.report.unchanged.push(path);
+    }
+
+    /// Determine which previously generated files are stale, and update the manifest.
+    pub fn finish(mut self) -> Result<OutputReport> {
+        let current = self.report.current().cloned().collect::<BTreeSet<_>>();
+        self.report.stale = self
+            .previous
+            .iter()
+            .filter(|path| !current.contains(*path) && path.is_file())
+            .cloned()
+            .collect();
+        let manifest = current
+            .iter()
+            .chain(self.report.stale.iter())
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        if manifest != self.previous {
+            write_manifest(&self.report.directory, manifest.iter())?;
+        }
+        Ok(self.report)
+    }
+}
+
+fn write_manifest<'a>(directory: &Path, files: impl Iterator<Item = &'a PathBuf>) -> Result<()> {
+    // Files are listed relative to the output directory, using forward
+    // slashes regardless of the platform.
+    let files = files
+        .filter_map(|file| file.strip_prefix(directory).ok())
+        .map(|file| {
+            file.components()
+                .map(|part| part.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .collect::<BTreeSet<_>>();
+    let mut manifest = String::new();
+    for file in files {
+        manifest.push_str(&file);
+        manifest.push('\n');
+    }
+    std::fs::write(directory.join(MANIFEST_FILE_NAME), manifest)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skip_unchanged_and_find_stale() -> Result<()> {
+        let dir = std::env::temp_dir().join("til_vhdl_output_test");
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+
+        let mut writer = OutputWriter::new(dir.clone())?;
+        writer.write("a.vhd", "a")?;
+        writer.write("b.vhd", "b")?;
+        let report = writer.finish()?;
+        assert_eq!(report.added(), &[dir.join("a.vhd"), dir.join("b.vhd")]);
+
+        let mut writer = OutputWriter::new(dir.clone())?;
+        writer.write("a.vhd", "a")?;
+        writer.write("c.vhd", "c")?;
+        let mut report = writer.finish()?;
+        assert_eq!(report.added(), &[dir.join("c.vhd")]);
+        assert_eq!(report.unchanged(), &[dir.join("a.vhd")]);
+        assert_eq!(report.stale(), &[dir.join("b.vhd")]);
+
+        // Stale files are reported until they are removed.
+        let mut writer = OutputWriter::new(dir.clone())?;
+        writer.write("a.vhd", "changed")?;
+        writer.keep("c.vhd");
+        let report_again = writer.finish()?;
+        assert_eq!(report_again.changed(), &[dir.join("a.vhd")]);
+        assert_eq!(report_again.stale(), &[dir.join("b.vhd")]);
+
+        report.remove_stale()?;
+        assert!(!dir.join("b.vhd").exists());
+        let writer = OutputWriter::new(dir.clone())?;
+        assert_eq!(
+            writer.finish()?.stale(),
+            &[dir.join("a.vhd"), dir.join("c.vhd")]
+        );
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn nested_files() -> Result<()> {
+        let dir = std::env::temp_dir().join("til_vhdl_output_nested_test");
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+
+        let mut writer = OutputWriter::new(dir.clone())?;
+        writer.write("pkg.vhd", "pkg")?;
+        writer.write("my/space/comp.vhd", "comp")?;
+        writer.finish()?;
+        assert_eq!(
+            std::fs::read_to_string(dir.join(MANIFEST_FILE_NAME))?,
+            "my/space/comp.vhd\npkg.vhd\n"
+        );
+
+        let writer = OutputWriter::new(dir.clone())?;
+        assert_eq!(
+            writer.finish()?.stale(),
+            &[dir.join("my/space/comp.vhd"), dir.join("pkg.vhd")]
+        );
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -1,6 +1,9 @@
 use std::convert::TryInto;
 
-use til_parser::project::into_query_storage;
+use til_parser::{
+    project::{from_path, into_query_storage},
+    query::{file_to_project_with_diagnostics, into_query_storage_default},
+};
 use til_query::{
     common::{
         logical::logicaltype::{
@@ -19,13 +22,21 @@ use til_query::{
         Ir,
     },
 };
-use til_vhdl::canonical;
-use tydi_common::error::Result;
+use til_vhdl::{
+    canonical,
+    config::{FileNaming, IndentStyle, MissingLinkBehaviour, VhdlConfig},
+    generate,
+};
+use tydi_common::{
+    error::{Error, Result},
+    name::{Name, PathName},
+    traits::Document,
+};
 
 extern crate til_vhdl;
 
-fn parse_to_output(file_path: &str, name: &str) -> Result<()> {
-    let db = into_query_storage(
+fn parse_to_db(file_path: &str, name: &str) -> Result<Database> {
+    into_query_storage(
         format!(
             r#"name = "{}"
 
@@ -35,9 +46,52 @@ output_path = "../../test_output/""#,
             name, file_path
         ),
         ".",
-    )?;
+    )
+}
+
+fn parse_to_output(file_path: &str, name: &str) -> Result<()> {
+    canonical(&parse_to_db(file_path, name)?)?;
+    Ok(())
+}
+
+/// The source of a file declaring the namespace `namespace` with `decls`.
+fn namespace_src<'a>(namespace: &str, decls: impl IntoIterator<Item = &'a str>) -> String {
+    format!(
+        "namespace {} {{\n{}\n}}",
+        namespace,
+        decls.into_iter().collect::<Vec<_>>().join("\n")
+    )
+}
 
-    canonical(&db)
+/// Evaluate the namespace `namespace` declaring `decls`, on its own.
+fn eval_namespace<'a>(
+    namespace: &str,
+    decls: impl IntoIterator<Item = &'a str>,
+) -> Result<Database> {
+    into_query_storage_default(namespace_src(namespace, decls))
+}
+
+/// The error of a file of which a declaration failed to evaluate.
+const EVAL_ERROR: &str = "Errors during evaluation, see report.";
+
+/// Evaluate the source of a single file, and assert that it fails with
+/// `error`, reporting a diagnostic which contains `message`.
+fn assert_eval_error(src: impl Into<String>, error: &str, message: &str) -> Result<()> {
+    let mut db = Database::default();
+    db.set_project(Project::new("proj", ".", None::<&str>)?);
+    let (result, diagnostics) = file_to_project_with_diagnostics(src, &mut db, ".")?;
+    assert_eq!(result.err(), Some(Error::ProjectError(error.to_string())));
+    let messages = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.message())
+        .collect::<Vec<_>>();
+    assert!(
+        messages.iter().any(|m| m.contains(message)),
+        "no diagnostic contains \"{}\": {:?}",
+        message,
+        messages
+    );
+    Ok(())
 }
 
 #[test]
@@ -50,13 +104,41 @@ fn test_import() -> Result<()> {
     parse_to_output("tests/til_files/test_import.til", "test_import")
 }
 
+#[test]
+fn test_import_forms() -> Result<()> {
+    parse_to_output("tests/til_files/test_import_forms.til", "test_import_forms")
+}
+
+#[test]
+fn test_import_ambiguous() -> Result<()> {
+    assert_eval_error(
+        std::fs::read_to_string("tests/til_files/test_import_ambiguous.til")?,
+        EVAL_ERROR,
+        "Ambiguous type byte, could refer to imported__space__byte or other__space__byte",
+    )
+}
+
+#[test]
+fn test_import_glob() -> Result<()> {
+    parse_to_output("tests/til_files/test_import_glob.til", "test_import_glob")
+}
+
+#[test]
+fn test_import_shadowing() -> Result<()> {
+    assert_eval_error(
+        std::fs::read_to_string("tests/til_files/test_import_shadowing.til")?,
+        EVAL_ERROR,
+        "Declaration of type byte shadows imported type imported__space__byte",
+    )
+}
+
 #[test]
 fn test_cyclical() -> Result<()> {
-    assert_eq!(
-        parse_to_output("tests/til_files/test_cyclical.til", "test_cyclical").is_ok(),
-        false
-    );
-    Ok(())
+    assert_eval_error(
+        std::fs::read_to_string("tests/til_files/test_cyclical.til")?,
+        "Import error, namespaces imported__space, my__test__space have a cyclical dependency, see report.",
+        "Import of imported__space by my__test__space is part of a cyclical dependency",
+    )
 }
 
 #[test]
@@ -74,6 +156,480 @@ fn simple_generics_parse() -> Result<()> {
     parse_to_output("tests/til_files/simple_generics.til", "simple_generics")
 }
 
+#[test]
+fn generic_bits() -> Result<()> {
+    let db = parse_to_db("tests/til_files/generic_bits.til", "generic_bits")?;
+    let output = generate(&db, &VhdlConfig::default())?;
+    let pass = output
+        .streamlet(&PathName::try_from("generics::bits::pass")?)
+        .unwrap();
+    assert!(pass.contains("a_data : in std_logic_vector((w + 2 * w) - 1 downto 0);"));
+    assert!(pass.contains("a_user : in std_logic_vector((w + 1) - 1 downto 0);"));
+
+    let top = output
+        .streamlet(&PathName::try_from("generics::bits::top")?)
+        .unwrap();
+    assert!(top.contains("a_data : in std_logic_vector(23 downto 0);"));
+    assert!(top.contains("a_user : in std_logic_vector(8 downto 0);"));
+    assert!(top.contains("generic map(\n    w => 8\n  )"));
+    Ok(())
+}
+
+#[test]
+fn generic_stream_properties() -> Result<()> {
+    let db = parse_to_db(
+        "tests/til_files/generic_stream_props.til",
+        "generic_stream_props",
+    )?;
+    let output = generate(&db, &VhdlConfig::default())?;
+    let narrow = output
+        .streamlet(&PathName::try_from("generics::lanes::narrow")?)
+        .unwrap();
+    assert!(narrow.contains("a_data : in std_logic_vector(7 downto 0);"));
+    assert!(!narrow.contains("a_stai"));
+    let wide = output
+        .streamlet(&PathName::try_from("generics::lanes::wide")?)
+        .unwrap();
+    assert!(wide.contains("a_data : in std_logic_vector(31 downto 0);"));
+    assert!(wide.contains("a_stai : in std_logic_vector(1 downto 0);"));
+    assert!(wide.contains("a_strb : in std_logic_vector(3 downto 0);"));
+
+    // Every distinct throughput results in its own entity, the entity for the
+    // default values keeps the name of the streamlet
+    let lanes = output
+        .streamlet(&PathName::try_from("generics::lanes::lanes")?)
+        .unwrap();
+    assert!(lanes.contains("a_data : in std_logic_vector(7 downto 0);"));
+    assert!(!lanes.contains("a_endi"));
+    let forward = output
+        .streamlet(&PathName::try_from("generics::lanes::forward")?)
+        .unwrap();
+    assert!(forward.contains("a_data : in std_logic_vector(15 downto 0);"));
+    assert!(forward.contains("inner: generics_0_lanes_0_lanes_0_n_2_com port map("));
+    let forward_4 = output
+        .streamlet(&PathName::try_from("generics::lanes::forward::n_4")?)
+        .unwrap();
+    assert!(forward_4.contains("entity generics_0_lanes_0_forward_0_n_4_com is"));
+    assert!(forward_4.contains("a_endi : in std_logic_vector(1 downto 0);"));
+    assert!(forward_4.contains("inner: generics_0_lanes_0_lanes_0_n_4_com port map("));
+    let top = output
+        .streamlet(&PathName::try_from("generics::lanes::top")?)
+        .unwrap();
+    assert!(top.contains("inner: generics_0_lanes_0_forward_0_n_4_com port map("));
+    assert!(!top.contains("generic map"));
+    // Streamlets which are not instantiated are emitted with their defaults
+    let comp = output
+        .streamlet(&PathName::try_from("generics::lanes::comp")?)
+        .unwrap();
+    assert!(comp.contains("a_data : in std_logic_vector(15 downto 0);"));
+    assert!(comp.contains("a_stai : in std_logic_vector(0 downto 0);"));
+    assert!(!comp.contains("b_stai"));
+    assert!(!comp.contains("generic ("));
+
+    let lane_stream = "type lane_stream<n: throughput = 1, c: complexity = 4; >= 4> = Stream(
+        data: Bits(8), throughput: n, dimensionality: 0, synchronicity: Sync, complexity: c,
+    );";
+    // Values which do not satisfy the (implicit) conditions of the parameters
+    for (decl, message) in [
+        (
+            "type s = lane_stream<0>;",
+            "Value Integer(0) is not a valid value for parameter n",
+        ),
+        (
+            "type s = lane_stream<2, 3>;",
+            "Value Integer(3) is not a valid value for parameter c",
+        ),
+        (
+            "type s = lane_stream<2, 9>;",
+            "Value Integer(9) is not a valid value for parameter c",
+        ),
+    ] {
+        assert_eval_error(
+            namespace_src("generics::lanes", [lane_stream, decl]),
+            EVAL_ERROR,
+            message,
+        )?;
+    }
+    // Throughput parameters can not be used for other properties
+    assert_eval_error(
+        namespace_src(
+            "generics::lanes",
+            [lane_stream, "type s<n: positive = 1> = lane_stream<n>;"],
+        ),
+        EVAL_ERROR,
+        "Expected a parameter of type Interface(Throughput), this is a parameter with type Behavioral(Integer(Positive))",
+    )?;
+    Ok(())
+}
+
+#[test]
+fn constants() -> Result<()> {
+    let db = parse_to_db("tests/til_files/constants.til", "constants")?;
+    let output = generate(&db, &VhdlConfig::default())?;
+    let top = output
+        .streamlet(&PathName::try_from("constants::streams::top")?)
+        .unwrap();
+    assert!(top.contains("a_data : in std_logic_vector(31 downto 0);"));
+    assert!(top.contains("c_data : in std_logic_vector(8 downto 0);"));
+    assert!(top.contains("generic map(\n    w => 32\n  )"));
+    assert!(!output.files()["constants_pkg.vhd"].contains("constant "));
+
+    let output = generate(&db, &VhdlConfig::default().with_export_constants(true))?;
+    let package = &output.files()["constants_pkg.vhd"];
+    assert!(package.contains("constant constants_0_sizes_0_byte : natural := 8;"));
+    assert!(package.contains("constant constants_0_sizes_0_word : positive := 32;"));
+
+    for (decls, message) in [
+        // Values must satisfy the constant's type
+        (
+            "const width: positive = 4 - 4;",
+            "Value of constant constants__width (0) is not valid for condition: (Positive, implicit: >= 1)",
+        ),
+        // Constants can not refer to constants declared after them, or to parameters
+        (
+            "const a: natural = b; const b: natural = 1;",
+            "No parameter or constant b exists.",
+        ),
+        (
+            "const a: dimensionality = 1;",
+            "Constant a must be an integer, natural or positive, not a Interface(Dimensionality)",
+        ),
+        (
+            "const a: natural = 1; const a: natural = 2;",
+            "Duplicate declaration for constant identity a",
+        ),
+        (
+            "type t = Bits(unknown);",
+            "No parameter or constant unknown exists.",
+        ),
+    ] {
+        assert_eval_error(namespace_src("constants", [decls]), EVAL_ERROR, message)?;
+    }
+    // Parameters take precedence over constants
+    let db = eval_namespace(
+        "constants",
+        ["const w: natural = 8; type t<w: positive = 4> = Bits(w); type u = t;"],
+    )?;
+    assert_eq!(
+        db.project()
+            .namespaces()
+            .values()
+            .next()
+            .unwrap()
+            .get(&db)
+            .get_type_no_assignments(&db, "u")?
+            .to_string(),
+        "Bits(4)"
+    );
+    Ok(())
+}
+
+#[test]
+fn type_aliases() -> Result<()> {
+    let db = parse_to_db("tests/til_files/type_aliases.til", "type_aliases")?;
+    let output = generate(&db, &VhdlConfig::default())?;
+    let top = output
+        .streamlet(&PathName::try_from("aliases::top")?)
+        .unwrap();
+    assert!(top.contains("a_data : in std_logic_vector(7 downto 0);"));
+    assert!(top.contains("a_last : in std_logic_vector(0 downto 0);"));
+    assert!(top.contains("c_data : in std_logic_vector(7 downto 0);"));
+    assert!(top.contains("c_last : in std_logic_vector(1 downto 0);"));
+    assert!(top.contains("e_data : in std_logic_vector(3 downto 0);"));
+    assert!(top.contains("e_last : in std_logic_vector(0 downto 0);"));
+    assert!(top.contains("g_data : in std_logic_vector(1 downto 0);"));
+    assert!(top.contains("g_last : in std_logic_vector(2 downto 0);"));
+
+    let wide = "type wide<d: dimensionality = 1, w: positive = 4> = Stream(
+        data: Bits(w),
+        dimensionality: d,
+        synchronicity: Sync,
+        complexity: 4,
+    );";
+    eval_namespace("aliases", [wide, "type t<w: positive> = wide<1, w>;"])?;
+    for (decl, message) in [
+        // Only parameters which are forwarded as-is can omit their default value
+        (
+            "type t<w: positive> = wide<1, w * 2>;",
+            "Parameter w has no default value, and is not forwarded to a parameter of the referenced type",
+        ),
+        (
+            "type t<w: positive> = Bits(w);",
+            "Parameter w has no default value, and is not forwarded to a parameter of the referenced type",
+        ),
+        // The inherited default value must satisfy the condition of the alias
+        (
+            "type t<w: positive; >= 8> = wide<1, w>;",
+            "Default value (Integer(4)) is not valid for condition: (Positive, implicit: >= 1) and >= 8",
+        ),
+        (
+            "type t<w: natural> = wide<1, w>;",
+            "Expected a parameter of type Positive, this is a parameter with type Natural",
+        ),
+        (
+            "type t<w: positive> = wide<1, x = w>;",
+            "Unable to determine the default value of parameter w: Invalid argument: No parameter with name x on type aliases__wide",
+        ),
+    ] {
+        assert_eval_error(namespace_src("aliases", [wide, decl]), EVAL_ERROR, message)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn enums() -> Result<()> {
+    let db = parse_to_db("tests/til_files/enums.til", "enums")?;
+    let output = generate(&db, &VhdlConfig::default())?;
+    let pass = output
+        .streamlet(&PathName::try_from("enums::pass")?)
+        .unwrap();
+    assert!(pass.contains("a_data : in std_logic_vector(9 downto 0);"));
+
+    let package = &output.files()["enums_pkg.vhd"];
+    assert!(
+        package.contains("constant enums_0_opcode_0_add : std_logic_vector(1 downto 0) := \"00\";")
+    );
+    assert!(
+        package.contains("constant enums_0_opcode_0_sub : std_logic_vector(1 downto 0) := \"01\";")
+    );
+    assert!(
+        package.contains("constant enums_0_opcode_0_mul : std_logic_vector(1 downto 0) := \"10\";")
+    );
+    assert!(
+        package.contains("constant enums_0_state_0_idle : std_logic_vector(0 downto 0) := \"0\";")
+    );
+    assert!(
+        package.contains("constant enums_0_state_0_busy : std_logic_vector(0 downto 0) := \"1\";")
+    );
+    assert!(!package.contains("enums_0_single"));
+    assert!(!package.contains("enums_0_instruction"));
+    Ok(())
+}
+
+#[test]
+fn documentation() -> Result<()> {
+    let db = parse_to_db("tests/til_files/documentation.til", "documentation")?;
+    let namespace = db.project().namespaces().values().next().unwrap().get(&db);
+    let pixel = &namespace.type_decls()[&Name::try_new("pixel")?];
+    assert_eq!(pixel.doc(), Some(&"A single pixel,\nin RGB.".to_string()));
+    let field_doc = |path: &str| -> Result<Option<String>> {
+        Ok(pixel.field_doc(&PathName::try_from(path)?).cloned())
+    };
+    assert_eq!(field_doc("r")?, Some("The red channel".to_string()));
+    assert_eq!(field_doc("g")?, Some(" The green channel ".to_string()));
+    assert_eq!(field_doc("b")?, None);
+    assert_eq!(
+        field_doc("alpha::opaque")?,
+        Some("Fully opaque".to_string())
+    );
+    assert_eq!(field_doc("alpha::level")?, None);
+    assert_eq!(
+        namespace.type_decls()[&Name::try_new("pixel_stream")?].doc(),
+        None
+    );
+
+    let pass = namespace.get_streamlet(&db, "pass")?;
+    assert_eq!(pass.doc(), Some(&"Passes pixels through".to_string()));
+    assert_eq!(
+        pass.try_get_port(&db, &Name::try_new("a")?)?.doc(),
+        Some(&"Incoming pixels".to_string())
+    );
+    assert_eq!(pass.try_get_port(&db, &Name::try_new("b")?)?.doc(), None);
+
+    let pixel_in = namespace.get_interface(&db, "pixel_in")?;
+    assert_eq!(pixel_in.doc(), Some(&"A pixel input".to_string()));
+    let channel_width = &namespace.constants()[&Name::try_new("channel_width")?];
+    assert_eq!(
+        channel_width.doc(),
+        Some(&"The width of a channel".to_string())
+    );
+
+    let output = generate(&db, &VhdlConfig::default().with_export_constants(true))?;
+    let package = &output.files()["documentation_pkg.vhd"];
+    assert!(package.contains(
+        "  -- The width of a channel\n  constant documentation_0_channel_width : positive := 8;"
+    ));
+    assert!(package.contains(
+        "  -- The mode of a blending unit\n  -- Keep the existing pixel\n  constant documentation_0_blend_mode_0_retain"
+    ));
+    assert!(package.contains("\n\n  constant documentation_0_blend_mode_0_replace"));
+    assert!(package.contains("  --  Mix both pixels \n  constant documentation_0_blend_mode_0_mix"));
+    assert!(package.contains(
+        "  -- Type documentation::pixel\n  -- A single pixel,\n  -- in RGB.\n  -- Field r: The red channel\n  -- Field g:  The green channel \n  -- Field alpha::opaque: Fully opaque\n"
+    ));
+    assert!(!package.contains("documentation::pixel_stream"));
+    Ok(())
+}
+
+#[test]
+fn arrays() -> Result<()> {
+    let db = parse_to_db("tests/til_files/arrays.til", "arrays")?;
+    let output = generate(&db, &VhdlConfig::default())?;
+    let pass = output
+        .streamlet(&PathName::try_from("arrays::pass")?)
+        .unwrap();
+    // 16 pixels of 24 bits each
+    assert!(pass.contains("a_data : in std_logic_vector(383 downto 0);"));
+    assert!(pass.contains("c_0_item0_data : in std_logic_vector(7 downto 0);"));
+    assert!(pass.contains("c_0_item1_data : in std_logic_vector(7 downto 0);"));
+    assert!(!pass.contains("c_0_item2"));
+
+    // An Array with a parameterized length results in an entity per length
+    let lanes = output
+        .streamlet(&PathName::try_from("arrays::lanes")?)
+        .unwrap();
+    assert!(lanes.contains("a_data : in std_logic_vector(47 downto 0);"));
+    assert!(!lanes.contains("generic ("));
+    let lanes_4 = output
+        .streamlet(&PathName::try_from("arrays::lanes::n_4")?)
+        .unwrap();
+    assert!(lanes_4.contains("a_data : in std_logic_vector(95 downto 0);"));
+    let top = output
+        .streamlet(&PathName::try_from("arrays::top")?)
+        .unwrap();
+    assert!(top.contains("inner: arrays_0_lanes_0_n_4_com port map("));
+
+    let lanes = "type t<n: positive = 2> = Array(Bits(8), n);";
+    eval_namespace("arrays", [lanes])?;
+    eval_namespace("arrays", ["type t<n: positive = 2> = Array(Bits(n), 2);"])?;
+    assert_eval_error(
+        namespace_src("arrays", ["type t = Array(Bits(8), 0);"]),
+        EVAL_ERROR,
+        "The length of an Array must be positive, not 0",
+    )?;
+    assert_eval_error(
+        namespace_src("arrays", [lanes, "type u = t<0>;"]),
+        EVAL_ERROR,
+        "Value Integer(0) is not a valid value for parameter n",
+    )?;
+    Ok(())
+}
+
+#[test]
+fn unchanged_output() -> Result<()> {
+    let db = parse_to_db("tests/til_files/test_nspace.til", "unchanged_output")?;
+    canonical(&db)?;
+    let report = canonical(&db)?;
+    assert!(report.added().is_empty());
+    assert!(report.changed().is_empty());
+    assert!(report.stale().is_empty());
+    assert!(!report.unchanged().is_empty());
+    Ok(())
+}
+
+#[test]
+fn generate_in_memory() -> Result<()> {
+    let db = parse_to_db("tests/til_files/test_nspace.til", "in_memory")?;
+    let output = generate(&db, &VhdlConfig::default())?;
+    assert!(!output.directory().exists());
+    assert_eq!(output.files().len(), db.all_streamlets().len() + 1);
+    assert!(output.files().contains_key("in_memory_pkg.vhd"));
+    assert!(output.templates().is_empty());
+
+    let link_comp = output
+        .streamlet(&PathName::try_from("my::test::space::link_comp")?)
+        .unwrap();
+    assert_eq!(
+        link_comp,
+        std::fs::read_to_string(
+            "tests/til_files/test_nspace_src/my_0_test_0_space_0_link_comp.vhd"
+        )?
+    );
+    Ok(())
+}
+
+#[test]
+fn backend_options() -> Result<()> {
+    let db = parse_to_db("tests/til_files/test_nspace.til", "backend_options")?;
+    let config = VhdlConfig::default()
+        .with_indent(IndentStyle::Tabs, 1)
+        .with_library("my_lib")?
+        .with_file_naming(FileNaming::Nested);
+    let output = generate(&db, &config)?;
+    assert!(output.files()["backend_options_pkg.vhd"].contains("\n\tcomponent "));
+    let comp1 = &output.files()["my/test/space/comp1.vhd"];
+    assert!(comp1.contains("library my_lib;\nuse my_lib.backend_options.all;"));
+    assert!(comp1.contains("\n\tport (\n"));
+
+    let config = config.with_exclude([PathName::try_from("my::test")?]);
+    let output = generate(&db, &config)?;
+    assert_eq!(
+        output.files().keys().collect::<Vec<_>>(),
+        vec!["backend_options_pkg.vhd"]
+    );
+    Ok(())
+}
+
+#[test]
+fn dependencies() -> Result<()> {
+    let db = from_path("tests/til_files/dependencies/app/project.toml")?;
+    let output = generate(&db, &VhdlConfig::default())?;
+
+    assert_eq!(output.dependencies().len(), 1);
+    let common = &output.dependencies()[0];
+    assert_eq!(
+        common.directory(),
+        output.directory().parent().unwrap().join("common")
+    );
+    assert!(common.files().contains_key("common_pkg.vhd"));
+    let pass = common
+        .streamlet(&PathName::try_from("streams::pass")?)
+        .unwrap();
+    assert!(pass.contains("library common;\nuse common.common.all;"));
+
+    let top = output.streamlet(&PathName::try_from("app::top")?).unwrap();
+    assert!(top.contains("library common;\nuse common.common.all;"));
+    assert!(top.contains("library work;\nuse work.app.all;"));
+    assert!(top.contains("first: streams_0_pass_com port map("));
+    assert!(!output.files()["app_pkg.vhd"].contains("streams_0_pass_com"));
+    Ok(())
+}
+
+#[test]
+fn generate_templates() -> Result<()> {
+    let link_root = std::env::temp_dir().join("til_vhdl_template_test");
+    std::fs::create_dir_all(link_root.join("src"))?;
+    let db = til_parser::query::into_query_storage(
+        r#"namespace templates {
+    type stream = Stream(data: Bits(8), dimensionality: 0, synchronicity: Sync, complexity: 4);
+    streamlet comp = (a: in stream) { impl: "src" };
+}"#,
+        Project::new("templates", ".", Some("../../test_output/"))?,
+        link_root.clone(),
+    )?;
+
+    let template = link_root.join("src/templates_0_comp.vhd");
+    let output = generate(&db, &VhdlConfig::default())?;
+    assert!(output.templates().contains_key(&template));
+    assert!(!template.exists());
+
+    let comp = PathName::try_from("templates::comp")?;
+    let with_missing_link =
+        |behaviour| generate(&db, &VhdlConfig::new().with_missing_link(behaviour));
+    assert_eq!(
+        with_missing_link(MissingLinkBehaviour::Error).err(),
+        Some(Error::BackEndError(format!(
+            "The linked implementation of streamlet templates::comp does not exist, expected a file at {}",
+            template.display()
+        )))
+    );
+    let skipped = with_missing_link(MissingLinkBehaviour::WarnAndSkip)?;
+    assert!(skipped.streamlet(&comp).is_none());
+    assert!(skipped.templates().is_empty());
+    let in_output = with_missing_link(MissingLinkBehaviour::CreateInOutputDir)?;
+    assert_eq!(in_output.streamlet(&comp), output.streamlet(&comp));
+    assert!(in_output.templates().is_empty());
+
+    output.write()?;
+    assert!(template.exists());
+    assert!(generate(&db, &VhdlConfig::default())?
+        .templates()
+        .is_empty());
+
+    std::fs::remove_dir_all(&link_root)?;
+    Ok(())
+}
+
 #[test]
 fn playground() -> Result<()> {
     let mut _db = Database::default();
@@ -5,7 +5,7 @@ use tydi_common::traits::{Document, Documents, Identify};
 use crate::declaration::DeclareWithIndent;
 use crate::traits::VhdlDocument;
 use crate::usings::DeclareUsings;
-use crate::{declaration::Declare, usings::ListUsings};
+use crate::usings::ListUsings;
 
 use super::*;
 
@@ -51,7 +51,7 @@ impl DeclareWithIndent for Architecture {
         let mut result = String::new();
         result.push_str(self.declare_usings()?.as_str());
 
-        result.push_str(self.entity.declare(db)?.as_str());
+        result.push_str(self.entity.declare_with_indent(db, indent_style)?.as_str());
         result.push_str("\n");
 
         if let Some(doc) = self.vhdl_doc() {
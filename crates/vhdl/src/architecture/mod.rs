@@ -106,7 +106,10 @@ impl Architecture {
     pub fn from_database(db: &dyn Arch, identifier: impl TryResult<VhdlName>) -> Result<Self> {
         let package = db.default_package();
         let mut usings = package.list_usings()?;
-        usings.add_using("work", format!("{}.all", package.identifier()))?;
+        usings.add_using(
+            package.library().clone(),
+            format!("{}.all", package.identifier()),
+        )?;
         Ok(Architecture {
             identifier: identifier.try_result()?,
             entity: Entity::from(db.subject_component()?.as_ref()),
@@ -1,7 +1,10 @@
 use tydi_common::{error::Result, traits::Identify};
 use tydi_intern::Id;
 
-use crate::{architecture::arch_storage::Arch, object::object_type::DeclarationTypeName};
+use crate::{
+    architecture::arch_storage::Arch, object::object_type::DeclarationTypeName,
+    traits::VhdlDocument,
+};
 
 use super::{ArchitectureDeclaration, DeclareWithIndent, ObjectDeclaration, ObjectKind};
 
@@ -29,21 +32,25 @@ impl DeclareWithIndent for ObjectDeclaration {
         } else {
             "".to_string()
         };
+        let doc = self.vhdl_doc().unwrap_or_default();
         Ok(match self.kind() {
             ObjectKind::Signal => format!(
-                "signal {} : {}{}",
+                "{}signal {} : {}{}",
+                doc,
                 self.identifier(),
                 self.object(db)?.typ(db).declaration_type_name(db)?,
                 default_string
             ),
             ObjectKind::Variable => format!(
-                "variable {} : {}{}",
+                "{}variable {} : {}{}",
+                doc,
                 self.identifier(),
                 self.object(db)?.typ(db).declaration_type_name(db)?,
                 default_string
             ),
             ObjectKind::Constant => format!(
-                "constant {} : {}{}",
+                "{}constant {} : {}{}",
+                doc,
                 self.identifier(),
                 self.object(db)?.typ(db).declaration_type_name(db)?,
                 default_string
@@ -85,6 +92,9 @@ impl DeclareWithIndent for Id<ObjectDeclaration> {
 
 #[cfg(test)]
 mod tests {
+    use tydi_common::traits::Documents;
+
+    use crate::architecture::arch_storage::interner::GetSelf;
     use crate::declaration::Declare;
     use crate::object::object_type::ObjectType;
     use crate::{architecture::arch_storage::db::Database, assignment::StdLogicValue};
@@ -120,6 +130,14 @@ mod tests {
             "alias TestAlias : std_logic is TestSignal",
             ObjectDeclaration::alias(db, "TestAlias", test_signal, vec![])?.declare(db)?
         );
+        let documented =
+            ObjectDeclaration::constant(db, "Documented", ObjectType::Bit, StdLogicValue::U)?
+                .get(db)
+                .with_doc("A documented\nconstant");
+        assert_eq!(
+            "-- A documented\n-- constant\nconstant Documented : std_logic := 'U'",
+            documented.declare(db)?
+        );
         Ok(())
     }
 }
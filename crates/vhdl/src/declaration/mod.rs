@@ -1,7 +1,7 @@
 use core::fmt;
 
 use tydi_common::error::{Error, Result, TryResult};
-use tydi_common::traits::Identify;
+use tydi_common::traits::{Document, Documents, Identify};
 use tydi_intern::Id;
 
 use crate::architecture::arch_storage::interner::InternSelf;
@@ -184,6 +184,8 @@ pub struct ObjectDeclaration {
     default: Option<AssignmentKind>,
     /// The kind of object
     kind: ObjectKind,
+    /// Documentation, declared as a comment preceding the object.
+    doc: Option<String>,
 }
 
 impl ObjectDeclaration {
@@ -199,6 +201,7 @@ impl ObjectDeclaration {
             obj: Object::try_new(db, typ, &kind)?,
             default,
             kind,
+            doc: None,
         }
         .test_default(db)
     }
@@ -215,6 +218,7 @@ impl ObjectDeclaration {
             obj: Object::try_new(db, typ, &kind)?,
             default,
             kind,
+            doc: None,
         }
         .test_default(db)
     }
@@ -231,6 +235,7 @@ impl ObjectDeclaration {
             obj: Object::try_new(db, typ, &kind)?,
             default: Some(value.into()),
             kind,
+            doc: None,
         }
         .test_default(db)
     }
@@ -249,6 +254,7 @@ impl ObjectDeclaration {
             obj: Object::try_new(db, typ, &kind)?,
             default: None,
             kind,
+            doc: None,
         }
         .test_default(db)
     }
@@ -265,6 +271,7 @@ impl ObjectDeclaration {
             obj: Object::try_new(db, typ, &kind)?,
             default: None,
             kind,
+            doc: None,
         }
         .test_default(db)
     }
@@ -288,6 +295,7 @@ impl ObjectDeclaration {
                 object_declaration.identifier(),
                 Box::new(object_declaration.kind().clone()),
             ),
+            doc: None,
         }
         .test_default(db)
     }
@@ -333,6 +341,7 @@ impl ObjectDeclaration {
             obj: Object::new(db, param.typ().clone().intern(db), (&kind).into()),
             default: param.default().clone(),
             kind,
+            doc: None,
         }
         .test_default(db)
     }
@@ -369,6 +378,18 @@ impl ObjectDeclaration {
     }
 }
 
+impl Document for ObjectDeclaration {
+    fn doc(&self) -> Option<&String> {
+        self.doc.as_ref()
+    }
+}
+
+impl Documents for ObjectDeclaration {
+    fn set_doc(&mut self, doc: impl Into<String>) {
+        self.doc = Some(doc.into());
+    }
+}
+
 impl Identify for ObjectDeclaration {
     fn identifier(&self) -> String {
         self.identifier.to_string()
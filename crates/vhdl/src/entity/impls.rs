@@ -28,7 +28,7 @@ impl DeclareWithIndent for Entity {
             let parameters = self
                 .parameters()
                 .iter()
-                .map(|(_, x)| x.declare(db))
+                .map(|(_, x)| x.declare_with_indent(db, indent_style))
                 .collect::<Result<Vec<String>>>()?
                 .join(";\n");
             parameter_body.push_str(&indent(&parameters, indent_style));
@@ -8,13 +8,14 @@ use tydi_common::{
     error::{Error, Result, TryResult},
     traits::Identify,
 };
+use tydi_intern::Id;
 
 use crate::object::object_type::ObjectType;
 use crate::{
     architecture::arch_storage::Arch,
     common::vhdl_name::{VhdlName, VhdlNameSelf},
     component::Component,
-    declaration::{Declare, DeclareWithIndent},
+    declaration::{DeclareWithIndent, ObjectDeclaration, ObjectKind},
     object::object_type::DeclarationTypeName,
     properties::Analyze,
     usings::{DeclareUsings, ListUsings, Usings},
@@ -30,6 +31,16 @@ pub struct Package {
     components: IndexMap<VhdlName, Arc<Component>>,
     /// The types declared within the library.
     types: Vec<ObjectType>,
+    /// The constants declared within the library.
+    constants: Vec<Id<ObjectDeclaration>>,
+    /// Comments declared at the start of the library, e.g. to document
+    /// declarations which have no VHDL equivalent.
+    comments: Vec<String>,
+    /// The library the package is compiled into, "work" by default.
+    library: VhdlName,
+    /// Packages in other libraries which this package (and architectures
+    /// based on it) use.
+    imports: Usings,
 }
 
 impl Package {
@@ -38,6 +49,10 @@ impl Package {
             identifier: identifier.try_result()?,
             components: IndexMap::new(),
             types: vec![],
+            constants: vec![],
+            comments: vec![],
+            library: VhdlName::try_new("work")?,
+            imports: Usings::new_empty(),
         })
     }
 
@@ -61,6 +76,10 @@ impl Package {
                 .into_iter()
                 .unique_by(|x| x.declaration_type_name(db))
                 .collect(),
+            constants: vec![],
+            comments: vec![],
+            library: VhdlName::try_new("work")?,
+            imports: Usings::new_empty(),
         })
     }
 
@@ -70,6 +89,10 @@ impl Package {
             identifier: "default".try_into().unwrap(),
             components: IndexMap::new(),
             types: vec![],
+            constants: vec![],
+            comments: vec![],
+            library: "work".try_into().unwrap(),
+            imports: Usings::new_empty(),
         }
     }
 
@@ -104,6 +127,27 @@ impl Package {
         self.types.push(typ);
     }
 
+    /// Add a constant declaration to the package, the declaration must be of
+    /// kind Constant.
+    pub fn add_constant(&mut self, db: &dyn Arch, constant: Id<ObjectDeclaration>) -> Result<()> {
+        match db.lookup_intern_object_declaration(constant).kind() {
+            ObjectKind::Constant => {
+                self.constants.push(constant);
+                Ok(())
+            }
+            kind => Err(Error::InvalidArgument(format!(
+                "Packages can only declare constants, this is a {}",
+                kind
+            ))),
+        }
+    }
+
+    /// Add a comment to the package, which is declared before its
+    /// constants. Every line of the comment is prefixed with `--`.
+    pub fn add_comment(&mut self, comment: impl Into<String>) {
+        self.comments.push(comment.into());
+    }
+
     pub fn components(&self) -> &IndexMap<VhdlName, Arc<Component>> {
         &self.components
     }
@@ -111,6 +155,36 @@ impl Package {
     pub fn types(&self) -> &Vec<ObjectType> {
         &self.types
     }
+
+    pub fn constants(&self) -> &Vec<Id<ObjectDeclaration>> {
+        &self.constants
+    }
+
+    pub fn comments(&self) -> &Vec<String> {
+        &self.comments
+    }
+
+    /// The library the package is compiled into.
+    pub fn library(&self) -> &VhdlName {
+        &self.library
+    }
+
+    /// Set the library the package is compiled into.
+    pub fn set_library(&mut self, library: impl TryResult<VhdlName>) -> Result<()> {
+        self.library = library.try_result()?;
+        Ok(())
+    }
+
+    /// Use all declarations of a package compiled into another library.
+    pub fn add_import(
+        &mut self,
+        library: impl TryResult<VhdlName>,
+        package: impl TryResult<VhdlName>,
+    ) -> Result<()> {
+        self.imports
+            .add_using(library, format!("{}.all", package.try_result()?))?;
+        Ok(())
+    }
 }
 
 impl DeclareWithIndent for Package {
@@ -120,11 +194,20 @@ impl DeclareWithIndent for Package {
         result.push_str(format!("package {} is\n\n", self.identifier).as_str());
 
         let mut body = String::new();
+        for comment in self.comments() {
+            for line in comment.split_terminator('\n') {
+                body.push_str(format!("-- {}\n", line).as_str());
+            }
+            body.push('\n');
+        }
+        for c in self.constants() {
+            body.push_str(format!("{};\n\n", c.declare_with_indent(db, indent_style)?).as_str());
+        }
         for t in self.types() {
             body.push_str(format!("{}\n\n", t.declare_with_indent(db, indent_style)?).as_str());
         }
         for (_, c) in &self.components {
-            body.push_str(format!("{}\n\n", c.declare(db)?).as_str());
+            body.push_str(format!("{}\n\n", c.declare_with_indent(db, indent_style)?).as_str());
         }
         result.push_str(&indent(&body, indent_style));
         result.push_str(format!("end {};", self.identifier).as_str());
@@ -160,6 +243,7 @@ impl ListUsings for Package {
         if types.any(|x| uses_std_logic(&x)) {
             usings.add_using(VhdlName::try_new("ieee")?, "std_logic_1164.all".to_string())?;
         }
+        usings.combine(&self.imports);
 
         Ok(usings)
     }
@@ -0,0 +1,249 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    Args, Parser, Subcommand,
+};
+use log::LevelFilter;
+use til_parser::report::ReportFormat;
+use til_vhdl::config::MissingLinkBehaviour;
+
+/// Command line interface for the Tydi Intermediate representation Language (TIL) toolchain.
+#[derive(Parser, Debug)]
+#[command(name = "til", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+
+    /// Increase logging verbosity (-v for info, -vv for debug, -vvv for trace)
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Only log errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+}
+
+impl Cli {
+    /// The log level selected by the verbosity flags.
+    pub fn log_level(&self) -> LevelFilter {
+        if self.quiet {
+            return LevelFilter::Error;
+        }
+        match self.verbose {
+            0 => LevelFilter::Warn,
+            1 => LevelFilter::Info,
+            2 => LevelFilter::Debug,
+            _ => LevelFilter::Trace,
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Parse the input and emit VHDL
+    Build(BuildArgs),
+    /// Parse and validate the input, without emitting anything
+    Check(InputArgs),
+    /// Print the namespaces, constants, types and streamlets declared by the input
+    List(InputArgs),
+    /// Remove previously generated output
+    Clean(OutputArgs),
+    /// Format the input file, or the files of a project, into the canonical layout
+    Fmt(FmtArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct InputArgs {
+    /// A single TIL file (*.til) or a project file (*.toml)
+    pub input: PathBuf,
+
+    /// Define a variable used in the paths of the project file (as `${KEY}`),
+    /// overrides environment variables of the same name
+    #[arg(short = 'D', long = "define", value_name = "KEY=VALUE", value_parser = parse_define)]
+    pub defines: Vec<(String, String)>,
+
+    /// How to print diagnostics: as reports with a snippet of the source, as a single line each, or as a JSON array per file
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value = "human",
+        value_parser = PossibleValuesParser::new(ReportFormat::VARIANTS)
+            .map(|format| format.parse::<ReportFormat>().unwrap()),
+    )]
+    pub message_format: ReportFormat,
+}
+
+impl InputArgs {
+    /// The variables defined on the command line, later definitions override
+    /// earlier ones.
+    pub fn defines(&self) -> BTreeMap<String, String> {
+        self.defines.iter().cloned().collect()
+    }
+}
+
+fn parse_define(define: &str) -> Result<(String, String), String> {
+    match define.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got \"{}\"", define)),
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct OutputArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Output directory, overrides the output path of a project file.
+    ///
+    /// Defaults to `./output` for single TIL files.
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct BuildArgs {
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    /// Keep running, and rebuild the VHDL of changed streamlets whenever the input files change
+    #[arg(short, long, conflicts_with_all = ["dry_run", "print"])]
+    pub watch: bool,
+
+    /// List the files that would be written (and templates that would be created), without writing anything
+    #[arg(long, conflicts_with = "print")]
+    pub dry_run: bool,
+
+    /// Print the entity and architecture of a single streamlet (e.g. `my::space::comp`) to stdout, without writing anything
+    #[arg(long, value_name = "STREAMLET")]
+    pub print: Option<String>,
+
+    /// Remove previously generated files which are no longer part of the output
+    #[arg(long)]
+    pub remove_stale: bool,
+
+    /// What to do when the file of a linked implementation does not exist, overrides the project file
+    #[arg(
+        long,
+        value_name = "MODE",
+        value_parser = PossibleValuesParser::new(MissingLinkBehaviour::VARIANTS)
+            .map(|mode| mode.parse::<MissingLinkBehaviour>().unwrap()),
+    )]
+    pub missing_link: Option<MissingLinkBehaviour>,
+}
+
+#[derive(Args, Debug)]
+pub struct FmtArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Check whether the files are formatted without changing them, failing if any of them are not
+    #[arg(long)]
+    pub check: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn verbosity() {
+        let cli = Cli::parse_from(["til", "check", "test.til"]);
+        assert_eq!(cli.log_level(), LevelFilter::Warn);
+        let cli = Cli::parse_from(["til", "-vv", "check", "test.til"]);
+        assert_eq!(cli.log_level(), LevelFilter::Debug);
+        let cli = Cli::parse_from(["til", "build", "test.til", "-q"]);
+        assert_eq!(cli.log_level(), LevelFilter::Error);
+        assert!(Cli::try_parse_from(["til", "-v", "-q", "list", "test.til"]).is_err());
+    }
+
+    #[test]
+    fn fmt_check() {
+        match Cli::parse_from(["til", "fmt", "project.toml", "--check"]).command {
+            Command::Fmt(args) => assert!(args.check),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn build_modes() {
+        assert!(Cli::try_parse_from(["til", "build", "test.til", "--watch", "--dry-run"]).is_err());
+        assert!(Cli::try_parse_from(["til", "build", "test.til", "--print", "a::b"]).is_ok());
+        assert!(
+            Cli::try_parse_from(["til", "build", "test.til", "--missing-link", "ask"]).is_err()
+        );
+        match Cli::parse_from([
+            "til",
+            "build",
+            "test.til",
+            "--missing-link",
+            "warn-and-skip",
+        ])
+        .command
+        {
+            Command::Build(args) => {
+                assert_eq!(args.missing_link, Some(MissingLinkBehaviour::WarnAndSkip))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn message_format() {
+        match Cli::parse_from(["til", "check", "test.til"]).command {
+            Command::Check(args) => assert_eq!(args.message_format, ReportFormat::Human),
+            _ => unreachable!(),
+        }
+        match Cli::parse_from(["til", "build", "test.til", "--message-format", "json"]).command {
+            Command::Build(args) => {
+                assert_eq!(args.output.input.message_format, ReportFormat::Json)
+            }
+            _ => unreachable!(),
+        }
+        assert!(
+            Cli::try_parse_from(["til", "check", "test.til", "--message-format", "xml"]).is_err()
+        );
+        assert!(Cli::try_parse_from([
+            "til",
+            "build",
+            "test.til",
+            "--watch",
+            "--message-format",
+            "short"
+        ])
+        .is_ok());
+    }
+
+    #[test]
+    fn defines() {
+        match Cli::parse_from([
+            "til",
+            "check",
+            "project.toml",
+            "-D",
+            "LIBS=/opt/libs",
+            "--define",
+            "OUT=a=b",
+            "-D",
+            "LIBS=libs",
+        ])
+        .command
+        {
+            Command::Check(args) => {
+                let defines = args.defines();
+                assert_eq!(defines["LIBS"], "libs");
+                assert_eq!(defines["OUT"], "a=b");
+            }
+            _ => unreachable!(),
+        }
+        assert!(Cli::try_parse_from(["til", "check", "project.toml", "-D", "LIBS"]).is_err());
+        assert!(Cli::try_parse_from(["til", "check", "project.toml", "-D", "=libs"]).is_err());
+    }
+}
@@ -0,0 +1,444 @@
+use std::{
+    collections::BTreeSet,
+    ffi::OsStr,
+    iter,
+    path::{Path, PathBuf},
+};
+
+use log::{debug, info, warn};
+use til_parser::{
+    format::format_source,
+    lint::report_lints,
+    project::{read_project_file_with_defines, BackendKeys, ProjectFile},
+    query::{files_to_project, SourceFile},
+    report::print_reports_as,
+};
+use til_query::ir::{db::Database, project::Project, traits::GetSelf, Ir};
+use til_vhdl::{
+    config::{IndentStyle, VhdlConfig},
+    output::OutputReport,
+};
+use tydi_common::{
+    error::{Error, Result},
+    name::{PathName, PathNameSelf},
+};
+
+use crate::{
+    cli::{BuildArgs, Command, FmtArgs, InputArgs, OutputArgs},
+    watch,
+};
+
+/// Output directory used for single TIL files when none is specified.
+const DEFAULT_OUTPUT: &str = "output";
+
+pub fn run(command: &Command) -> Result<()> {
+    match command {
+        Command::Build(args) => build(args),
+        Command::Check(args) => check(args),
+        Command::List(args) => list(args),
+        Command::Clean(args) => clean(args),
+        Command::Fmt(args) => fmt(args),
+    }
+}
+
+/// Whether the input is a single TIL file or a project file.
+pub enum InputKind {
+    Til,
+    Project,
+}
+
+impl InputKind {
+    pub fn of(input: &InputArgs) -> Result<Self> {
+        match input.input.extension().and_then(OsStr::to_str) {
+            Some("til") => Ok(InputKind::Til),
+            Some("toml") => Ok(InputKind::Project),
+            _ => Err(Error::FileIOError(format!(
+                "Expected file ending in .toml or .til, got: {}",
+                input.input.display()
+            ))),
+        }
+    }
+}
+
+/// Parse either a single TIL file or a project file into a query database.
+fn load(input: &InputArgs) -> Result<Database> {
+    let file_path = &input.input;
+    match InputKind::of(input)? {
+        InputKind::Til => {
+            let input_file = std::fs::read_to_string(file_path).map_err(|err| {
+                Error::FileIOError(format!(
+                    "Unable to read file {}: {}",
+                    file_path.display(),
+                    err
+                ))
+            })?;
+            let mut db = Database::default();
+            db.set_project(Project::new("proj", ".", None::<&str>)?);
+            let file = SourceFile::new(input_file, ".")?.with_path(file_path);
+            let files = [file];
+            files_to_project(&files, &mut db, input.message_format)?
+                .pop()
+                .unwrap()?;
+            report_lints(&files, &db, input.message_format)?;
+            Ok(db)
+        }
+        InputKind::Project => til_parser::project::from_path_with_report_format(
+            file_path.clone(),
+            &input.defines(),
+            input.message_format,
+        ),
+    }
+}
+
+/// Parse the input, and apply the output directory override (if any).
+fn load_with_output(args: &OutputArgs) -> Result<Database> {
+    let mut db = load(&args.input)?;
+    let mut project = db.project();
+    if let Some(output) = output_override(args, &project)? {
+        project.set_output_path(Some(output))?;
+        db.set_project(project);
+    }
+    Ok(db)
+}
+
+/// The output directory to use instead of the project's own output path, if any.
+///
+/// Single TIL files have no output directory of their own, so they fall back
+/// on [`DEFAULT_OUTPUT`].
+pub fn output_override(args: &OutputArgs, project: &Project) -> Result<Option<PathBuf>> {
+    let output = match &args.output {
+        Some(output) => Some(output.clone()),
+        None if project.output_path().is_none() => Some(PathBuf::from(DEFAULT_OUTPUT)),
+        None => None,
+    };
+    // Overrides are relative to the working directory, not the project.
+    match output {
+        Some(output) => Ok(Some(std::env::current_dir()?.join(output))),
+        None => Ok(None),
+    }
+}
+
+fn build(args: &BuildArgs) -> Result<()> {
+    let config = vhdl_config(args)?;
+    if args.watch {
+        return watch::watch(args, &config);
+    }
+    let db = load_with_output(&args.output)?;
+    if let Some(streamlet) = &args.print {
+        return print_streamlet(&db, &config, streamlet);
+    }
+    if args.dry_run {
+        return dry_run(&db, &config);
+    }
+    let mut report = til_vhdl::canonical_with_config(&db, &config)?;
+    log_report(&mut report, args.remove_stale)
+}
+
+/// The backend configuration, based on the project file (if any), overridden
+/// by the command line arguments.
+pub fn vhdl_config(args: &BuildArgs) -> Result<VhdlConfig> {
+    let mut config = VhdlConfig::new();
+    if let InputKind::Project = InputKind::of(&args.output.input)? {
+        let project_file =
+            read_project_file_with_defines(&args.output.input.input, &args.output.input.defines())?;
+        config = project_config(&project_file)?;
+    }
+    if let Some(missing_link) = args.missing_link {
+        config = config.with_missing_link(missing_link);
+    }
+    Ok(config)
+}
+
+/// The backend configuration selected by the `[config]`, `[backend]` and
+/// `[emit]` tables of a project file.
+fn project_config(project_file: &ProjectFile) -> Result<VhdlConfig> {
+    let mut config = VhdlConfig::new();
+    if let Some(missing_link) = project_file.config_missing_link() {
+        config = config.with_missing_link(
+            missing_link
+                .parse()
+                .map_err(|err| Error::ProjectError(format!("Invalid [config] table: {}", err)))?,
+        );
+    }
+    config = backend_config(config, project_file.backend())
+        .map_err(|err| Error::ProjectError(format!("Invalid [backend] table: {}", err)))?
+        .with_include(project_file.emit_include()?)
+        .with_exclude(project_file.emit_exclude()?);
+    Ok(config)
+}
+
+/// Apply the options of the project file's `[backend]` table.
+fn backend_config(mut config: VhdlConfig, backend: &BackendKeys) -> Result<VhdlConfig> {
+    if backend.indent_style().is_some() || backend.indent_width().is_some() {
+        let style = match backend.indent_style() {
+            Some(style) => style.parse()?,
+            None => IndentStyle::default(),
+        };
+        let width = backend.indent_width().unwrap_or(match style {
+            IndentStyle::Spaces => 2,
+            IndentStyle::Tabs => 1,
+        });
+        config = config.with_indent(style, width);
+    }
+    if let Some(standard) = backend.vhdl_standard() {
+        config = config.with_standard(standard.parse()?);
+    }
+    if let Some(library) = backend.library() {
+        config = config.with_library(library)?;
+    }
+    if let Some(file_naming) = backend.file_naming() {
+        config = config.with_file_naming(file_naming.parse()?);
+    }
+    if let Some(export_constants) = backend.export_constants() {
+        config = config.with_export_constants(export_constants);
+    }
+    Ok(config)
+}
+
+/// Print the files that would be written, and whether they differ from the existing files.
+fn dry_run(db: &Database, config: &VhdlConfig) -> Result<()> {
+    let output = til_vhdl::generate(db, config)?;
+    for output in output.dependencies().iter().chain(iter::once(&output)) {
+        for (path, contents) in output.paths() {
+            let status = match std::fs::read_to_string(&path) {
+                Ok(existing) if existing == contents => "unchanged",
+                Ok(_) => "changed",
+                Err(_) => "added",
+            };
+            println!("{} ({})", path.display(), status);
+        }
+        for path in output.templates().keys() {
+            println!("{} (template)", path.display());
+        }
+    }
+    Ok(())
+}
+
+fn print_streamlet(db: &Database, config: &VhdlConfig, streamlet: &str) -> Result<()> {
+    let name = PathName::try_from(streamlet)?;
+    let output = til_vhdl::generate_streamlets(db, config, &BTreeSet::from([name.clone()]))?;
+    match output.streamlet(&name) {
+        Some(vhdl) => {
+            print!("{}", vhdl);
+            Ok(())
+        }
+        None => Err(Error::InvalidArgument(format!(
+            "No streamlet with name {} exists",
+            streamlet
+        ))),
+    }
+}
+
+/// Log which files were written, and deal with stale files.
+pub fn log_report(report: &mut OutputReport, remove_stale: bool) -> Result<()> {
+    for dependency in report.dependencies_mut() {
+        log_report(dependency, remove_stale)?;
+    }
+    for file in report.added() {
+        info!("Added {}", file.display());
+    }
+    for file in report.changed() {
+        info!("Updated {}", file.display());
+    }
+    for file in report.unchanged() {
+        debug!("Unchanged {}", file.display());
+    }
+    info!(
+        "{}: {} added, {} updated, {} unchanged",
+        report.directory().display(),
+        report.added().len(),
+        report.changed().len(),
+        report.unchanged().len()
+    );
+    if remove_stale {
+        for file in report.stale() {
+            info!("Removing stale {}", file.display());
+        }
+        report.remove_stale()?;
+    } else {
+        for file in report.stale() {
+            warn!(
+                "{} is stale, use --remove-stale to remove it",
+                file.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn check(args: &InputArgs) -> Result<()> {
+    let db = load(args)?;
+    info!(
+        "{}: {} namespace(s), {} streamlet(s), no errors",
+        args.input.display(),
+        db.project().namespaces().len(),
+        db.all_streamlets().len()
+    );
+    Ok(())
+}
+
+fn list(args: &InputArgs) -> Result<()> {
+    let db = load(args)?;
+    for (_, namespace_id) in db.project().namespaces() {
+        let namespace = namespace_id.get(&db);
+        println!("namespace {}", namespace.path_name().join("::"));
+        for (name, constant) in namespace.constants() {
+            println!("  const {} = {}", name, constant.value());
+        }
+        for name in namespace.type_decls().keys() {
+            println!("  type {}", name);
+        }
+        for name in namespace.streamlet_ids().keys() {
+            println!("  streamlet {}", name);
+        }
+    }
+    Ok(())
+}
+
+fn clean(args: &OutputArgs) -> Result<()> {
+    let db = load_with_output(args)?;
+    for dir in til_vhdl::output_directories(&db)? {
+        remove_dir(&dir)?;
+    }
+    Ok(())
+}
+
+/// Format a single TIL file, or the files of a project (but not those of its
+/// dependencies).
+fn fmt(args: &FmtArgs) -> Result<()> {
+    let input = &args.input;
+    let paths = match InputKind::of(input)? {
+        InputKind::Til => vec![input.input.clone()],
+        InputKind::Project => {
+            let project_file = read_project_file_with_defines(&input.input, &input.defines())?;
+            let location = input.input.parent().unwrap_or_else(|| Path::new(""));
+            project_file
+                .file_paths(location)?
+                .into_iter()
+                .map(|(path, _)| path)
+                .collect()
+        }
+    };
+
+    let mut unformatted = 0;
+    for path in paths {
+        let src = std::fs::read_to_string(&path).map_err(|err| {
+            Error::FileIOError(format!("Unable to read file {}: {}", path.display(), err))
+        })?;
+        let formatted = match format_source(&src) {
+            Ok(formatted) => formatted,
+            Err(diagnostics) => {
+                let diagnostics = diagnostics
+                    .into_iter()
+                    .map(|diagnostic| diagnostic.with_file(&path))
+                    .collect::<Vec<_>>();
+                print_reports_as(&src, &diagnostics, input.message_format)?;
+                return Err(Error::ParsingError(format!(
+                    "Unable to format {}, see report.",
+                    path.display()
+                )));
+            }
+        };
+        if formatted == src {
+            debug!("{} is formatted", path.display());
+        } else if args.check {
+            warn!("{} is not formatted", path.display());
+            unformatted += 1;
+        } else {
+            std::fs::write(&path, formatted).map_err(|err| {
+                Error::FileIOError(format!("Unable to write {}: {}", path.display(), err))
+            })?;
+            info!("Formatted {}", path.display());
+        }
+    }
+    if unformatted > 0 {
+        Err(Error::CLIError(format!(
+            "{} file(s) are not formatted, run `til fmt` to format them",
+            unformatted
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+fn remove_dir(dir: &Path) -> Result<()> {
+    if dir.is_dir() {
+        std::fs::remove_dir_all(dir).map_err(|err| {
+            Error::FileIOError(format!("Unable to remove {}: {}", dir.display(), err))
+        })?;
+        info!("Removed {}", dir.display());
+    } else {
+        info!("Nothing to clean, {} does not exist", dir.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use til_parser::project::parse_project_file;
+    use til_vhdl::config::{FileNaming, MissingLinkBehaviour, VhdlStandard};
+
+    use super::*;
+
+    #[test]
+    fn project_config_from_tables() -> Result<()> {
+        let project_file = parse_project_file(
+            r#"
+name = "proj"
+files = []
+output_path = "out"
+
+[config]
+missing_link = "error"
+
+[backend]
+indent_style = "tabs"
+vhdl_standard = "93"
+library = "my_lib"
+file_naming = "nested"
+
+[emit]
+exclude = [ "my::test" ]
+"#,
+        )?;
+        let config = project_config(&project_file)?;
+        assert_eq!(config.missing_link(), MissingLinkBehaviour::Error);
+        assert_eq!(config.indent(), "\t");
+        assert_eq!(config.standard(), VhdlStandard::Vhdl1993);
+        assert_eq!(config.library().to_string(), "my_lib");
+        assert_eq!(config.file_naming(), FileNaming::Nested);
+        assert!(!config.emits(&PathName::try_from("my::test::comp")?));
+
+        for (table, error) in [
+            (
+                "[backend]\nindent_style = \"tab\"",
+                "\"tab\" is not a valid indent style",
+            ),
+            (
+                "[backend]\nvhdl_standard = \"2019\"",
+                "\"2019\" is not a valid VHDL standard",
+            ),
+            (
+                "[backend]\nfile_naming = \"deep\"",
+                "\"deep\" is not a valid file naming scheme",
+            ),
+            (
+                "[config]\nmissing_link = \"ask\"",
+                "\"ask\" is not a valid behaviour",
+            ),
+        ] {
+            let project_file = parse_project_file(format!(
+                "name = \"proj\"\nfiles = []\noutput_path = \"out\"\n{}",
+                table
+            ))?;
+            let err = project_config(&project_file).err().unwrap();
+            assert!(
+                matches!(&err, Error::ProjectError(msg) if msg.starts_with("Invalid [")),
+                "{:?}",
+                err
+            );
+            assert!(err.to_string().contains(error), "{}", err);
+        }
+        Ok(())
+    }
+}
@@ -1,20 +1,50 @@
-use std::{env, ffi::OsStr, path::PathBuf};
-use tydi_common::error::{Error, Result};
+use std::process::ExitCode;
 
-fn main() -> Result<()> {
-    let args: Vec<String> = env::args().collect();
-    let file_path = PathBuf::from(&args[1]);
-    let db = match file_path.extension().and_then(OsStr::to_str) {
-        Some("til") => {
-            let input_file = std::fs::read_to_string(&file_path).unwrap();
-            til_parser::query::into_query_storage_default_with_output(input_file, &args[2])
+use clap::Parser;
+use log::{error, LevelFilter};
+use tydi_common::error::Error;
+
+use crate::cli::Cli;
+
+mod cli;
+mod commands;
+mod watch;
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    env_logger::Builder::new()
+        .filter_level(cli.log_level())
+        // Salsa traces every query at the info level, which drowns out everything else.
+        .filter_module("salsa", cli.log_level().min(LevelFilter::Warn))
+        .format_timestamp(None)
+        .init();
+
+    match commands::run(&cli.command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            error!("{}", err);
+            ExitCode::from(exit_code(&err))
         }
-        Some("toml") => til_parser::project::from_path(file_path),
-        _ => Err(Error::FileIOError(format!(
-            "Expected file ending in .toml or .til, got: {}",
-            &args[1]
-        ))),
-    }?;
-    til_vhdl::canonical(&db)?;
-    Ok(())
+    }
+}
+
+/// Map errors onto process exit codes, so scripts can distinguish between
+/// failure categories.
+///
+/// Exit code 2 is left to `clap`, which uses it for invalid command line usage.
+fn exit_code(err: &Error) -> u8 {
+    match err {
+        Error::UnknownError => 1,
+        Error::CLIError(_) => 3,
+        Error::InvalidArgument(_) => 4,
+        Error::UnexpectedDuplicate(_) => 5,
+        Error::FileIOError(_) => 6,
+        Error::ParsingError(_) | Error::ImplParsingError(_) => 7,
+        Error::InvalidTarget(_) => 8,
+        Error::BackEndError(_) => 9,
+        Error::InterfaceError(_) => 10,
+        Error::ProjectError(_) => 11,
+        Error::ComposerError(_) => 12,
+        Error::LibraryError(_) => 13,
+    }
 }
@@ -0,0 +1,209 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::Duration,
+};
+
+use log::{error, info};
+use notify::{event::ModifyKind, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use til_parser::incremental::IncrementalProject;
+use til_query::ir::{project::Project, Ir};
+use til_vhdl::config::VhdlConfig;
+use tydi_common::error::{Error, Result};
+
+use crate::{
+    cli::BuildArgs,
+    commands::{log_report, output_override, vhdl_config, InputKind},
+};
+
+/// How long to wait for more file system events before rebuilding, as editors
+/// tend to produce several events for a single save.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Build the input, then rebuild the VHDL of changed streamlets whenever any
+/// of the input files change. Runs until interrupted.
+///
+/// Changes to the project file, and files added to or removed from the
+/// directories of the project, cause the project file to be loaded again.
+pub fn watch(build_args: &BuildArgs, config: &VhdlConfig) -> Result<()> {
+    let args = &build_args.output;
+    let format = args.input.message_format;
+    let mut project = match InputKind::of(&args.input)? {
+        InputKind::Til => IncrementalProject::new(
+            Project::new("proj", ".", None::<&str>)?,
+            vec![(args.input.input.clone(), PathBuf::from("."))],
+        )
+        .with_report_format(format),
+        InputKind::Project => IncrementalProject::from_path_with_report_format(
+            args.input.input.clone(),
+            &args.input.defines(),
+            format,
+        )?,
+    };
+    if let Some(output) = output_override(args, &project.db().project())? {
+        project.set_output_path(output)?;
+    }
+    let mut config = config.clone();
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).map_err(watch_error)?;
+    let mut watched = Watched::default();
+    watched.update(&mut watcher, &project)?;
+
+    rebuild(&mut project, &config, build_args.remove_stale);
+    info!("Watching {} file(s) for changes", watched.files.len());
+
+    loop {
+        let event = rx
+            .recv()
+            .map_err(|err| Error::CLIError(err.to_string()))?
+            .map_err(watch_error)?;
+        let mut reload = watched.requires_reload(&event);
+        if !reload && !watched.affects_files(&event) {
+            continue;
+        }
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            if let Ok(event) = event {
+                reload |= watched.requires_reload(&event);
+            }
+        }
+        if reload {
+            info!("Reloading the project");
+            let reloaded = project
+                .reload()
+                .and_then(|()| vhdl_config(build_args))
+                .and_then(|reloaded| {
+                    watched.update(&mut watcher, &project)?;
+                    Ok(reloaded)
+                });
+            match reloaded {
+                Ok(reloaded) => config = reloaded,
+                Err(err) => {
+                    error!("{}", err);
+                    continue;
+                }
+            }
+        }
+        rebuild(&mut project, &config, build_args.remove_stale);
+    }
+}
+
+/// The files and directories being watched.
+#[derive(Default)]
+struct Watched {
+    project_file: Option<PathBuf>,
+    files: BTreeSet<PathBuf>,
+    /// The watched directories, and whether they are watched recursively.
+    dirs: BTreeMap<PathBuf, bool>,
+}
+
+impl Watched {
+    /// Watch the files and directories of `project`, and stop watching those
+    /// which are no longer part of it.
+    fn update(
+        &mut self,
+        watcher: &mut RecommendedWatcher,
+        project: &IncrementalProject,
+    ) -> Result<()> {
+        let project_file = project.project_file().map(canonical_path);
+        let files = project.files().map(canonical_path).collect::<BTreeSet<_>>();
+        // Watch the directories rather than the files themselves, as some
+        // editors save files by replacing them.
+        let mut dirs: BTreeMap<PathBuf, bool> = BTreeMap::new();
+        let file_dirs = files
+            .iter()
+            .chain(project_file.iter())
+            .filter_map(|file| file.parent())
+            .map(|dir| (dir.to_path_buf(), false));
+        let source_dirs = project
+            .source_dirs()
+            .iter()
+            .map(|(dir, recursive)| (canonical_path(dir), *recursive));
+        for (dir, recursive) in file_dirs.chain(source_dirs) {
+            *dirs.entry(dir).or_default() |= recursive;
+        }
+
+        for (dir, recursive) in self.dirs.iter() {
+            if dirs.get(dir) != Some(recursive) {
+                // Directories which were removed are no longer watched anyway
+                let _ = watcher.unwatch(dir);
+            }
+        }
+        for (dir, recursive) in dirs.iter() {
+            if self.dirs.get(dir) != Some(recursive) {
+                let mode = if *recursive {
+                    RecursiveMode::Recursive
+                } else {
+                    RecursiveMode::NonRecursive
+                };
+                watcher.watch(dir, mode).map_err(watch_error)?;
+            }
+        }
+
+        *self = Watched {
+            project_file,
+            files,
+            dirs,
+        };
+        Ok(())
+    }
+
+    /// Whether `event` affects any of the files of the project.
+    fn affects_files(&self, event: &Event) -> bool {
+        event
+            .paths
+            .iter()
+            .any(|path| self.files.contains(&canonical_path(path)))
+    }
+
+    /// Whether `event` requires the project file to be loaded again: the
+    /// project file changed, or a TIL file was added, removed or renamed.
+    fn requires_reload(&self, event: &Event) -> bool {
+        let adds_or_removes = matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(_))
+        );
+        event.paths.iter().any(|path| {
+            self.project_file.as_ref() == Some(&canonical_path(path))
+                || (adds_or_removes && path.extension() == Some(OsStr::new("til")))
+        })
+    }
+}
+
+/// Re-evaluate the project, and write the VHDL of the streamlets which changed.
+fn rebuild(project: &mut IncrementalProject, config: &VhdlConfig, remove_stale: bool) {
+    let changed = match project.update() {
+        Ok(changed) => changed,
+        Err(err) => {
+            error!("{}", err);
+            return;
+        }
+    };
+    if changed.is_empty() {
+        info!("No streamlets changed");
+        return;
+    }
+    let result = til_vhdl::canonical_streamlets(project.db(), config, &changed)
+        .and_then(|mut report| log_report(&mut report, remove_stale));
+    match result {
+        Ok(()) => {
+            for streamlet in changed.iter() {
+                info!("Rebuilt {}", streamlet.join("::"));
+            }
+        }
+        Err(err) => error!("{}", err),
+    }
+}
+
+/// Resolve paths, so paths from the project can be compared to those reported
+/// by the watcher. Falls back on the path itself if it no longer exists.
+fn canonical_path(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn watch_error(err: notify::Error) -> Error {
+    Error::CLIError(format!("Unable to watch input files: {}", err))
+}